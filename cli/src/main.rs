@@ -1,22 +1,88 @@
 use anyhow::anyhow;
 use ethabi::{
-	decode, encode,
-	param_type::{ParamType, Reader},
+	canonical_signature, checksum_address, decode, encode, explain_encoding,
+	param_type::{ParamType, Reader, Writer},
+	signature_hash,
 	token::{LenientTokenizer, StrictTokenizer, Token, Tokenizer},
-	Contract, Event, Function, Hash,
+	token_to_json, Address, Contract, Event, ExplainedWord, Function, Hash, Log, RawLog, StateMutability, WordRole,
+	I256,
 };
 use itertools::Itertools;
-use sha3::{Digest, Keccak256};
-use std::fs::File;
-use structopt::StructOpt;
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, convert::TryInto, fs::File, io::Read, path::Path};
+use structopt::{clap::AppSettings, StructOpt};
+
+mod repl;
 
 #[derive(StructOpt, Debug)]
 /// Ethereum ABI coder.
-enum Opt {
+struct Opt {
+	/// Print results as JSON, with the names and types of decoded params.
+	#[structopt(long, global = true)]
+	json: bool,
+	/// Etherscan API key, to download the verified ABI of the contract at `--address`.
+	#[structopt(long, global = true)]
+	etherscan_key: Option<String>,
+	/// Address of a verified contract on Etherscan, the ABI of which is downloaded in place of the JSON ABI file, or
+	/// in addition to the JSON ABI files of commands taking several.
+	#[structopt(long, global = true, requires = "etherscan-key")]
+	address: Option<Address>,
+	/// ID of the chain of the contract at `--address`, 1 for mainnet by default.
+	#[structopt(long, global = true, requires = "address")]
+	chain: Option<u64>,
+	#[structopt(subcommand)]
+	command: Command,
+}
+
+#[derive(StructOpt, Debug)]
+enum Command {
 	/// Encode ABI call.
 	Encode(Encode),
 	/// Decode ABI call result.
 	Decode(Decode),
+	/// Print the selectors of the functions and errors and the topics of the events of a JSON ABI file.
+	Selectors { abi_path: String },
+	/// Decode a function call with each JSON ABI file of a directory which has a function of its selector.
+	Identify {
+		abi_dir: String,
+		/// Hex encoded calldata, `-` to read it from stdin or `@<path>` to read it from a file.
+		data: String,
+		#[structopt(flatten)]
+		format: Format,
+	},
+	/// Print a JSON map of the selectors of the functions and errors and the topics of the events of JSON ABI files
+	/// to their signatures, for debuggers and tracing tools.
+	SelectorMap {
+		#[structopt(required_unless = "address")]
+		abi_paths: Vec<String>,
+		/// File to write the map to instead of printing it.
+		#[structopt(long)]
+		out: Option<String>,
+	},
+	/// Print the functions, events and errors added, removed or changed between two JSON ABI files.
+	Diff { old_abi_path: String, new_abi_path: String },
+	/// Convert a JSON ABI file into human-readable signatures, one per line, or such signatures into a JSON ABI.
+	Fmt { abi_path: String },
+	/// Generate the Rust bindings `ethabi-derive` generates of a JSON ABI file or human-readable signatures.
+	Codegen {
+		abi_path: String,
+		/// File to write the bindings to instead of printing them.
+		#[structopt(long)]
+		out: Option<String>,
+		/// Include a Markdown reference of the contract as `DOCS`.
+		#[structopt(long)]
+		docs: bool,
+		/// Add round trip tests of every function and event.
+		#[structopt(long)]
+		generate_tests: bool,
+	},
+	/// Interactively encode calls, decode data and compute selectors of the functions of JSON ABI files.
+	Repl {
+		#[structopt(required_unless = "address")]
+		abi_paths: Vec<String>,
+		#[structopt(flatten)]
+		format: Format,
+	},
 }
 
 #[derive(StructOpt, Debug)]
@@ -25,7 +91,33 @@ enum Encode {
 	Function {
 		abi_path: String,
 		function_name_or_signature: String,
-		#[structopt(short, number_of_values = 1)]
+		#[structopt(short, number_of_values = 1, allow_hyphen_values = true)]
+		params: Vec<String>,
+		/// Allow short representation of input params.
+		#[structopt(short, long)]
+		lenient: bool,
+		/// Print the data word by word with the param each word belongs to and what it holds.
+		#[structopt(long)]
+		explain: bool,
+	},
+	/// Specify function by human-readable signature, e.g. `transfer(address,uint256)`.
+	#[structopt(setting = AppSettings::AllowNegativeNumbers)]
+	Sig {
+		signature: String,
+		params: Vec<String>,
+		/// Allow short representation of input params.
+		#[structopt(short, long)]
+		lenient: bool,
+		/// Print the data word by word with the param each word belongs to and what it holds.
+		#[structopt(long)]
+		explain: bool,
+	},
+	/// Encode the creation input of a contract from JSON ABI file, its code followed by the constructor params.
+	#[structopt(setting = AppSettings::AllowNegativeNumbers)]
+	Constructor {
+		abi_path: String,
+		/// Hex encoded code, `-` to read it from stdin or `@<path>` to read it from a file.
+		code: String,
 		params: Vec<String>,
 		/// Allow short representation of input params.
 		#[structopt(short, long)]
@@ -41,18 +133,112 @@ enum Encode {
 		/// Allow short representation of input params (numbers are in decimal form).
 		#[structopt(short, long)]
 		lenient: bool,
+		/// Print the data word by word with the param each word belongs to and what it holds.
+		#[structopt(long)]
+		explain: bool,
+	},
+	/// Encode a call of a function per row of a CSV file.
+	Batch {
+		#[structopt(long = "abi", required_unless = "address", conflicts_with = "address")]
+		abi_path: Option<String>,
+		#[structopt(long = "function")]
+		function_name_or_signature: String,
+		/// CSV file with the input params of a call per row, optionally preceded by a
+		/// header of the param names, or `-` to read it from stdin. Fields containing commas must be quoted.
+		#[structopt(long = "csv")]
+		csv_path: String,
+		/// Allow short representation of input params.
+		#[structopt(short, long)]
+		lenient: bool,
+		/// Encode a single `multicall(bytes[])` of all the calls instead of a call per line.
+		#[structopt(long)]
+		multicall: bool,
 	},
 }
 
+/// How decoded values are printed, except as JSON, which has a format of its own.
+#[derive(StructOpt, Debug, Clone, Copy)]
+struct Format {
+	/// Print addresses with EIP-55 checksums.
+	#[structopt(long)]
+	checksum: bool,
+	/// Print integers in decimal rather than hex, signed integers with their sign.
+	#[structopt(long)]
+	decimal: bool,
+	/// Print addresses, bytes and hex integers with a `0x` prefix.
+	#[structopt(long)]
+	prefix: bool,
+}
+
 #[derive(StructOpt, Debug)]
 enum Decode {
 	/// Load function from JSON ABI file.
-	Function { abi_path: String, function_name_or_signature: String, data: String },
+	Function {
+		abi_path: String,
+		function_name_or_signature: String,
+		/// Hex encoded data, `-` to read it from stdin or `@<path>` to read it from a file.
+		data: String,
+		#[structopt(flatten)]
+		format: Format,
+		/// Decode each line of the data separately, e.g. of a file of calldata.
+		#[structopt(long)]
+		batch: bool,
+		/// Print the data word by word with the param each word belongs to and what it holds.
+		#[structopt(long)]
+		explain: bool,
+	},
+	/// Decode a function call from JSON ABI file, finding the function by its selector.
+	Call {
+		abi_path: String,
+		/// Hex encoded data, `-` to read it from stdin or `@<path>` to read it from a file.
+		data: String,
+		#[structopt(flatten)]
+		format: Format,
+		/// Decode each line of the data separately, e.g. of a file of calldata.
+		#[structopt(long)]
+		batch: bool,
+		/// Print the data word by word with the param each word belongs to and what it holds.
+		#[structopt(long)]
+		explain: bool,
+	},
+	/// Decode the constructor params of a contract creation input from JSON ABI file.
+	Constructor {
+		abi_path: String,
+		/// Hex encoded creation input, `-` to read it from stdin or `@<path>` to read it from a file.
+		data: String,
+		/// Hex encoded code the creation input starts with, `-` to read it from stdin or `@<path>` to read it
+		/// from a file.
+		#[structopt(long, required_unless = "code-len")]
+		code: Option<String>,
+		/// Length of the code the creation input starts with in bytes.
+		#[structopt(long, conflicts_with = "code")]
+		code_len: Option<usize>,
+		#[structopt(flatten)]
+		format: Format,
+	},
 	/// Specify types of input params inline.
 	Params {
 		#[structopt(short, name = "type", number_of_values = 1)]
 		types: Vec<String>,
+		/// Hex encoded data, `-` to read it from stdin or `@<path>` to read it from a file.
+		data: String,
+		#[structopt(flatten)]
+		format: Format,
+		/// Decode each line of the data separately, e.g. of a file of calldata.
+		#[structopt(long)]
+		batch: bool,
+		/// Print the data word by word with the param each word belongs to and what it holds.
+		#[structopt(long)]
+		explain: bool,
+	},
+	/// Decode packed encoded data, as of `abi.encodePacked`, of types specified inline.
+	Packed {
+		#[structopt(short, name = "type", number_of_values = 1)]
+		types: Vec<String>,
+		/// Hex encoded data, `-` to read it from stdin or `@<path>` to read it from a file.
 		data: String,
+		#[structopt(flatten)]
+		format: Format,
 	},
 	/// Decode event log.
 	Log {
@@ -60,12 +246,28 @@ enum Decode {
 		event_name_or_signature: String,
 		#[structopt(short = "l", name = "topic", number_of_values = 1)]
 		topics: Vec<String>,
+		/// Hex encoded data, `-` to read it from stdin or `@<path>` to read it from a file.
 		data: String,
+		#[structopt(flatten)]
+		format: Format,
+	},
+	/// Decode each log of a JSON file of logs or receipts, e.g. of `eth_getLogs`, which an event of the JSON ABI
+	/// files matches, printing a JSON object per line.
+	Logs {
+		/// JSON file of logs, receipts, arrays or JSON-RPC responses of them, or `-` to read it from stdin.
+		logs_path: String,
+		#[structopt(long = "abi", required_unless = "address", number_of_values = 1)]
+		abi_paths: Vec<String>,
+		#[structopt(flatten)]
+		format: Format,
 	},
 }
 
 fn main() -> anyhow::Result<()> {
-	println!("{}", execute(std::env::args())?);
+	let result = execute(std::env::args())?;
+	if !result.is_empty() {
+		println!("{}", result);
+	}
 
 	Ok(())
 }
@@ -75,37 +277,172 @@ where
 	I: IntoIterator,
 	I::Item: Into<std::ffi::OsString> + Clone,
 {
-	let opt = Opt::from_iter(args);
+	let Opt { json, etherscan_key, address, chain, command } = Opt::from_iter(insert_address_as_abi_path(args));
+	let download = etherscan_key.as_deref().zip(address).map(|(key, address)| (key, chain.unwrap_or(1), address));
 
-	match opt {
-		Opt::Encode(Encode::Function { abi_path, function_name_or_signature, params, lenient }) => {
-			encode_input(&abi_path, &function_name_or_signature, &params, lenient)
+	match command {
+		Command::Encode(Encode::Function { abi_path, function_name_or_signature, params, lenient, explain }) => {
+			encode_input(&abi_path, download, &function_name_or_signature, &params, lenient, json, explain)
+		}
+		Command::Encode(Encode::Sig { signature, params, lenient, explain }) => {
+			encode_sig(&signature, &params, lenient, json, explain)
+		}
+		Command::Encode(Encode::Constructor { abi_path, code, params, lenient }) => {
+			encode_constructor(&abi_path, download, &code, &params, lenient, json)
+		}
+		Command::Encode(Encode::Params { params, lenient, explain }) => encode_params(&params, lenient, json, explain),
+		Command::Encode(Encode::Batch { abi_path, function_name_or_signature, csv_path, lenient, multicall }) => {
+			encode_batch(
+				abi_path.as_deref().unwrap_or_default(),
+				download,
+				&function_name_or_signature,
+				&csv_path,
+				lenient,
+				multicall,
+				json,
+			)
+		}
+		Command::Decode(Decode::Function {
+			abi_path, function_name_or_signature, data, batch, explain: true, ..
+		}) => {
+			let function = load_function(&abi_path, download, &function_name_or_signature)?;
+			decode_each(&data, batch, json, |data| Ok(display_explained(&function.explain_output(data)?, json)))
+		}
+		Command::Decode(Decode::Call { abi_path, data, batch, explain: true, .. }) => {
+			explain_call(&abi_path, download, &data, json, batch)
+		}
+		Command::Decode(Decode::Params { types, data, batch, explain: true, .. }) => {
+			let types: Vec<ParamType> = types.iter().map(|s| Reader::read(s)).collect::<Result<_, _>>()?;
+			decode_each(&data, batch, json, |data| Ok(display_explained(&explain_encoding(&types, data)?, json)))
+		}
+		Command::Decode(Decode::Function { abi_path, function_name_or_signature, data, format, batch, .. }) => {
+			decode_call_output(&abi_path, download, &function_name_or_signature, &data, format, json, batch)
+		}
+		Command::Decode(Decode::Call { abi_path, data, format, batch, .. }) => {
+			decode_call(&abi_path, download, &data, format, json, batch)
+		}
+		Command::Decode(Decode::Constructor { abi_path, data, code, code_len, format }) => {
+			decode_constructor(&abi_path, download, &data, code.as_deref(), code_len, format, json)
+		}
+		Command::Decode(Decode::Params { types, data, format, batch, .. }) => {
+			decode_params(&types, &data, format, json, batch)
+		}
+		Command::Decode(Decode::Packed { types, data, format }) => decode_packed(&types, &data, format, json),
+		Command::Decode(Decode::Log { abi_path, event_name_or_signature, topics, data, format }) => {
+			decode_log(&abi_path, download, &event_name_or_signature, &topics, &data, format, json)
+		}
+		Command::Decode(Decode::Logs { logs_path, abi_paths, format }) => {
+			decode_logs(&logs_path, &abi_paths, download, format)
 		}
-		Opt::Encode(Encode::Params { params, lenient }) => encode_params(&params, lenient),
-		Opt::Decode(Decode::Function { abi_path, function_name_or_signature, data }) => {
-			decode_call_output(&abi_path, &function_name_or_signature, &data)
+		Command::Selectors { abi_path } => selectors(&abi_path, download, json),
+		Command::Identify { abi_dir, data, format } => identify(&abi_dir, &data, format, json),
+		Command::SelectorMap { abi_paths, out } => selector_map(&abi_paths, download, out.as_deref()),
+		Command::Diff { old_abi_path, new_abi_path } => diff(&old_abi_path, &new_abi_path, json),
+		Command::Fmt { abi_path } => fmt(&abi_path),
+		Command::Codegen { abi_path, out, docs, generate_tests } => {
+			codegen(&abi_path, out.as_deref(), docs, generate_tests)
 		}
-		Opt::Decode(Decode::Params { types, data }) => decode_params(&types, &data),
-		Opt::Decode(Decode::Log { abi_path, event_name_or_signature, topics, data }) => {
-			decode_log(&abi_path, &event_name_or_signature, &topics, &data)
+		Command::Repl { abi_paths, format } => {
+			let contracts = load_contracts(&abi_paths, download)?;
+			repl::Repl::new(&contracts, format, json)?.run()?;
+			Ok(String::new())
 		}
 	}
 }
 
-fn load_function(path: &str, name_or_signature: &str) -> anyhow::Result<Function> {
-	let file = File::open(path)?;
-	let contract = Contract::load(file)?;
+/// Commands the first positional argument of which is a JSON ABI file.
+const ABI_PATH_COMMANDS: [&[&str]; 7] = [
+	&["encode", "function"],
+	&["encode", "constructor"],
+	&["decode", "function"],
+	&["decode", "call"],
+	&["decode", "constructor"],
+	&["decode", "log"],
+	&["selectors"],
+];
+
+/// Given `--address`, inserts the address as the ABI path of the commands taking a single JSON ABI file, since the
+/// ABI downloaded for the address takes the place of the file.
+fn insert_address_as_abi_path<I>(args: I) -> Vec<std::ffi::OsString>
+where
+	I: IntoIterator,
+	I::Item: Into<std::ffi::OsString>,
+{
+	let mut args: Vec<std::ffi::OsString> = args.into_iter().map(Into::into).collect();
+	let address = args.iter().enumerate().find_map(|(i, arg)| match arg.to_str()? {
+		"--address" => args.get(i + 1).cloned(),
+		arg => arg.strip_prefix("--address=").map(Into::into),
+	});
+	let address = match address {
+		Some(address) => address,
+		None => return args,
+	};
+
+	// the names of the command, with their indices, follow the binary name, options and their values
+	let mut names = Vec::new();
+	let mut index = 1;
+	while let Some(arg) = args.get(index).and_then(|arg| arg.to_str()) {
+		match arg {
+			"--etherscan-key" | "--address" | "--chain" => index += 1,
+			_ if arg.starts_with('-') => {}
+			_ if names.len() < 2 => names.push((index, arg)),
+			_ => break,
+		}
+		index += 1;
+	}
+
+	let end = ABI_PATH_COMMANDS.iter().find_map(|command| {
+		let matches = names.len() >= command.len() && names.iter().zip(command.iter()).all(|((_, a), b)| a == b);
+		matches.then(|| names[command.len() - 1].0 + 1)
+	});
+	if let Some(end) = end {
+		args.insert(end, address);
+	}
+	args
+}
+
+/// Loads a JSON ABI file, or given the Etherscan API key, chain and address of `--address`, downloads the verified
+/// ABI of the contract at the address in its place.
+fn load_contract(path: &str, download: Option<(&str, u64, Address)>) -> anyhow::Result<Contract> {
+	match download {
+		#[cfg(feature = "online")]
+		Some((key, chain, address)) => {
+			Ok(ethabi::remote::fetch_abi_from(&ethabi::remote::AbiSource::etherscan(key), chain, address)?)
+		}
+		#[cfg(not(feature = "online"))]
+		Some(_) => Err(anyhow!("downloading ABIs from Etherscan requires the `online` feature")),
+		None => Ok(Contract::load(File::open(path)?)?),
+	}
+}
+
+/// Loads JSON ABI files, preceded by the verified ABI downloaded for `--address`.
+fn load_contracts(paths: &[String], download: Option<(&str, u64, Address)>) -> anyhow::Result<Vec<Contract>> {
+	let downloaded = download.map(|download| load_contract("", Some(download))).transpose()?;
+	downloaded.into_iter().map(Ok).chain(paths.iter().map(|path| load_contract(path, None))).collect()
+}
+
+fn load_function(
+	path: &str,
+	download: Option<(&str, u64, Address)>,
+	name_or_signature: &str,
+) -> anyhow::Result<Function> {
+	let contract = load_contract(path, download)?;
+	find_function(&contract, name_or_signature)
+}
+
+fn find_function(contract: &Contract, name_or_signature: &str) -> anyhow::Result<Function> {
 	let params_start = name_or_signature.find('(');
 
 	match params_start {
 		// It's a signature
 		Some(params_start) => {
-			let name = &name_or_signature[..params_start];
+			let name = name_or_signature[..params_start].trim();
+			let signature = canonical_signature(name_or_signature)?;
 
 			contract
 				.functions_by_name(name)?
 				.iter()
-				.find(|f| f.signature() == name_or_signature)
+				.find(|f| f.signature() == signature)
 				.cloned()
 				.ok_or_else(|| anyhow!("invalid function signature `{}`", name_or_signature))
 		}
@@ -125,16 +462,15 @@ fn load_function(path: &str, name_or_signature: &str) -> anyhow::Result<Function
 	}
 }
 
-fn load_event(path: &str, name_or_signature: &str) -> anyhow::Result<Event> {
-	let file = File::open(path)?;
-	let contract = Contract::load(file)?;
+fn load_event(path: &str, download: Option<(&str, u64, Address)>, name_or_signature: &str) -> anyhow::Result<Event> {
+	let contract = load_contract(path, download)?;
 	let params_start = name_or_signature.find('(');
 
 	match params_start {
 		// It's a signature.
 		Some(params_start) => {
-			let name = &name_or_signature[..params_start];
-			let signature = hash_signature(name_or_signature);
+			let name = name_or_signature[..params_start].trim();
+			let signature = signature_hash(name_or_signature)?;
 			contract
 				.events_by_name(name)?
 				.iter()
@@ -169,19 +505,70 @@ fn parse_tokens(params: &[(ParamType, &str)], lenient: bool) -> anyhow::Result<V
 		.map_err(From::from)
 }
 
-fn encode_input(path: &str, name_or_signature: &str, values: &[String], lenient: bool) -> anyhow::Result<String> {
-	let function = load_function(path, name_or_signature)?;
+fn encode_input(
+	path: &str,
+	download: Option<(&str, u64, Address)>,
+	name_or_signature: &str,
+	values: &[String],
+	lenient: bool,
+	json: bool,
+	explain: bool,
+) -> anyhow::Result<String> {
+	let function = load_function(path, download, name_or_signature)?;
+	encode_function_input(&function, values, lenient, json, explain)
+}
 
+fn encode_sig(signature: &str, values: &[String], lenient: bool, json: bool, explain: bool) -> anyhow::Result<String> {
+	let function = Function::from_signature(signature)?;
+	encode_function_input(&function, values, lenient, json, explain)
+}
+
+fn encode_function_input(
+	function: &Function,
+	values: &[String],
+	lenient: bool,
+	json: bool,
+	explain: bool,
+) -> anyhow::Result<String> {
 	let params: Vec<_> =
 		function.inputs.iter().map(|param| param.kind.clone()).zip(values.iter().map(|v| v as &str)).collect();
 
 	let tokens = parse_tokens(&params, lenient)?;
 	let result = function.encode_input(&tokens)?;
 
-	Ok(hex::encode(&result))
+	match explain {
+		true => display_explained_calldata(function, &result, json),
+		false => Ok(display_encoded(&result, json)),
+	}
+}
+
+/// Encodes the creation input of a contract, its code followed by the params of its constructor, if it has one.
+fn encode_constructor(
+	path: &str,
+	download: Option<(&str, u64, Address)>,
+	code: &str,
+	values: &[String],
+	lenient: bool,
+	json: bool,
+) -> anyhow::Result<String> {
+	let contract = load_contract(path, download)?;
+	let code = hex_input(&read_input(code)?)?;
+	let inputs = contract.constructor().map(|constructor| constructor.inputs.as_slice()).unwrap_or_default();
+	if values.len() != inputs.len() {
+		return Err(anyhow!("constructor has {} params, but {} were given", inputs.len(), values.len()));
+	}
+
+	let params: Vec<_> = inputs.iter().map(|param| param.kind.clone()).zip(values.iter().map(|v| v as &str)).collect();
+	let tokens = parse_tokens(&params, lenient)?;
+	let result = match contract.constructor() {
+		Some(constructor) => constructor.encode_input(code, &tokens)?,
+		None => code,
+	};
+
+	Ok(display_encoded(&result, json))
 }
 
-fn encode_params(params: &[String], lenient: bool) -> anyhow::Result<String> {
+fn encode_params(params: &[String], lenient: bool, json: bool, explain: bool) -> anyhow::Result<String> {
 	assert_eq!(params.len() % 2, 0);
 
 	let params = params
@@ -193,65 +580,918 @@ fn encode_params(params: &[String], lenient: bool) -> anyhow::Result<String> {
 	let tokens = parse_tokens(params.as_slice(), lenient)?;
 	let result = encode(&tokens);
 
-	Ok(hex::encode(&result))
+	match explain {
+		true => {
+			let types: Vec<_> = params.into_iter().map(|(kind, _)| kind).collect();
+			Ok(display_explained(&explain_encoding(&types, &result)?, json))
+		}
+		false => Ok(display_encoded(&result, json)),
+	}
+}
+
+fn encode_batch(
+	path: &str,
+	download: Option<(&str, u64, Address)>,
+	name_or_signature: &str,
+	csv_path: &str,
+	lenient: bool,
+	multicall: bool,
+	json: bool,
+) -> anyhow::Result<String> {
+	let function = load_function(path, download, name_or_signature)?;
+	let csv = match csv_path {
+		"-" => read_input(csv_path)?,
+		_ => std::fs::read_to_string(csv_path)?,
+	};
+	let names: Vec<&str> = function.inputs.iter().map(|param| param.name.as_str()).collect();
+
+	let mut calls = Vec::new();
+	let mut errors = Vec::new();
+	for (index, line) in csv.lines().enumerate() {
+		if line.trim().is_empty() {
+			continue;
+		}
+
+		let row = index + 1;
+		let result = split_csv_row(line).and_then(|values| {
+			if row == 1 && !names.is_empty() && values == names {
+				return Ok(None);
+			}
+			if values.len() != function.inputs.len() {
+				return Err(anyhow!("expected {} values, found {}", function.inputs.len(), values.len()));
+			}
+
+			let params: Vec<_> =
+				function.inputs.iter().map(|param| param.kind.clone()).zip(values.iter().map(String::as_str)).collect();
+			Ok(Some(function.encode_input(&parse_tokens(&params, lenient)?)?))
+		});
+
+		match result {
+			Ok(Some(call)) => calls.push(call),
+			Ok(None) => (),
+			Err(err) => errors.push(format!("row {}: {}", row, err)),
+		}
+	}
+
+	if !errors.is_empty() {
+		return Err(anyhow!("{} invalid rows\n{}", errors.len(), errors.join("\n")));
+	}
+
+	match multicall {
+		true => {
+			let calls = calls.into_iter().map(Token::Bytes).collect();
+			let result = ethabi::encode_function_call("multicall(bytes[])", &[Token::Array(calls)])?;
+			Ok(display_encoded(&result, json))
+		}
+		false if json => Ok(Value::Array(calls.iter().map(|call| hex_json(call)).collect()).to_string()),
+		false => Ok(calls.iter().map(hex::encode).join("\n")),
+	}
+}
+
+/// Splits a row of a CSV file into its trimmed fields, which may be quoted by `"`, with `""`
+/// as an escaped quote.
+fn split_csv_row(line: &str) -> anyhow::Result<Vec<String>> {
+	let mut fields = Vec::new();
+	let mut field = String::new();
+	let mut quoted = false;
+	let mut chars = line.chars().peekable();
+	while let Some(c) = chars.next() {
+		match (c, quoted) {
+			('"', true) if chars.peek() == Some(&'"') => {
+				chars.next();
+				field.push('"');
+			}
+			('"', _) => quoted = !quoted,
+			(',', false) => fields.push(std::mem::take(&mut field).trim().to_owned()),
+			(c, _) => field.push(c),
+		}
+	}
+
+	if quoted {
+		return Err(anyhow!("unterminated quoted field"));
+	}
+	fields.push(field.trim().to_owned());
+	Ok(fields)
 }
 
-fn decode_call_output(path: &str, name_or_signature: &str, data: &str) -> anyhow::Result<String> {
-	let function = load_function(path, name_or_signature)?;
-	let data: Vec<u8> = hex::decode(&data)?;
-	let tokens = function.decode_output(&data)?;
-	let types = function.outputs;
+fn decode_call_output(
+	path: &str,
+	download: Option<(&str, u64, Address)>,
+	name_or_signature: &str,
+	data: &str,
+	format: Format,
+	json: bool,
+	batch: bool,
+) -> anyhow::Result<String> {
+	let function = load_function(path, download, name_or_signature)?;
+
+	decode_each(data, batch, json, |data| {
+		let tokens = function.decode_output(data)?;
+		Ok(display_output(&function, &tokens, format, json))
+	})
+}
 
+/// Displays the decoded output of a function as its params.
+fn display_output(function: &Function, tokens: &[Token], format: Format, json: bool) -> String {
+	let types = &function.outputs;
 	assert_eq!(types.len(), tokens.len());
 
-	let result = types
+	if json {
+		let params =
+			types.iter().zip(tokens.iter()).map(|(ty, to)| param_json(Some(&ty.name), &ty.kind, to, format.checksum));
+		return Value::Array(params.collect()).to_string();
+	}
+
+	types
 		.iter()
 		.zip(tokens.iter())
-		.map(|(ty, to)| format!("{} {}", ty.kind, to))
+		.map(|(ty, to)| format!("{} {}", ty.kind, display(to, format)))
+		.collect::<Vec<String>>()
+		.join("\n")
+}
+
+fn decode_call(
+	path: &str,
+	download: Option<(&str, u64, Address)>,
+	data: &str,
+	format: Format,
+	json: bool,
+	batch: bool,
+) -> anyhow::Result<String> {
+	let contract = load_contract(path, download)?;
+
+	decode_each(data, batch, json, |data| match contract.decode_input(data) {
+		Ok((function, tokens)) => Ok(display_call(function, &tokens, format, json)),
+		#[cfg(feature = "online")]
+		Err(ethabi::Error::InvalidName(_)) => decode_call_online(data, format, json),
+		Err(err) => Err(err.into()),
+	})
+}
+
+/// Explains calldata word by word with the function of the ABI of its selector.
+fn explain_call(
+	path: &str,
+	download: Option<(&str, u64, Address)>,
+	data: &str,
+	json: bool,
+	batch: bool,
+) -> anyhow::Result<String> {
+	let contract = load_contract(path, download)?;
+
+	decode_each(data, batch, json, |data| {
+		let selector = data.get(..4).ok_or(ethabi::Error::InvalidData)?;
+		let function = contract.function_by_selector(selector.try_into()?)?;
+		display_explained_calldata(function, data, json)
+	})
+}
+
+/// Decodes a call of a function missing from the ABI with each signature of its selector in
+/// the 4byte.directory database whose function encodes the same calldata, as some will not.
+#[cfg(feature = "online")]
+fn decode_call_online(data: &[u8], format: Format, json: bool) -> anyhow::Result<String> {
+	let selector = hex::encode(&data[..4]);
+	let url = format!("https://www.4byte.directory/api/v1/signatures/?hex_signature=0x{}", selector);
+	let response: Value = serde_json::from_str(&ureq::get(&url).call()?.into_string()?)?;
+
+	let signatures =
+		response["results"].as_array().into_iter().flatten().filter_map(|result| result["text_signature"].as_str());
+	let calls = decode_call_candidates(data, signatures, format, json);
+
+	match (calls.is_empty(), json) {
+		(true, _) => Err(anyhow!("no function of selector 0x{} in the ABI or 4byte.directory", selector)),
+		(false, true) => Ok(format!("[{}]", calls.join(","))),
+		(false, false) => Ok(calls.join("\n\n")),
+	}
+}
+
+/// Decodes a call with the function of each signature which encodes back to the same calldata.
+#[cfg(feature = "online")]
+fn decode_call_candidates<'a>(
+	data: &[u8],
+	signatures: impl IntoIterator<Item = &'a str>,
+	format: Format,
+	json: bool,
+) -> Vec<String> {
+	signatures
+		.into_iter()
+		.filter_map(|signature| {
+			let function = Function::from_signature(signature).ok()?;
+			let tokens = function.decode_input(&data[4..]).ok()?;
+			let encoded = function.encode_input(&tokens).ok()?;
+			(encoded == data).then(|| display_call(&function, &tokens, format, json))
+		})
+		.collect()
+}
+
+/// Displays a decoded call as its function signature followed by its params.
+fn display_call(function: &Function, tokens: &[Token], format: Format, json: bool) -> String {
+	assert_eq!(function.inputs.len(), tokens.len());
+
+	if json {
+		return call_json(function, tokens, format.checksum).to_string();
+	}
+
+	let params = function
+		.inputs
+		.iter()
+		.zip(tokens.iter())
+		.map(|(param, to)| format!("{} {} {}", param.name, param.kind, display(to, format)));
+
+	std::iter::once(function.signature()).chain(params).collect::<Vec<String>>().join("\n")
+}
+
+/// Converts a decoded call into a JSON object of its function signature and params.
+fn call_json(function: &Function, tokens: &[Token], checksum: bool) -> Value {
+	let params = function
+		.inputs
+		.iter()
+		.zip(tokens.iter())
+		.map(|(param, to)| param_json(Some(&param.name), &param.kind, to, checksum));
+	json!({ "function": function.signature(), "params": params.collect::<Vec<_>>() })
+}
+
+/// Decodes a function call with each JSON ABI file of the directory which has a function of its selector, to
+/// find which contract an unlabeled address may be. Files which are not JSON ABIs are skipped.
+fn identify(dir: &str, data: &str, format: Format, json: bool) -> anyhow::Result<String> {
+	let data = hex_input(&read_input(data)?)?;
+	if data.len() < 4 {
+		return Err(anyhow!("calldata is shorter than a selector"));
+	}
+
+	let mut paths = std::fs::read_dir(dir)?.map(|entry| Ok(entry?.path())).collect::<anyhow::Result<Vec<_>>>()?;
+	paths.sort();
+
+	let mut matches = Vec::new();
+	for path in paths.iter().filter(|path| path.is_file()) {
+		let contract = match std::fs::read(path).ok().and_then(|source| Contract::load(source.as_slice()).ok()) {
+			Some(contract) => contract,
+			None => continue,
+		};
+		if let Ok((function, tokens)) = contract.decode_input(&data) {
+			matches.push((path.display().to_string(), function.clone(), tokens));
+		}
+	}
+
+	if matches.is_empty() {
+		return Err(anyhow!("no ABI in `{}` has a function of selector 0x{}", dir, hex::encode(&data[..4])));
+	}
+
+	if json {
+		let matches = matches.iter().map(|(path, function, tokens)| {
+			let mut call = call_json(function, tokens, format.checksum);
+			call["abi"] = Value::String(path.clone());
+			call
+		});
+		return Ok(Value::Array(matches.collect()).to_string());
+	}
+
+	let result = matches
+		.iter()
+		.map(|(path, function, tokens)| format!("{}\n{}", path, display_call(function, tokens, format, false)))
+		.collect::<Vec<String>>()
+		.join("\n\n");
+
+	Ok(result)
+}
+
+fn decode_constructor(
+	path: &str,
+	download: Option<(&str, u64, Address)>,
+	data: &str,
+	code: Option<&str>,
+	code_len: Option<usize>,
+	format: Format,
+	json: bool,
+) -> anyhow::Result<String> {
+	let contract = load_contract(path, download)?;
+	let constructor = contract.constructor().ok_or_else(|| anyhow!("ABI has no constructor"))?;
+	let data = hex_input(&read_input(data)?)?;
+
+	let code_len = match code {
+		Some(code) => {
+			let code = hex_input(&read_input(code)?)?;
+			if !data.starts_with(&code) {
+				return Err(anyhow!("creation input does not start with the code"));
+			}
+			code.len()
+		}
+		None => code_len.unwrap_or_default(),
+	};
+	if data.len() < code_len {
+		return Err(anyhow!("creation input is shorter than the code"));
+	}
+
+	let tokens = constructor.decode_input(&data[code_len..])?;
+
+	assert_eq!(constructor.inputs.len(), tokens.len());
+
+	if json {
+		let params = constructor
+			.inputs
+			.iter()
+			.zip(tokens.iter())
+			.map(|(param, to)| param_json(Some(&param.name), &param.kind, to, format.checksum));
+		return Ok(Value::Array(params.collect()).to_string());
+	}
+
+	let result = constructor
+		.inputs
+		.iter()
+		.zip(tokens.iter())
+		.map(|(param, to)| format!("{} {} {}", param.name, param.kind, display(to, format)))
 		.collect::<Vec<String>>()
 		.join("\n");
 
 	Ok(result)
 }
 
-fn decode_params(types: &[String], data: &str) -> anyhow::Result<String> {
+fn decode_params(types: &[String], data: &str, format: Format, json: bool, batch: bool) -> anyhow::Result<String> {
 	let types: Vec<ParamType> = types.iter().map(|s| Reader::read(s)).collect::<Result<_, _>>()?;
 
-	let data: Vec<u8> = hex::decode(&data)?;
+	decode_each(data, batch, json, |data| {
+		let tokens = decode(&types, data)?;
+
+		assert_eq!(types.len(), tokens.len());
+
+		if json {
+			let params = types.iter().zip(tokens.iter()).map(|(ty, to)| param_json(None, ty, to, format.checksum));
+			return Ok(Value::Array(params.collect()).to_string());
+		}
+
+		let result = types
+			.iter()
+			.zip(tokens.iter())
+			.map(|(ty, to)| format!("{} {}", ty, display(to, format)))
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		Ok(result)
+	})
+}
 
-	let tokens = decode(&types, &data)?;
+fn decode_packed(types: &[String], data: &str, format: Format, json: bool) -> anyhow::Result<String> {
+	let types: Vec<ParamType> = types.iter().map(|s| Reader::read(s)).collect::<Result<_, _>>()?;
+	let data = hex_input(&read_input(data)?)?;
+	let tokens = ethabi::decode_packed(&types, &data)?;
 
 	assert_eq!(types.len(), tokens.len());
 
-	let result =
-		types.iter().zip(tokens.iter()).map(|(ty, to)| format!("{} {}", ty, to)).collect::<Vec<String>>().join("\n");
+	if json {
+		let params = types.iter().zip(tokens.iter()).map(|(ty, to)| param_json(None, ty, to, format.checksum));
+		return Ok(Value::Array(params.collect()).to_string());
+	}
+
+	let result = types
+		.iter()
+		.zip(tokens.iter())
+		.map(|(ty, to)| format!("{} {}", ty, display(to, format)))
+		.collect::<Vec<String>>()
+		.join("\n");
 
 	Ok(result)
 }
 
-fn decode_log(path: &str, name_or_signature: &str, topics: &[String], data: &str) -> anyhow::Result<String> {
-	let event = load_event(path, name_or_signature)?;
+fn decode_log(
+	path: &str,
+	download: Option<(&str, u64, Address)>,
+	name_or_signature: &str,
+	topics: &[String],
+	data: &str,
+	format: Format,
+	json: bool,
+) -> anyhow::Result<String> {
+	let event = load_event(path, download, name_or_signature)?;
 	let topics: Vec<Hash> = topics.iter().map(|t| t.parse()).collect::<Result<_, _>>()?;
-	let data = hex::decode(data)?;
+	let data = hex_input(&read_input(data)?)?;
 	let decoded = event.parse_log((topics, data).into())?;
 
+	if json {
+		return Ok(log_json(&event, &decoded, format.checksum).to_string());
+	}
+
 	let result = decoded
 		.params
 		.into_iter()
-		.map(|log_param| format!("{} {}", log_param.name, log_param.value))
+		.map(|log_param| format!("{} {}", log_param.name, display(&log_param.value, format)))
+		.collect::<Vec<String>>()
+		.join("\n");
+
+	Ok(result)
+}
+
+/// Decodes each log of the JSON file, as of `eth_getLogs` or `eth_getTransactionReceipt`, with the first event of
+/// the ABIs which matches it, as a JSON object of the event signature, the params and where the log is from. Logs
+/// no event matches are skipped.
+fn decode_logs(
+	logs_path: &str,
+	abi_paths: &[String],
+	download: Option<(&str, u64, Address)>,
+	format: Format,
+) -> anyhow::Result<String> {
+	let contracts = load_contracts(abi_paths, download)?;
+	let input = match logs_path {
+		"-" => read_input(logs_path)?,
+		_ => std::fs::read_to_string(logs_path)?,
+	};
+	// e.g. a dump of a response per line
+	let mut logs = Vec::new();
+	for value in serde_json::Deserializer::from_str(&input).into_iter() {
+		collect_logs(value?, &mut logs)?;
+	}
+
+	let mut lines = Vec::new();
+	for log in logs {
+		let raw = raw_log(&log)?;
+		let topic0 = match raw.topics.first() {
+			Some(topic0) => *topic0,
+			None => continue,
+		};
+		// Events of different ABIs may have the same topic but different indexed params, as ERC-20 and ERC-721
+		// `Transfer`, so each is tried until one parses the log.
+		let decoded = contracts
+			.iter()
+			.flat_map(Contract::events)
+			.filter(|event| !event.anonymous && event.signature() == topic0)
+			.find_map(|event| Some((event, event.parse_log(raw.clone()).ok()?)));
+
+		if let Some((event, decoded)) = decoded {
+			let signature = event.canonical_signature();
+			let mut line = json!({ "event": signature, "params": log_json(event, &decoded, format.checksum) });
+			for key in &["address", "blockNumber", "transactionHash", "logIndex"] {
+				if let Some(value) = log.get(key) {
+					line[key] = value.clone();
+				}
+			}
+			lines.push(line.to_string());
+		}
+	}
+
+	Ok(lines.join("\n"))
+}
+
+/// Collects the logs of a log, a receipt, an array or a JSON-RPC response of them.
+fn collect_logs(value: Value, logs: &mut Vec<Value>) -> anyhow::Result<()> {
+	match value {
+		Value::Array(values) => values.into_iter().try_for_each(|value| collect_logs(value, logs)),
+		Value::Object(object) if object.contains_key("topics") => {
+			logs.push(Value::Object(object));
+			Ok(())
+		}
+		Value::Object(mut object) => match object.remove("result").or_else(|| object.remove("logs")) {
+			Some(value) => collect_logs(value, logs),
+			None => Err(anyhow!("expected logs, receipts or JSON-RPC responses of them")),
+		},
+		// e.g. the result of a receipt of a pending transaction
+		Value::Null => Ok(()),
+		_ => Err(anyhow!("expected logs, receipts or JSON-RPC responses of them, got `{}`", value)),
+	}
+}
+
+/// Reads the topics and data of a JSON log.
+fn raw_log(log: &Value) -> anyhow::Result<RawLog> {
+	let invalid = || anyhow!("invalid log `{}`", log);
+	let topics = log["topics"]
+		.as_array()
+		.ok_or_else(invalid)?
+		.iter()
+		.map(|topic| topic.as_str().and_then(|topic| topic.parse().ok()).ok_or_else(invalid))
+		.collect::<anyhow::Result<_>>()?;
+	let data = hex_input(log["data"].as_str().ok_or_else(invalid)?)?;
+	Ok(RawLog { topics, data })
+}
+
+/// Converts the params of a decoded log into a JSON array of their names, types and values.
+fn log_json(event: &Event, log: &Log, checksum: bool) -> Value {
+	// Only the hash of the value of hashed params is logged, so they are `bytes32` rather than of their type.
+	let params = event.inputs.iter().zip(log.params.iter()).map(|(param, log_param)| {
+		let kind = if param.is_hashed() { ParamType::FixedBytes(32) } else { param.kind.clone() };
+		param_json(Some(&log_param.name), &kind, &log_param.value, checksum)
+	});
+	Value::Array(params.collect())
+}
+
+/// Lists the canonical signatures of the functions, events and errors of the ABI with their selectors, or
+/// topics for events, sorted by signature. Anonymous events are left out, as their logs have no topic of their signature.
+fn selectors(path: &str, download: Option<(&str, u64, Address)>, json: bool) -> anyhow::Result<String> {
+	let contract = load_contract(path, download)?;
+	let sections = selector_sections(&contract);
+
+	if json {
+		let sections = sections.iter().map(|(section, items)| {
+			let items = items.iter().map(|(signature, selector)| (signature.clone(), hex_json(selector)));
+			(section.to_string(), Value::Object(items.collect()))
+		});
+		return Ok(Value::Object(sections.collect()).to_string());
+	}
+
+	let result = sections
+		.iter()
+		.filter(|(_, items)| !items.is_empty())
+		.flat_map(|(section, items)| {
+			let items = items.iter().map(|(signature, selector)| format!("  {} {}", hex::encode(selector), signature));
+			std::iter::once(format!("{}:", section)).chain(items)
+		})
+		.collect::<Vec<String>>()
+		.join("\n");
+
+	Ok(result)
+}
+
+/// Maps the selectors of the functions and errors and the topics of the events of the ABIs to their canonical
+/// signatures, in a JSON object of each, writing it to `out` if given. Different signatures of the same selector
+/// are an error, as tools could not tell them apart.
+fn selector_map(paths: &[String], download: Option<(&str, u64, Address)>, out: Option<&str>) -> anyhow::Result<String> {
+	let mut map: BTreeMap<&str, BTreeMap<String, String>> = BTreeMap::new();
+	for contract in load_contracts(paths, download)? {
+		for (section, items) in selector_sections(&contract).iter() {
+			let selectors = map.entry(section).or_default();
+			for (signature, selector) in items {
+				let selector = format!("0x{}", hex::encode(selector));
+				match selectors.get(&selector) {
+					Some(other) if other != signature => {
+						return Err(anyhow!("`{}` and `{}` have the same selector {}", other, signature, selector))
+					}
+					_ => selectors.insert(selector, signature.clone()),
+				};
+			}
+		}
+	}
+
+	let map = serde_json::to_string(&map)?;
+	match out {
+		Some(out) => {
+			std::fs::write(out, map)?;
+			Ok(String::new())
+		}
+		None => Ok(map),
+	}
+}
+
+/// Canonical signatures with their selectors, or topics for events.
+type Selectors = Vec<(String, Vec<u8>)>;
+
+/// Returns the canonical signatures of the functions, events and errors of the ABI with their selectors, or topics
+/// for events, sorted by signature. Anonymous events are left out, as their logs have no topic of their signature.
+fn selector_sections(contract: &Contract) -> [(&'static str, Selectors); 3] {
+	[
+		(
+			"functions",
+			contract.functions().map(|f| (f.canonical_signature(), f.selector().to_vec())).sorted().collect::<Vec<_>>(),
+		),
+		(
+			"events",
+			contract
+				.events()
+				.filter(|e| !e.anonymous)
+				.map(|e| (e.canonical_signature(), e.signature().0.to_vec()))
+				.sorted()
+				.collect(),
+		),
+		("errors", contract.errors().map(|e| (e.signature(), e.selector().to_vec())).sorted().collect()),
+	]
+}
+
+/// Function, event or error of an ABI, as compared by `diff`.
+struct AbiItem {
+	name: String,
+	signature: String,
+	/// Selector of functions and errors, topic of events.
+	selector: Vec<u8>,
+	/// Named properties not part of the signature, e.g. the outputs of functions.
+	properties: Vec<(&'static str, String)>,
+}
+
+/// Change of an ABI item between two ABIs.
+enum AbiChange {
+	Added(&'static str, String),
+	Removed(&'static str, String),
+	/// Changed item, by its old signature, with descriptions of the changes.
+	Changed(&'static str, String, Vec<String>),
+}
+
+/// Lists the changes of the functions, events and errors between two ABIs.
+///
+/// Items are matched by their signature, or by name if only a single item of the name was removed and
+/// added, whose signature and thus selector changed.
+fn diff(old_path: &str, new_path: &str, json: bool) -> anyhow::Result<String> {
+	let old = load_contract(old_path, None)?;
+	let new = load_contract(new_path, None)?;
+
+	let mut changes = diff_items("function", abi_functions(&old), abi_functions(&new));
+	changes.extend(diff_items("event", abi_events(&old), abi_events(&new)));
+	changes.extend(diff_items("error", abi_errors(&old), abi_errors(&new)));
+
+	if json {
+		let changes = changes.iter().map(|change| match change {
+			AbiChange::Added(kind, signature) => json!({ "change": "added", "kind": kind, "signature": signature }),
+			AbiChange::Removed(kind, signature) => json!({ "change": "removed", "kind": kind, "signature": signature }),
+			AbiChange::Changed(kind, signature, details) => {
+				json!({ "change": "changed", "kind": kind, "signature": signature, "details": details })
+			}
+		});
+		return Ok(Value::Array(changes.collect()).to_string());
+	}
+
+	let result = changes
+		.iter()
+		.map(|change| match change {
+			AbiChange::Added(kind, signature) => format!("+ {} {}", kind, signature),
+			AbiChange::Removed(kind, signature) => format!("- {} {}", kind, signature),
+			AbiChange::Changed(kind, signature, details) => format!("~ {} {}: {}", kind, signature, details.join(", ")),
+		})
 		.collect::<Vec<String>>()
 		.join("\n");
 
 	Ok(result)
 }
 
-fn hash_signature(sig: &str) -> Hash {
-	Hash::from_slice(Keccak256::digest(&sig.replace(" ", "").as_bytes()).as_slice())
+fn diff_items(kind: &'static str, old: Vec<AbiItem>, new: Vec<AbiItem>) -> Vec<AbiChange> {
+	let find = |items: &[AbiItem], signature: &str| items.iter().position(|item| item.signature == signature);
+	let mut removed: Vec<&AbiItem> = old.iter().filter(|item| find(&new, &item.signature).is_none()).collect();
+	let mut added: Vec<&AbiItem> = new.iter().filter(|item| find(&old, &item.signature).is_none()).collect();
+
+	let mut changes = Vec::new();
+	for item in &old {
+		if let Some(index) = find(&new, &item.signature) {
+			let details = diff_properties(item, &new[index]);
+			if !details.is_empty() {
+				changes.push(AbiChange::Changed(kind, item.signature.clone(), details));
+			}
+		}
+	}
+
+	let names: Vec<&str> = removed.iter().map(|item| item.name.as_str()).collect();
+	for name in names {
+		let single = |items: &[&AbiItem]| items.iter().filter(|item| item.name == name).count() == 1;
+		if !single(&removed) || !single(&added) {
+			continue;
+		}
+
+		let old_item = removed.remove(removed.iter().position(|item| item.name == name).expect("single item; qed"));
+		let new_item = added.remove(added.iter().position(|item| item.name == name).expect("single item; qed"));
+		let selector = if kind == "event" { "topic" } else { "selector" };
+		let mut details = vec![
+			format!("signature {} -> {}", old_item.signature, new_item.signature),
+			format!("{} {} -> {}", selector, hex::encode(&old_item.selector), hex::encode(&new_item.selector)),
+		];
+		details.extend(diff_properties(old_item, new_item));
+		changes.push(AbiChange::Changed(kind, old_item.signature.clone(), details));
+	}
+
+	changes.extend(removed.into_iter().map(|item| AbiChange::Removed(kind, item.signature.clone())));
+	changes.extend(added.into_iter().map(|item| AbiChange::Added(kind, item.signature.clone())));
+	changes
+}
+
+fn diff_properties(old: &AbiItem, new: &AbiItem) -> Vec<String> {
+	old.properties
+		.iter()
+		.zip(new.properties.iter())
+		.filter(|((_, old), (_, new))| old != new)
+		.map(|((name, old), (_, new))| format!("{} {} -> {}", name, old, new))
+		.collect()
+}
+
+fn abi_functions(contract: &Contract) -> Vec<AbiItem> {
+	contract
+		.functions()
+		.map(|f| AbiItem {
+			name: f.name.clone(),
+			signature: f.canonical_signature(),
+			selector: f.selector().to_vec(),
+			properties: vec![
+				("outputs", format!("({})", f.outputs.iter().map(|p| Writer::write(&p.kind)).join(","))),
+				(
+					"state mutability",
+					match f.state_mutability() {
+						StateMutability::Pure => "pure",
+						StateMutability::View => "view",
+						StateMutability::NonPayable => "nonpayable",
+						StateMutability::Payable => "payable",
+					}
+					.to_owned(),
+				),
+			],
+		})
+		.sorted_by(|a, b| a.signature.cmp(&b.signature))
+		.collect()
+}
+
+fn abi_events(contract: &Contract) -> Vec<AbiItem> {
+	contract
+		.events()
+		.map(|e| AbiItem {
+			name: e.name.clone(),
+			signature: e.canonical_signature(),
+			selector: e.signature().0.to_vec(),
+			properties: vec![
+				("indexed", format!("({})", e.inputs.iter().filter(|p| p.indexed).map(|p| &p.name).join(","))),
+				("anonymous", e.anonymous.to_string()),
+			],
+		})
+		.sorted_by(|a, b| a.signature.cmp(&b.signature))
+		.collect()
+}
+
+fn abi_errors(contract: &Contract) -> Vec<AbiItem> {
+	contract
+		.errors()
+		.map(|e| AbiItem {
+			name: e.name.clone(),
+			signature: e.signature(),
+			selector: e.selector().to_vec(),
+			properties: vec![],
+		})
+		.sorted_by(|a, b| a.signature.cmp(&b.signature))
+		.collect()
+}
+
+/// Converts a JSON ABI, which is an array, into human-readable signatures and any other file from them.
+fn fmt(path: &str) -> anyhow::Result<String> {
+	let abi = std::fs::read_to_string(path)?;
+	match abi.trim_start().starts_with('[') {
+		true => Ok(Contract::load(abi.as_bytes())?.to_text()),
+		false => Ok(serde_json::to_string_pretty(&Contract::load_text(abi.as_bytes())?)?),
+	}
+}
+
+/// Generates the bindings of the ABI as a Rust module, writing them to `out` if given.
+fn codegen(path: &str, out: Option<&str>, docs: bool, generate_tests: bool) -> anyhow::Result<String> {
+	let path = Path::new(path);
+	let (contract, _) = ethabi_codegen::load_contract(path)?;
+	let mut bindings = ethabi_codegen::Contract::from(&contract);
+	if docs {
+		let title = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+		bindings = bindings.docs(ethabi_codegen::markdown(title.split('.').next().unwrap_or_default(), &contract));
+	}
+	if generate_tests {
+		bindings = bindings.generate_tests();
+	}
+
+	let file = syn::parse2(bindings.generate())?;
+	let source =
+		format!("// Generated by `ethabi codegen` from `{}`.\n\n{}", path.display(), prettyplease::unparse(&file));
+	match out {
+		Some(out) => {
+			std::fs::write(out, source)?;
+			Ok(String::new())
+		}
+		None => Ok(source.trim_end().to_owned()),
+	}
+}
+
+/// Decodes the hex encoded data of the input, see `read_input`, or if `batch` of each of its lines, printing
+/// their results separated by empty lines, or as a JSON array if `json`.
+fn decode_each<F>(input: &str, batch: bool, json: bool, decode: F) -> anyhow::Result<String>
+where
+	F: Fn(&[u8]) -> anyhow::Result<String>,
+{
+	let input = read_input(input)?;
+	if !batch {
+		return decode(&hex_input(&input)?);
+	}
+
+	let results = input
+		.lines()
+		.enumerate()
+		.filter(|(_, line)| !line.trim().is_empty())
+		.map(|(index, line)| {
+			hex_input(line).and_then(|data| decode(&data)).map_err(|err| anyhow!("line {}: {}", index + 1, err))
+		})
+		.collect::<anyhow::Result<Vec<_>>>()?;
+
+	match json {
+		true => Ok(format!("[{}]", results.join(","))),
+		false => Ok(results.join("\n\n")),
+	}
+}
+
+/// Reads an input argument, which is `-` to read it from stdin, `@<path>` to read it from a file or the input itself.
+fn read_input(input: &str) -> anyhow::Result<String> {
+	match input {
+		"-" => {
+			let mut input = String::new();
+			std::io::stdin().read_to_string(&mut input)?;
+			Ok(input)
+		}
+		_ => match input.strip_prefix('@') {
+			Some(path) => Ok(std::fs::read_to_string(path)?),
+			None => Ok(input.to_owned()),
+		},
+	}
+}
+
+/// Decodes hex encoded data, which may be `0x` prefixed and surrounded by whitespace.
+fn hex_input(data: &str) -> anyhow::Result<Vec<u8>> {
+	let data = data.trim();
+	Ok(hex::decode(data.strip_prefix("0x").unwrap_or(data))?)
+}
+
+/// Displays the token in the format, like its `Display` implementation by default.
+fn display(token: &Token, format: Format) -> String {
+	let prefix = if format.prefix { "0x" } else { "" };
+	match token {
+		Token::Address(address) if format.checksum => format!("{}{}", prefix, checksum_address(address)),
+		Token::Address(address) => format!("{}{:x}", prefix, address),
+		Token::Bytes(bytes) | Token::FixedBytes(bytes) => format!("{}{}", prefix, hex::encode(bytes)),
+		Token::Uint(uint) if format.decimal => uint.to_string(),
+		Token::Int(int) if format.decimal => I256::from_raw(*int).to_string(),
+		Token::Uint(uint) | Token::Int(uint) => format!("{}{:x}", prefix, uint),
+		Token::Array(tokens) | Token::FixedArray(tokens) => {
+			format!("[{}]", tokens.iter().map(|token| display(token, format)).join(","))
+		}
+		Token::Tuple(tokens) => format!("({})", tokens.iter().map(|token| display(token, format)).join(",")),
+		Token::Bool(_) | Token::String(_) => token.to_string(),
+	}
+}
+
+/// Displays encoded data as hex, or as a `0x` prefixed JSON string if `json`.
+fn display_encoded(data: &[u8], json: bool) -> String {
+	match json {
+		true => hex_json(data).to_string(),
+		false => hex::encode(data),
+	}
+}
+
+/// Displays explained calldata of a call of the function, preceded by its signature and selector unless `json`.
+fn display_explained_calldata(function: &Function, data: &[u8], json: bool) -> anyhow::Result<String> {
+	let words = display_explained(&function.explain_calldata(data)?, json);
+	match json {
+		true => Ok(words),
+		false => Ok(format!("{} selector 0x{}\n{}", function.signature(), hex::encode(function.selector()), words)),
+	}
+}
+
+/// Displays explained words of encoded data, one per line, as their offset, the word, whether it is in the head
+/// or a tail, the param it belongs to and what it holds, or as a JSON array of objects of them if `json`.
+fn display_explained(words: &[ExplainedWord], json: bool) -> String {
+	let role = |role: &WordRole| match role {
+		WordRole::Value(kind) => kind.to_string(),
+		WordRole::Offset(offset) => format!("offset 0x{:x}", offset),
+		WordRole::Length(len) => format!("length {}", len),
+		WordRole::Data => "data".to_owned(),
+		WordRole::Unused => "unused".to_owned(),
+	};
+	let section = |word: &ExplainedWord| if word.head { "head" } else { "tail" };
+
+	if json {
+		let words = words.iter().map(|word| {
+			json!({
+				"offset": word.offset,
+				"word": hex_json(&word.word[..]),
+				"section": section(word),
+				"param": word.param,
+				"role": role(&word.role),
+			})
+		});
+		return Value::Array(words.collect()).to_string();
+	}
+
+	let width = words.iter().map(|word| word.param.len()).max().unwrap_or_default();
+	words
+		.iter()
+		.map(|word| {
+			let line = format!(
+				"0x{:04x}  {}  {}  {:width$}  {}",
+				word.offset,
+				hex::encode(word.word),
+				section(word),
+				word.param,
+				role(&word.role),
+				width = width
+			);
+			line.trim_end().to_owned()
+		})
+		.join("\n")
+}
+
+fn hex_json(data: &[u8]) -> Value {
+	Value::String(format!("0x{}", hex::encode(data)))
+}
+
+/// Converts a decoded param into a JSON object of its name, if any, type and value.
+fn param_json(name: Option<&str>, kind: &ParamType, token: &Token, checksum: bool) -> Value {
+	let mut param = json!({ "type": kind.to_string(), "value": token_json(token, checksum) });
+	if let Some(name) = name {
+		param["name"] = Value::String(name.to_owned());
+	}
+	param
+}
+
+/// Converts the token into JSON as `token_to_json` does, with EIP-55 checksums of addresses if `checksum`.
+fn token_json(token: &Token, checksum: bool) -> Value {
+	match token {
+		Token::Address(address) if checksum => Value::String(format!("0x{}", checksum_address(address))),
+		Token::Array(tokens) | Token::FixedArray(tokens) | Token::Tuple(tokens) if checksum => {
+			Value::Array(tokens.iter().map(|token| token_json(token, checksum)).collect())
+		}
+		_ => token_to_json(token.clone()),
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use super::execute;
+	use super::{execute, insert_address_as_abi_path};
 
 	#[test]
 	fn simple_encode() {
@@ -260,6 +1500,25 @@ mod tests {
 		assert_eq!(execute(command).unwrap(), expected);
 	}
 
+	#[test]
+	fn sig_encode() {
+		let command =
+			"ethabi encode sig transfer(address,uint256) 0x4444444444444444444444444444444444444444 100 --lenient"
+				.split(' ');
+		let expected = "a9059cbb0000000000000000000000004444444444444444444444444444444444444444\
+		                0000000000000000000000000000000000000000000000000000000000000064";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi encode sig foo(int256,bool) -1 true --lenient".split(' ');
+		let expected = "580bc5abffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+		                0000000000000000000000000000000000000000000000000000000000000001";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command =
+			"ethabi encode sig transfer(address,uint256) 0x4444444444444444444444444444444444444444".split(' ');
+		assert!(execute(command).is_err());
+	}
+
 	#[test]
 	fn int_encode() {
 		let command = "ethabi encode params -v int256 -2 --lenient".split(' ');
@@ -344,6 +1603,12 @@ mod tests {
 		assert_eq!(execute(command).unwrap(), expected);
 	}
 
+	#[test]
+	fn function_encode_negative_param() {
+		let command = "ethabi encode function ../res/eip20.abi transfer -p 1111111111111111111111111111111111111111 -p -2 --lenient".split(' ');
+		assert_eq!(execute(command).unwrap_err().to_string(), "Uint parse error: InvalidCharacter");
+	}
+
 	#[test]
 	fn function_encode_by_signature() {
 		let command = "ethabi encode function ../res/test.abi foo(bool) -p 1".split(' ');
@@ -413,6 +1678,13 @@ bool false";
 		assert_eq!(execute(command).unwrap(), expected);
 	}
 
+	#[test]
+	fn checksum_decode() {
+		let command = "ethabi decode params -t address 0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed --checksum".split(' ');
+		let expected = "address 5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+		assert_eq!(execute(command).unwrap(), expected);
+	}
+
 	#[test]
 	fn abi_decode() {
 		let command = "ethabi decode function ../res/foo.abi bar 0000000000000000000000000000000000000000000000000000000000000001".split(' ');
@@ -420,6 +1692,336 @@ bool false";
 		assert_eq!(execute(command).unwrap(), expected);
 	}
 
+	#[test]
+	fn call_decode() {
+		let command = "ethabi decode call ../res/eip20.abi 0xa9059cbb0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed000000000000000000000000000000000000000000000000000000000000002a --checksum".split(' ');
+		let expected = "transfer(address,uint256):(bool)
+_to address 5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed
+_value uint256 2a";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi decode call ../res/eip20.abi 12345678".split(' ');
+		assert!(execute(command).is_err());
+	}
+
+	#[cfg(feature = "online")]
+	#[test]
+	fn call_decode_candidates() {
+		let data = hex::decode("a9059cbb0000000000000000000000004444444444444444444444444444444444444444000000000000000000000000000000000000000000000000000000000000002a").unwrap();
+		let format = super::Format { checksum: false, decimal: false, prefix: false };
+		// many_msg_babbage(bytes1) shares the selector of transfer, but encodes other calldata
+		let signatures = ["many_msg_babbage(bytes1)", "transfer(address,uint256)", "transfer(address"];
+		let expected = " address 4444444444444444444444444444444444444444
+ uint256 2a";
+		let calls = super::decode_call_candidates(&data, signatures, format, false);
+		assert_eq!(calls, vec![format!("transfer(address,uint256)\n{}", expected)]);
+	}
+
+	#[test]
+	fn json_decode() {
+		let command = "ethabi decode function ../res/foo.abi bar 0000000000000000000000000000000000000000000000000000000000000001 --json".split(' ');
+		let expected = r#"[{"name":"","type":"bool","value":true}]"#;
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi --json decode params -t address -t uint256[] 0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed0000000000000000000000000000000000000000000000000000000000000040000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000ff --checksum".split(' ');
+		let expected = r#"[{"type":"address","value":"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"},{"type":"uint256[]","value":["255"]}]"#;
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi decode call ../res/eip20.abi a9059cbb0000000000000000000000004444444444444444444444444444444444444444000000000000000000000000000000000000000000000000000000000000002a --json".split(' ');
+		let expected = r#"{"function":"transfer(address,uint256):(bool)","params":[{"name":"_to","type":"address","value":"0x4444444444444444444444444444444444444444"},{"name":"_value","type":"uint256","value":"42"}]}"#;
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi decode log ../res/event.abi Event -l 0000000000000000000000000000000000000000000000000000000000000001 0000000000000000000000004444444444444444444444444444444444444444 --json".split(' ');
+		let expected = r#"[{"name":"a","type":"bool","value":true},{"name":"b","type":"address","value":"0x4444444444444444444444444444444444444444"}]"#;
+		assert_eq!(execute(command).unwrap(), expected);
+	}
+
+	#[test]
+	fn json_encode() {
+		let command = "ethabi encode params -v bool 1 --json".split(' ');
+		let expected = r#""0x0000000000000000000000000000000000000000000000000000000000000001""#;
+		assert_eq!(execute(command).unwrap(), expected);
+	}
+
+	#[test]
+	fn batch_decode() {
+		let command = "ethabi decode call ../res/eip20.abi @../res/calls.txt --batch".split(' ');
+		let expected = "transfer(address,uint256):(bool)
+_to address 1111111111111111111111111111111111111111
+_value uint256 3e8
+
+transfer(address,uint256):(bool)
+_to address 2222222222222222222222222222222222222222
+_value uint256 1";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi decode call ../res/eip20.abi @../res/calls.txt --batch --json".split(' ');
+		let result = execute(command).unwrap();
+		assert!(result.starts_with(r#"[{"function":"transfer(address,uint256):(bool)""#));
+		assert!(result.ends_with(r#""value":"1"}]}]"#));
+
+		let command =
+			"ethabi decode params -t bool 0x0000000000000000000000000000000000000000000000000000000000000001 --batch"
+				.split(' ');
+		assert_eq!(execute(command).unwrap(), "bool true");
+
+		let command = "ethabi decode params -t bool 00\n\n01 --batch".split(' ');
+		assert!(execute(command).unwrap_err().to_string().starts_with("line 1: Invalid data"));
+	}
+
+	#[test]
+	fn constructor_decode() {
+		let data = format!("60806040{:0>64}", "44".repeat(20));
+		let command = format!("ethabi decode constructor ../res/constructor.abi {} --code 0x60806040", data);
+		let expected = "a address 4444444444444444444444444444444444444444";
+		assert_eq!(execute(command.split(' ')).unwrap(), expected);
+
+		let command = format!("ethabi decode constructor ../res/constructor.abi {} --code-len 4", data);
+		assert_eq!(execute(command.split(' ')).unwrap(), expected);
+
+		let command = format!("ethabi decode constructor ../res/constructor.abi {} --code 6080604f", data);
+		assert!(execute(command.split(' ')).is_err());
+
+		let command = format!("ethabi decode constructor ../res/constructor.abi {} --code-len 40", data);
+		assert!(execute(command.split(' ')).is_err());
+	}
+
+	#[test]
+	fn abi_selectors() {
+		let command = "ethabi selectors ../res/eip20.abi".split(' ');
+		let expected = "functions:
+  dd62ed3e allowance(address,address)
+  095ea7b3 approve(address,uint256)
+  70a08231 balanceOf(address)
+  18160ddd totalSupply()
+  a9059cbb transfer(address,uint256)
+  23b872dd transferFrom(address,address,uint256)
+events:
+  8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925 Approval(address,address,uint256)
+  ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef Transfer(address,address,uint256)";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi selectors ../res/event.abi".split(' ');
+		assert_eq!(execute(command).unwrap(), "");
+
+		let command = "ethabi selectors ../res/foo.abi --json".split(' ');
+		let expected = r#"{"errors":{},"events":{},"functions":{"bar(address)":"0x646ea56d"}}"#;
+		assert_eq!(execute(command).unwrap(), expected);
+	}
+
+	#[test]
+	fn abi_address() {
+		let args = |command: &str| command.split(' ').map(Into::into).collect::<Vec<std::ffi::OsString>>();
+		let address = "0x1111111111111111111111111111111111111111";
+
+		// the address takes the place of the ABI path of commands taking a single ABI
+		let command = format!("ethabi --address {} --etherscan-key key selectors --json", address);
+		let expected = format!("ethabi --address {0} --etherscan-key key selectors {0} --json", address);
+		assert_eq!(insert_address_as_abi_path(args(&command)), args(&expected));
+		let command = format!("ethabi encode function --address={} transfer -p 1", address);
+		let expected = format!("ethabi encode function {0} --address={0} transfer -p 1", address);
+		assert_eq!(insert_address_as_abi_path(args(&command)), args(&expected));
+		let command = format!("ethabi decode logs logs.json --address {} --chain 10", address);
+		assert_eq!(insert_address_as_abi_path(args(&command)), args(&command));
+		let command = "ethabi selectors ../res/foo.abi";
+		assert_eq!(insert_address_as_abi_path(args(command)), args(command));
+
+		// the path is never read as an address
+		let command = format!("ethabi selectors {} --etherscan-key key", address);
+		assert!(execute(command.split(' ')).is_err());
+
+		#[cfg(not(feature = "online"))]
+		for command in [
+			"ethabi selectors --json",
+			"ethabi encode function transfer -p 1",
+			"ethabi encode batch --function transfer --csv -",
+			"ethabi selector-map",
+			"ethabi decode logs - --chain 10",
+		] {
+			let command = format!("{} --address {} --etherscan-key key", command, address);
+			let err = execute(command.split(' ')).unwrap_err();
+			assert_eq!(err.to_string(), "downloading ABIs from Etherscan requires the `online` feature");
+		}
+	}
+
+	#[test]
+	fn abi_diff() {
+		let command = "ethabi diff ../res/eip20.abi ../res/eip20_v2.abi".split(' ');
+		let expected = "~ function balanceOf(address): state mutability view -> nonpayable
+~ function transfer(address,uint256): outputs (bool) -> ()
+~ function transferFrom(address,address,uint256): signature transferFrom(address,address,uint256) -> transferFrom(address,address,uint128), selector 23b872dd -> 7b781452
+- function approve(address,uint256)
++ function mint(address,uint256)
+~ event Transfer(address,address,uint256): indexed (from,to) -> (from)
++ error InsufficientBalance(uint256,uint256)";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi diff ../res/eip20.abi ../res/eip20.abi --json".split(' ');
+		assert_eq!(execute(command).unwrap(), "[]");
+	}
+
+	#[test]
+	fn abi_fmt() {
+		let command = "ethabi fmt ../res/event.abi".split(' ');
+		let expected = "event Event(bool indexed a, address b) anonymous";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let json = execute("ethabi fmt ../res/eip20.txt.abi".split(' ')).unwrap();
+		let text = std::fs::File::open("../res/eip20.txt.abi").unwrap();
+		assert_eq!(ethabi::Contract::load(json.as_bytes()).unwrap(), ethabi::Contract::load_text(text).unwrap());
+	}
+
+	#[test]
+	fn abi_codegen() {
+		let bindings = execute("ethabi codegen ../res/eip20.abi".split(' ')).unwrap();
+		assert!(bindings.starts_with("// Generated by `ethabi codegen` from `../res/eip20.abi`."));
+		assert!(bindings.contains("pub mod functions {"));
+		assert!(bindings.contains("pub mod transfer_from {"));
+		assert!(!bindings.contains("pub const DOCS"));
+
+		let bindings = execute("ethabi codegen ../res/eip20.abi --docs".split(' ')).unwrap();
+		assert!(bindings.contains("pub const DOCS"));
+
+		let bindings = execute("ethabi codegen ../res/tuples.abi".split(' ')).unwrap();
+		assert!(bindings.contains("pub mod swap {"));
+	}
+
+	#[test]
+	fn logs_decode() {
+		let command = "ethabi decode logs ../res/logs.json --abi ../res/eip20.abi --abi ../res/event.abi".split(' ');
+		let expected = r#"{"address":"0x5555555555555555555555555555555555555555","blockNumber":"0xe4e1c0","event":"Transfer(address,address,uint256)","logIndex":"0x0","params":[{"name":"from","type":"address","value":"0x4444444444444444444444444444444444444444"},{"name":"to","type":"address","value":"0x6666666666666666666666666666666666666666"},{"name":"value","type":"uint256","value":"100"}],"transactionHash":"0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"}"#;
+		assert_eq!(execute(command).unwrap(), expected);
+
+		// addresses of repeated digits have no letters to checksum
+		let command = "ethabi decode logs ../res/logs.json --abi ../res/eip20.abi --checksum".split(' ');
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi decode logs ../res/eip20.abi --abi ../res/eip20.abi".split(' ');
+		assert!(execute(command).is_err());
+	}
+
+	#[test]
+	fn abi_selector_map() {
+		let command = "ethabi selector-map ../res/foo.abi ../res/event.abi ../res/foo.abi".split(' ');
+		let expected = r#"{"errors":{},"events":{},"functions":{"0x646ea56d":"bar(address)"}}"#;
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let out = std::env::temp_dir().join("ethabi_selector_map.json");
+		let command = format!("ethabi selector-map ../res/eip20.abi --out {}", out.display());
+		assert_eq!(execute(command.split(' ')).unwrap(), "");
+		let map: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+		assert_eq!(map["functions"]["0xa9059cbb"], "transfer(address,uint256)");
+		assert_eq!(
+			map["events"]["0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"],
+			"Transfer(address,address,uint256)"
+		);
+		let path = std::env::temp_dir().join("ethabi_selector_map_clash.abi");
+		let abi = r#"[
+			{"type":"function","name":"burn","inputs":[{"name":"","type":"uint256"}],"outputs":[]},
+			{"type":"function","name":"collate_propagate_storage","inputs":[{"name":"","type":"bytes16"}],"outputs":[]}
+		]"#;
+		std::fs::write(&path, abi).unwrap();
+		let command = format!("ethabi selector-map {}", path.display());
+		let err = execute(command.split(' ')).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"`burn(uint256)` and `collate_propagate_storage(bytes16)` have the same selector 0x42966c68"
+		);
+	}
+
+	#[test]
+	fn constructor_encode() {
+		let command =
+			"ethabi encode constructor ../res/constructor.abi 0x6080 4444444444444444444444444444444444444444"
+				.split(' ');
+		let expected = "60800000000000000000000000004444444444444444444444444444444444444444";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi encode constructor ../res/eip20.abi 6080".split(' ');
+		assert_eq!(execute(command).unwrap(), "6080");
+
+		let command = "ethabi encode constructor ../res/constructor.abi 6080".split(' ');
+		assert_eq!(execute(command).unwrap_err().to_string(), "constructor has 1 params, but 0 were given");
+	}
+
+	#[test]
+	fn calldata_identify() {
+		let command = "ethabi identify ../res 646ea56d0000000000000000000000004444444444444444444444444444444444444444"
+			.split(' ');
+		let expected = "../res/foo.abi
+bar(address):(bool)
+hello address 4444444444444444444444444444444444444444";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi identify ../res 0x12345678".split(' ');
+		let expected = "no ABI in `../res` has a function of selector 0x12345678";
+		assert_eq!(execute(command).unwrap_err().to_string(), expected);
+	}
+
+	#[test]
+	fn explain() {
+		let command = "ethabi encode sig f(string[],bool) [a] true --lenient --explain".split(' ');
+		let expected = "f(string[],bool) selector 0x4440771e
+0x0004  0000000000000000000000000000000000000000000000000000000000000040  head  #0     offset 0x40
+0x0024  0000000000000000000000000000000000000000000000000000000000000001  head  #1     bool
+0x0044  0000000000000000000000000000000000000000000000000000000000000001  tail  #0     length 1
+0x0064  0000000000000000000000000000000000000000000000000000000000000020  tail  #0[0]  offset 0x20
+0x0084  0000000000000000000000000000000000000000000000000000000000000001  tail  #0[0]  length 1
+0x00a4  6100000000000000000000000000000000000000000000000000000000000000  tail  #0[0]  data";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let data = "a9059cbb0000000000000000000000004444444444444444444444444444444444444444\
+		            0000000000000000000000000000000000000000000000000000000000000064";
+		let command = format!("ethabi decode call ../res/eip20.abi {} --explain", data);
+		let expected = "transfer(address,uint256):(bool) selector 0xa9059cbb
+0x0004  0000000000000000000000004444444444444444444444444444444444444444  head  _to     address
+0x0024  0000000000000000000000000000000000000000000000000000000000000064  head  _value  uint256";
+		assert_eq!(execute(command.split(' ')).unwrap(), expected);
+
+		let command = "ethabi decode params -t uint8 0000000000000000000000000000000000000000000000000000000000000007\
+		               0000000000000000000000000000000000000000000000000000000000000000 --explain --json"
+			.split(' ');
+		let expected = r##"[{"offset":0,"param":"#0","role":"uint8","section":"head","word":"0x0000000000000000000000000000000000000000000000000000000000000007"},{"offset":32,"param":"","role":"unused","section":"tail","word":"0x0000000000000000000000000000000000000000000000000000000000000000"}]"##;
+		assert_eq!(execute(command).unwrap(), expected);
+	}
+
+	#[test]
+	fn packed_decode() {
+		let command = "ethabi decode packed -t address -t uint16 -t string 4444444444444444444444444444444444444444000768656c6c6f".split(' ');
+		let expected = "address 4444444444444444444444444444444444444444
+uint16 7
+string hello";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi decode packed -t string -t bytes 68656c6c6f".split(' ');
+		let expected = "Ambiguous packed data: the lengths of both string and bytes are not encoded";
+		assert_eq!(execute(command).unwrap_err().to_string(), expected);
+	}
+
+	#[test]
+	fn format_decode() {
+		let data = "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed\
+		            00000000000000000000000000000000000000000000000000000000000000ff\
+		            fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff6\
+		            abcd000000000000000000000000000000000000000000000000000000000000";
+		let command = format!("ethabi decode params -t address -t uint256 -t int8 -t bytes2 {}", data);
+		let expected = "address 5aaeb6053f3e94c9b9a09f33669435e7ef1beaed
+uint256 ff
+int8 fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff6
+bytes2 abcd";
+		assert_eq!(execute(command.split(' ')).unwrap(), expected);
+
+		let command = format!("{} --decimal --prefix --checksum", command);
+		let expected = "address 0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed
+uint256 255
+int8 -10
+bytes2 0xabcd";
+		assert_eq!(execute(command.split(' ')).unwrap(), expected);
+
+		let command = "ethabi decode params -t (uint8,bytes1)[] 000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000010ff00000000000000000000000000000000000000000000000000000000000000 --prefix".split(' ');
+		assert_eq!(execute(command).unwrap(), "(uint8,bytes1)[] [(0x10,0xff)]");
+	}
+
 	#[test]
 	fn log_decode() {
 		let command = "ethabi decode log ../res/event.abi Event -l 0000000000000000000000000000000000000000000000000000000000000001 0000000000000000000000004444444444444444444444444444444444444444".split(' ');
@@ -428,6 +2030,15 @@ b 4444444444444444444444444444444444444444";
 		assert_eq!(execute(command).unwrap(), expected);
 	}
 
+	#[test]
+	fn overloaded_function_encode_by_shorthand_signature() {
+		let command = "ethabi encode function ../res/test.abi bar(string):(uint) -p 1".split(' ');
+		let expected = "d473a8ed0000000000000000000000000000000000000000000000000000000000000020\
+		                000000000000000000000000000000000000000000000000000000000000000131000000\
+		                00000000000000000000000000000000000000000000000000000000";
+		assert_eq!(execute(command).unwrap(), expected);
+	}
+
 	#[test]
 	fn log_decode_signature() {
 		let command = "ethabi decode log ../res/event.abi Event(bool,address) -l 0000000000000000000000000000000000000000000000000000000000000001 0000000000000000000000004444444444444444444444444444444444444444".split(' ');
@@ -442,4 +2053,41 @@ b 4444444444444444444444444444444444444444";
 		let command = "ethabi decode log ../res/event.abi Nope(bool,address) -l 0000000000000000000000000000000000000000000000000000000000000000 0000000000000000000000004444444444444444444444444444444444444444".split(' ');
 		assert!(execute(command).is_err());
 	}
+
+	#[test]
+	fn batch_encode() {
+		let command =
+			"ethabi encode batch --abi ../res/eip20.abi --function transfer --csv ../res/recipients.csv --lenient"
+				.split(' ');
+		let expected = "\
+a9059cbb000000000000000000000000111111111111111111111111111111111111111100000000000000000000000000000000000000000000000000000000000003e8
+a9059cbb00000000000000000000000022222222222222222222222222222222222222220000000000000000000000000000000000000000000000000000000000000001";
+		assert_eq!(execute(command).unwrap(), expected);
+	}
+
+	#[test]
+	fn batch_encode_multicall() {
+		let command =
+			"ethabi encode batch --abi ../res/eip20.abi --function transfer --csv ../res/recipients.csv --lenient --multicall"
+				.split(' ');
+		let result = execute(command).unwrap();
+		assert!(result.starts_with("ac9650d8"));
+		assert!(result.contains("a9059cbb0000000000000000000000001111111111111111111111111111111111111111"));
+		assert!(result.contains("a9059cbb0000000000000000000000002222222222222222222222222222222222222222"));
+	}
+
+	#[test]
+	fn batch_encode_reports_invalid_rows() {
+		let command =
+			"ethabi encode batch --abi ../res/eip20.abi --function transfer --csv ../res/recipients.csv".split(' ');
+		let err = execute(command).unwrap_err().to_string();
+		assert!(err.starts_with("2 invalid rows\nrow 2: "), "{}", err);
+		assert!(err.contains("\nrow 4: "), "{}", err);
+	}
+
+	#[test]
+	fn split_csv_row() {
+		assert_eq!(super::split_csv_row("a, \"[1,2]\" ,\"say \"\"hi\"\"\"").unwrap(), ["a", "[1,2]", "say \"hi\""]);
+		assert!(super::split_csv_row("\"a").is_err());
+	}
 }
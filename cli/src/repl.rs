@@ -0,0 +1,298 @@
+//! Interactive mode of `ethabi repl`, evaluating commands against the functions, events and errors of
+//! one or more ABIs.
+
+use crate::{display_call, display_output, encode_function_input, find_function, hex_input, hex_json, Format};
+use anyhow::anyhow;
+use ethabi::{canonical_signature, signature_hash, Contract, Function, MergePolicy};
+use itertools::Itertools;
+use rustyline::{
+	completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter, validate::Validator, Context,
+	Editor, Helper,
+};
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Commands of the REPL with the usage of their arguments and what they do, as listed by `help`.
+const COMMANDS: &[(&str, &str, &str)] = &[
+	("encode", "<function-name-or-signature> [<param>]...", "Encode a call of a function, with lenient params."),
+	("decode", "<data>", "Decode a function call, finding the function by its selector."),
+	("output", "<function-name-or-signature> <data>", "Decode the output of a function."),
+	("selector", "<name-or-signature>", "Print the selectors of functions and errors and the topics of events."),
+	("functions", "", "List the signatures of the functions of the ABIs."),
+	("help", "", "Print this message."),
+	("exit", "", "Exit the REPL."),
+];
+
+/// State of the REPL, the ABIs merged into a single contract and how to print results.
+pub struct Repl {
+	contract: Contract,
+	format: Format,
+	json: bool,
+}
+
+impl Repl {
+	/// Creates a REPL of the contracts, of which the first to define a function, event or error is used.
+	pub fn new(contracts: &[Contract], format: Format, json: bool) -> anyhow::Result<Self> {
+		let contract = contracts
+			.iter()
+			.try_fold(Contract::default(), |merged, contract| merged.merge_with(contract, MergePolicy::PreferLeft))?;
+		Ok(Repl { contract, format, json })
+	}
+
+	/// Reads and evaluates lines until `exit` or the end of input, keeping their history in `~/.ethabi_history`.
+	pub fn run(&self) -> anyhow::Result<()> {
+		let mut editor = Editor::new();
+		editor.set_helper(Some(Completions::new(&self.contract)));
+		let history = std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".ethabi_history"));
+		if let Some(history) = &history {
+			// there is no history on the first run
+			let _ = editor.load_history(history);
+		}
+
+		loop {
+			let line = match editor.readline("ethabi> ") {
+				Ok(line) => line,
+				Err(ReadlineError::Interrupted) => continue,
+				Err(ReadlineError::Eof) => break,
+				Err(err) => return Err(err.into()),
+			};
+			editor.add_history_entry(line.as_str());
+
+			match self.eval(&line) {
+				Ok(Some(result)) if result.is_empty() => (),
+				Ok(Some(result)) => println!("{}", result),
+				Ok(None) => break,
+				Err(err) => eprintln!("Error: {}", err),
+			}
+		}
+
+		if let Some(history) = &history {
+			editor.save_history(history)?;
+		}
+		Ok(())
+	}
+
+	/// Evaluates a line, returning what to print, or `None` to exit.
+	pub fn eval(&self, line: &str) -> anyhow::Result<Option<String>> {
+		let words = split_words(line)?;
+		let (command, args) = match words.split_first() {
+			Some((command, args)) => (command.as_str(), args),
+			None => return Ok(Some(String::new())),
+		};
+
+		let result = match (command, args) {
+			("encode", [function, params @ ..]) => {
+				encode_function_input(&self.function(function)?, params, true, self.json, false)?
+			}
+			("decode", [data]) => {
+				let (function, tokens) = self.contract.decode_input(&hex_input(data)?)?;
+				display_call(function, &tokens, self.format, self.json)
+			}
+			("output", [function, data]) => {
+				let function = self.function(function)?;
+				let tokens = function.decode_output(&hex_input(data)?)?;
+				display_output(&function, &tokens, self.format, self.json)
+			}
+			("selector", [name_or_signature]) => self.selector(name_or_signature)?,
+			("functions", []) => self.contract.functions().map(Function::signature).sorted().join("\n"),
+			("help", []) => help(),
+			("exit", []) | ("quit", []) => return Ok(None),
+			_ => match COMMANDS.iter().find(|(name, _, _)| *name == command) {
+				Some((name, usage, _)) => return Err(anyhow!("usage: {} {}", name, usage)),
+				None => return Err(anyhow!("unknown command `{}`, try `help`", command)),
+			},
+		};
+
+		Ok(Some(result))
+	}
+
+	/// Finds a function of the ABIs, or parses the signature of a function missing from them.
+	fn function(&self, name_or_signature: &str) -> anyhow::Result<Function> {
+		match find_function(&self.contract, name_or_signature) {
+			Err(_) if name_or_signature.contains('(') => Ok(Function::from_signature(name_or_signature)?),
+			result => result,
+		}
+	}
+
+	/// Lists the selectors of the functions and errors and the topics of the events of the name, or the
+	/// selector of the signature.
+	fn selector(&self, name_or_signature: &str) -> anyhow::Result<String> {
+		let items: Vec<(String, Vec<u8>)> = match name_or_signature.contains('(') {
+			true => {
+				let selector = signature_hash(name_or_signature)?.as_bytes()[..4].to_vec();
+				vec![(canonical_signature(name_or_signature)?, selector)]
+			}
+			false => {
+				let functions = self
+					.contract
+					.functions_by_name(name_or_signature)
+					.into_iter()
+					.flatten()
+					.map(|f| (f.canonical_signature(), f.selector().to_vec()));
+				let events = self
+					.contract
+					.events_by_name(name_or_signature)
+					.into_iter()
+					.flatten()
+					.map(|e| (e.canonical_signature(), e.signature().0.to_vec()));
+				let errors = self
+					.contract
+					.errors_by_name(name_or_signature)
+					.into_iter()
+					.flatten()
+					.map(|e| (e.signature(), e.selector().to_vec()));
+				functions.chain(events).chain(errors).collect()
+			}
+		};
+
+		match (items.is_empty(), self.json) {
+			(true, _) => Err(anyhow!("no function, event or error named `{}`", name_or_signature)),
+			(false, true) => {
+				let items = items.into_iter().map(|(signature, selector)| (signature, hex_json(&selector)));
+				Ok(Value::Object(items.collect()).to_string())
+			}
+			(false, false) => Ok(items
+				.into_iter()
+				.map(|(signature, selector)| format!("{} {}", hex::encode(selector), signature))
+				.collect::<Vec<_>>()
+				.join("\n")),
+		}
+	}
+}
+
+fn help() -> String {
+	let usages: Vec<_> = COMMANDS.iter().map(|(name, usage, _)| format!("{} {}", name, usage)).collect();
+	let width = usages.iter().map(|usage| usage.trim_end().len()).max().unwrap_or_default();
+	let lines = COMMANDS
+		.iter()
+		.zip(&usages)
+		.map(|((_, _, about), usage)| format!("    {:width$}  {}", usage.trim_end(), about, width = width));
+	std::iter::once("Commands:".to_owned()).chain(lines).collect::<Vec<_>>().join("\n")
+}
+
+/// Splits a line into words separated by whitespace, in which double quoted params such as `"1.5 ether"`
+/// may contain whitespace.
+fn split_words(line: &str) -> anyhow::Result<Vec<String>> {
+	let mut words = Vec::new();
+	let mut word: Option<String> = None;
+	let mut quoted = false;
+
+	for c in line.chars() {
+		match c {
+			'"' => {
+				quoted = !quoted;
+				word.get_or_insert_with(String::new);
+			}
+			c if c.is_whitespace() && !quoted => words.extend(word.take()),
+			c => word.get_or_insert_with(String::new).push(c),
+		}
+	}
+
+	if quoted {
+		return Err(anyhow!("unterminated quote in `{}`", line));
+	}
+	words.extend(word);
+	Ok(words)
+}
+
+/// Completes the commands of the REPL and the names of the functions, events and errors of its ABIs.
+struct Completions {
+	names: Vec<String>,
+}
+
+impl Completions {
+	fn new(contract: &Contract) -> Self {
+		let mut names: Vec<String> = contract.functions.keys().chain(contract.events.keys()).cloned().collect();
+		names.extend(contract.errors.keys().cloned());
+		names.sort();
+		names.dedup();
+		Completions { names }
+	}
+
+	/// Returns the start of the word before the position and the words it may be completed to.
+	fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+		let start = line[..pos].rfind(char::is_whitespace).map_or(0, |space| space + 1);
+		let word = &line[start..pos];
+		let candidates: Vec<&str> = match line[..start].split_whitespace().collect::<Vec<_>>().as_slice() {
+			[] => COMMANDS.iter().map(|(name, _, _)| *name).collect(),
+			["encode"] | ["output"] | ["selector"] => self.names.iter().map(String::as_str).collect(),
+			_ => Vec::new(),
+		};
+		(start, candidates.into_iter().filter(|candidate| candidate.starts_with(word)).map(str::to_owned).collect())
+	}
+}
+
+impl Completer for Completions {
+	type Candidate = String;
+
+	fn complete(&self, line: &str, pos: usize, _: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+		Ok(self.candidates(line, pos))
+	}
+}
+
+impl Hinter for Completions {
+	type Hint = String;
+}
+
+impl Highlighter for Completions {}
+
+impl Validator for Completions {}
+
+impl Helper for Completions {}
+
+#[cfg(test)]
+mod tests {
+	use super::{split_words, Completions, Repl};
+	use crate::Format;
+	use ethabi::Contract;
+	use std::fs::File;
+
+	fn repl() -> Repl {
+		let contracts =
+			["../res/eip20.abi", "../res/event.abi"].map(|path| Contract::load(File::open(path).unwrap()).unwrap());
+		Repl::new(&contracts, Format { checksum: false, decimal: true, prefix: false }, false).unwrap()
+	}
+
+	#[test]
+	fn eval() {
+		let repl = repl();
+		let data = "a9059cbb0000000000000000000000004444444444444444444444444444444444444444\
+		            0000000000000000000000000000000000000000000000000000000000000064";
+		assert_eq!(repl.eval("encode transfer 0x4444444444444444444444444444444444444444 100").unwrap().unwrap(), data);
+		assert_eq!(
+			repl.eval(&format!("decode 0x{}", data)).unwrap().unwrap(),
+			"transfer(address,uint256):(bool)\n_to address 4444444444444444444444444444444444444444\n_value uint256 100"
+		);
+		assert!(repl.eval("output balanceOf 0x64").is_err());
+		assert_eq!(
+			repl.eval("output balanceOf 0000000000000000000000000000000000000000000000000000000000000064")
+				.unwrap()
+				.unwrap(),
+			"uint256 100"
+		);
+		assert_eq!(repl.eval("selector approve").unwrap().unwrap(), "095ea7b3 approve(address,uint256)");
+		assert_eq!(repl.eval("selector foo(uint)").unwrap().unwrap(), "2fbebd38 foo(uint256)");
+		assert_eq!(repl.eval("").unwrap().unwrap(), "");
+		assert_eq!(repl.eval("exit").unwrap(), None);
+		assert_eq!(repl.eval("decode").unwrap_err().to_string(), "usage: decode <data>");
+		assert_eq!(repl.eval("foo").unwrap_err().to_string(), "unknown command `foo`, try `help`");
+	}
+
+	#[test]
+	fn words() {
+		assert_eq!(split_words(r#"encode f "1.5 ether" "" 2"#).unwrap(), ["encode", "f", "1.5 ether", "", "2"]);
+		assert!(split_words(r#"encode f "1"#).is_err());
+	}
+
+	#[test]
+	fn completions() {
+		let completions = Completions::new(&repl().contract);
+		assert_eq!(completions.candidates("enc", 3), (0, vec!["encode".to_owned()]));
+		assert_eq!(
+			completions.candidates("encode tra", 10),
+			(7, vec!["transfer".to_owned(), "transferFrom".to_owned()])
+		);
+		assert_eq!(completions.candidates("selector E", 10), (9, vec!["Event".to_owned()]));
+		assert_eq!(completions.candidates("decode tra", 10), (7, vec![]));
+	}
+}
@@ -61,19 +61,28 @@ impl<'a> From<&'a ethabi::Constructor> for Constructor {
 }
 
 impl Constructor {
+	/// Quote used to recreate `ethabi::Constructor`.
+	pub fn recreate_quote(&self) -> TokenStream {
+		let recreate_inputs = &self.recreate_inputs;
+
+		quote! {
+			ethabi::Constructor {
+				inputs: #recreate_inputs,
+			}
+		}
+	}
+
 	/// Generates contract constructor interface.
 	pub fn generate(&self) -> TokenStream {
 		let declarations = &self.inputs_declarations;
 		let definitions = &self.inputs_definitions;
 		let tokenize = &self.tokenize;
-		let recreate_inputs = &self.recreate_inputs;
+		let recreate = self.recreate_quote();
 
 		quote! {
 			/// Encodes a call to contract's constructor.
 			pub fn constructor<#(#declarations),*>(#(#definitions),*) -> ethabi::Bytes {
-				let c = ethabi::Constructor {
-					inputs: #recreate_inputs,
-				};
+				let c = #recreate;
 				let tokens = vec![#(#tokenize),*];
 				c.encode_input(code, &tokens).expect(INTERNAL_ERR)
 			}
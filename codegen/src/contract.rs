@@ -0,0 +1,420 @@
+// Copyright 2015-2019 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{constructor::Constructor, event::Event, function::Function, quote_state_mutability, to_ethabi_param_vec};
+
+/// Structure used to generate rust interface for solidity contract.
+pub struct Contract {
+	constructor: Option<Constructor>,
+	functions: Vec<Function>,
+	events: Vec<Event>,
+	/// Quotes recreating the custom errors of the contract, which have no bindings.
+	errors: Vec<TokenStream>,
+	receive: bool,
+	fallback: Option<ethabi::StateMutability>,
+	embedded_abi: Option<(String, AbiFormat)>,
+	docs: Option<String>,
+	generate_tests: bool,
+}
+
+/// Format of an ABI file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbiFormat {
+	/// JSON array of functions and events.
+	Json,
+	/// Human-readable signatures, one per line.
+	Text,
+}
+
+impl<'a> From<&'a ethabi::Contract> for Contract {
+	fn from(c: &'a ethabi::Contract) -> Self {
+		Contract {
+			constructor: c.constructor.as_ref().map(Into::into),
+			functions: c.functions().map(Into::into).collect(),
+			events: c.events().map(Into::into).collect(),
+			errors: {
+				let mut errors: Vec<_> = c.errors().collect();
+				errors.sort_by_key(|error| error.signature());
+				errors.iter().map(|error| recreate_error(error)).collect()
+			},
+			receive: c.receive,
+			fallback: c.fallback,
+			embedded_abi: None,
+			docs: None,
+			generate_tests: false,
+		}
+	}
+}
+
+impl Contract {
+	/// Embeds the ABI file at `path` into the generated interface.
+	pub fn embed_abi(mut self, path: String, format: AbiFormat) -> Self {
+		self.embedded_abi = Some((path, format));
+		self
+	}
+
+	/// Sets default values of params, by function name and param name.
+	pub fn defaults(mut self, defaults: &HashMap<String, HashMap<String, String>>) -> Self {
+		for function in &mut self.functions {
+			if let Some(defaults) = defaults.get(function.name()) {
+				function.set_defaults(defaults);
+			}
+		}
+		self
+	}
+
+	/// Decodes the output of the functions named in `names` leniently, see `Function::lenient_bool`.
+	pub fn lenient_bool(mut self, names: &[String]) -> Self {
+		for function in &mut self.functions {
+			if names.iter().any(|name| name == function.name()) {
+				function.lenient_bool();
+			}
+		}
+		self
+	}
+
+	/// Reuses events and logs generated in `module` from `contract` instead of generating equal ones.
+	pub fn share_events(mut self, module: &syn::Path, contract: &ethabi::Contract) -> Self {
+		for event in &mut self.events {
+			event.share(module, contract);
+		}
+		self
+	}
+
+	/// Embeds a Markdown reference of the contract into the generated interface.
+	pub fn docs(mut self, markdown: String) -> Self {
+		self.docs = Some(markdown);
+		self
+	}
+
+	/// Adds round trip tests of every function and event to the generated interface.
+	pub fn generate_tests(mut self) -> Self {
+		self.generate_tests = true;
+		self
+	}
+
+	/// Generates rust interface for a contract.
+	pub fn generate(&self) -> TokenStream {
+		let constructor = self.constructor.as_ref().map(Constructor::generate);
+		let functions: Vec<_> = self.functions.iter().map(Function::generate).collect();
+		let events: Vec<_> = self.events.iter().map(Event::generate_event).collect();
+		let logs: Vec<_> = self.events.iter().map(Event::generate_log).collect();
+		let embedded_abi = self.embedded_abi.as_ref().map(|(path, format)| generate_embedded_abi(path, *format));
+		let abi = self.generate_abi();
+		let docs = self.docs.as_ref().map(|markdown| {
+			quote! {
+				/// Markdown reference of the contract, generated from the same ABI as the bindings.
+				pub const DOCS: &'static str = #markdown;
+			}
+		});
+		let tests = match self.generate_tests {
+			true => Some(self.generate_tests_module()),
+			false => None,
+		};
+		quote! {
+			use ethabi;
+			const INTERNAL_ERR: &'static str = "`ethabi_derive` internal error";
+
+			#embedded_abi
+
+			#docs
+
+			#abi
+
+			#constructor
+
+			/// Contract's functions.
+			pub mod functions {
+				use super::INTERNAL_ERR;
+				#(#functions)*
+			}
+
+			/// Contract's events.
+			pub mod events {
+				use super::INTERNAL_ERR;
+				#(#events)*
+			}
+
+			/// Contract's logs.
+			pub mod logs {
+				use super::INTERNAL_ERR;
+				use ethabi;
+				#(#logs)*
+			}
+
+			#tests
+		}
+	}
+
+	/// Generates tests of the generated interface.
+	fn generate_tests_module(&self) -> TokenStream {
+		let function_tests = self.functions.iter().map(Function::generate_test);
+		let event_tests = self.events.iter().map(Event::generate_test);
+
+		quote! {
+			#[cfg(test)]
+			mod generated_tests {
+				mod functions {
+					#(#function_tests)*
+				}
+
+				mod events {
+					#(#event_tests)*
+				}
+			}
+		}
+	}
+
+	/// Generates accessors describing the contract, recreated from the generated bindings.
+	fn generate_abi(&self) -> TokenStream {
+		let constructor = match self.constructor {
+			Some(ref constructor) => {
+				let recreate = constructor.recreate_quote();
+				quote! { Some(#recreate) }
+			}
+			None => quote! { None },
+		};
+		let function_modules: Vec<_> = self.functions.iter().map(Function::module_name).collect();
+		let event_modules: Vec<_> = self.events.iter().map(Event::module_name).collect();
+		let mut function_names: Vec<_> = self.functions.iter().map(Function::name).collect();
+		function_names.sort_unstable();
+		let mut event_names: Vec<_> = self.events.iter().map(Event::name).collect();
+		event_names.sort_unstable();
+		let receive = self.receive;
+		let fallback = match self.fallback {
+			Some(state_mutability) => {
+				let state_mutability = quote_state_mutability(state_mutability);
+				quote! { Some(#state_mutability) }
+			}
+			None => quote! { None },
+		};
+
+		let init = match self.embedded_abi {
+			Some((_, AbiFormat::Json)) => {
+				quote! { ethabi::Contract::load(ABI_JSON.as_bytes()).expect(INTERNAL_ERR) }
+			}
+			Some((_, AbiFormat::Text)) => {
+				quote! { ethabi::Contract::load_text(ABI_TEXT.as_bytes()).expect(INTERNAL_ERR) }
+			}
+			None => {
+				let contract = quote! {
+					ethabi::Contract {
+						constructor: #constructor,
+						receive: #receive,
+						fallback: #fallback,
+						..Default::default()
+					}
+				};
+				match function_modules.is_empty() && event_modules.is_empty() && self.errors.is_empty() {
+					true => contract,
+					false => {
+						let errors = &self.errors;
+						quote! {{
+							let mut contract = #contract;
+							#(
+								let function = functions::#function_modules::function();
+								contract.functions.entry(function.name.clone()).or_default().push(function);
+							)*
+							#(
+								let event = events::#event_modules::event();
+								contract.events.entry(event.name.clone()).or_default().push(event);
+							)*
+							#(
+								let error = #errors;
+								contract.errors.entry(error.name.clone()).or_default().push(error);
+							)*
+							contract
+						}}
+					}
+				}
+			}
+		};
+
+		quote! {
+			/// Returns contract's ABI, parsed from the embedded ABI or recreated from the bindings on first use.
+			pub fn abi() -> &'static ethabi::Contract {
+				static ABI: std::sync::OnceLock<ethabi::Contract> = std::sync::OnceLock::new();
+				ABI.get_or_init(|| #init)
+			}
+
+			/// Returns names of contract's functions.
+			pub fn functions_list() -> &'static [&'static str] {
+				&[#(#function_names),*]
+			}
+
+			/// Returns names of contract's events.
+			pub fn events_list() -> &'static [&'static str] {
+				&[#(#event_names),*]
+			}
+		}
+	}
+}
+
+fn recreate_error(error: &ethabi::AbiError) -> TokenStream {
+	let name = &error.name;
+	let inputs = to_ethabi_param_vec(&error.inputs);
+	quote! {
+		ethabi::AbiError {
+			name: #name.into(),
+			inputs: #inputs,
+		}
+	}
+}
+
+fn generate_embedded_abi(path: &str, format: AbiFormat) -> TokenStream {
+	let abi = match format {
+		AbiFormat::Json => quote! {
+			/// Contract's ABI in JSON format.
+			pub const ABI_JSON: &'static str = include_str!(#path);
+		},
+		AbiFormat::Text => quote! {
+			/// Contract's ABI as human-readable signatures.
+			pub const ABI_TEXT: &'static str = include_str!(#path);
+		},
+	};
+
+	quote! {
+		#abi
+
+		/// Returns contract's ABI, parsed on first use, same as `abi()`.
+		pub fn contract() -> &'static ethabi::Contract {
+			abi()
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use quote::quote;
+
+	use super::{AbiFormat, Contract};
+
+	#[test]
+	fn test_no_body() {
+		let ethabi_contract = ethabi::Contract {
+			constructor: None,
+			functions: Default::default(),
+			events: Default::default(),
+			errors: Default::default(),
+			receive: false,
+			fallback: None,
+		};
+
+		let c = Contract::from(&ethabi_contract);
+
+		let expected = quote! {
+			use ethabi;
+			const INTERNAL_ERR: &'static str = "`ethabi_derive` internal error";
+
+			/// Returns contract's ABI, parsed from the embedded ABI or recreated from the bindings on first use.
+			pub fn abi() -> &'static ethabi::Contract {
+				static ABI: std::sync::OnceLock<ethabi::Contract> = std::sync::OnceLock::new();
+				ABI.get_or_init(|| ethabi::Contract {
+					constructor: None,
+					receive: false,
+					fallback: None,
+					..Default::default()
+				})
+			}
+
+			/// Returns names of contract's functions.
+			pub fn functions_list() -> &'static [&'static str] {
+				&[]
+			}
+
+			/// Returns names of contract's events.
+			pub fn events_list() -> &'static [&'static str] {
+				&[]
+			}
+
+			/// Contract's functions.
+			pub mod functions {
+				use super::INTERNAL_ERR;
+			}
+
+			/// Contract's events.
+			pub mod events {
+				use super::INTERNAL_ERR;
+			}
+
+			/// Contract's logs.
+			pub mod logs {
+				use super::INTERNAL_ERR;
+				use ethabi;
+			}
+		};
+
+		assert_eq!(expected.to_string(), c.generate().to_string());
+	}
+
+	#[test]
+	fn test_embedded_abi() {
+		let ethabi_contract = ethabi::Contract {
+			constructor: None,
+			functions: Default::default(),
+			events: Default::default(),
+			errors: Default::default(),
+			receive: false,
+			fallback: None,
+		};
+
+		let c = Contract::from(&ethabi_contract).embed_abi("/abi/foo.abi".into(), AbiFormat::Json);
+
+		let expected = quote! {
+			use ethabi;
+			const INTERNAL_ERR: &'static str = "`ethabi_derive` internal error";
+
+			/// Contract's ABI in JSON format.
+			pub const ABI_JSON: &'static str = include_str!("/abi/foo.abi");
+
+			/// Returns contract's ABI, parsed on first use, same as `abi()`.
+			pub fn contract() -> &'static ethabi::Contract {
+				abi()
+			}
+
+			/// Returns contract's ABI, parsed from the embedded ABI or recreated from the bindings on first use.
+			pub fn abi() -> &'static ethabi::Contract {
+				static ABI: std::sync::OnceLock<ethabi::Contract> = std::sync::OnceLock::new();
+				ABI.get_or_init(|| ethabi::Contract::load(ABI_JSON.as_bytes()).expect(INTERNAL_ERR))
+			}
+
+			/// Returns names of contract's functions.
+			pub fn functions_list() -> &'static [&'static str] {
+				&[]
+			}
+
+			/// Returns names of contract's events.
+			pub fn events_list() -> &'static [&'static str] {
+				&[]
+			}
+
+			/// Contract's functions.
+			pub mod functions {
+				use super::INTERNAL_ERR;
+			}
+
+			/// Contract's events.
+			pub mod events {
+				use super::INTERNAL_ERR;
+			}
+
+			/// Contract's logs.
+			pub mod logs {
+				use super::INTERNAL_ERR;
+				use ethabi;
+			}
+		};
+
+		assert_eq!(expected.to_string(), c.generate().to_string());
+	}
+}
@@ -0,0 +1,188 @@
+// Copyright 2015-2019 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt::Write;
+
+use ethabi::{param_type::Writer, Contract, Param, ParamType, StateMutability};
+
+/// Renders a Markdown reference of the contract, with a section per function and event.
+pub fn markdown(title: &str, contract: &Contract) -> String {
+	let mut out = format!("# {}\n\n", title);
+
+	if let Some(constructor) = contract.constructor() {
+		out.push_str("## Constructor\n\n");
+		write_params(&mut out, "Input", &constructor.inputs);
+	}
+
+	let mut functions: Vec<_> = contract.functions().collect();
+	functions.sort_by_key(|function| (function.name.clone(), canonical(&function.name, &function.inputs)));
+	if !functions.is_empty() {
+		out.push_str("## Functions\n\n");
+	}
+	for function in functions {
+		let selector: String = function.selector().iter().map(|byte| format!("{:02x}", byte)).collect();
+		let _ = write!(
+			out,
+			"### `{}`\n\n`{}`, selector `0x{}`, {}\n\n",
+			function.name,
+			canonical(&function.name, &function.inputs),
+			selector,
+			state_mutability(function.state_mutability()),
+		);
+		write_params(&mut out, "Input", &function.inputs);
+		write_params(&mut out, "Output", &function.outputs);
+	}
+
+	let mut events: Vec<_> = contract.events().collect();
+	events.sort_by_key(|event| (event.name.clone(), event.topic0()));
+	if !events.is_empty() {
+		out.push_str("## Events\n\n");
+	}
+	for event in events {
+		let kinds: Vec<_> = event.inputs.iter().map(|param| param.kind.clone()).collect();
+		let signature = format!("{}({})", event.name, kinds.iter().map(Writer::write).collect::<Vec<_>>().join(","));
+		let _ = match event.anonymous {
+			true => write!(out, "### `{}`\n\n`{}`, anonymous\n\n", event.name, signature),
+			false => write!(out, "### `{}`\n\n`{}`, topic0 `0x{:x}`\n\n", event.name, signature, event.topic0()),
+		};
+		if !event.inputs.is_empty() {
+			out.push_str("| Param | Type | Indexed |\n| --- | --- | --- |\n");
+			for (index, param) in event.inputs.iter().enumerate() {
+				let _ = writeln!(
+					out,
+					"| {} | `{}` | {} |",
+					param_name(&param.name, index),
+					Writer::write(&param.kind),
+					param.indexed
+				);
+			}
+			out.push('\n');
+		}
+	}
+
+	let mut errors: Vec<_> = contract.errors().collect();
+	errors.sort_by_key(|error| error.signature());
+	if !errors.is_empty() {
+		out.push_str("## Errors\n\n");
+	}
+	for error in errors {
+		let selector: String = error.selector().iter().map(|byte| format!("{:02x}", byte)).collect();
+		let _ = write!(out, "### `{}`\n\n`{}`, selector `0x{}`\n\n", error.name, error.signature(), selector);
+		write_params(&mut out, "Input", &error.inputs);
+	}
+
+	out.truncate(out.trim_end().len());
+	out.push('\n');
+	out
+}
+
+fn canonical(name: &str, params: &[Param]) -> String {
+	let kinds: Vec<ParamType> = params.iter().map(|param| param.kind.clone()).collect();
+	format!("{}({})", name, kinds.iter().map(Writer::write).collect::<Vec<_>>().join(","))
+}
+
+fn write_params(out: &mut String, header: &str, params: &[Param]) {
+	if params.is_empty() {
+		return;
+	}
+
+	let _ = write!(out, "| {} | Type |\n| --- | --- |\n", header);
+	for (index, param) in params.iter().enumerate() {
+		let _ = writeln!(out, "| {} | `{}` |", param_name(&param.name, index), Writer::write(&param.kind));
+	}
+	out.push('\n');
+}
+
+fn state_mutability(state_mutability: StateMutability) -> &'static str {
+	match state_mutability {
+		StateMutability::Pure => "pure",
+		StateMutability::View => "view",
+		StateMutability::NonPayable => "nonpayable",
+		StateMutability::Payable => "payable",
+	}
+}
+
+fn param_name(name: &str, index: usize) -> String {
+	match name.is_empty() {
+		true => format!("#{}", index),
+		false => format!("`{}`", name),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::markdown;
+
+	#[test]
+	fn test_markdown() {
+		let contract = ethabi::Contract::load_text(
+			"constructor(string name)\n\
+			function transfer(address to, uint256 amount) returns (bool)\n\
+			function totalSupply() view returns (uint256)\n\
+			event Transfer(address indexed from, address indexed to, uint256)\n\
+			error Unauthorized(address caller)"
+				.as_bytes(),
+		)
+		.unwrap();
+
+		let expected = "# Token
+
+## Constructor
+
+| Input | Type |
+| --- | --- |
+| `name` | `string` |
+
+## Functions
+
+### `totalSupply`
+
+`totalSupply()`, selector `0x18160ddd`, view
+
+| Output | Type |
+| --- | --- |
+| #0 | `uint256` |
+
+### `transfer`
+
+`transfer(address,uint256)`, selector `0xa9059cbb`, nonpayable
+
+| Input | Type |
+| --- | --- |
+| `to` | `address` |
+| `amount` | `uint256` |
+
+| Output | Type |
+| --- | --- |
+| #0 | `bool` |
+
+## Events
+
+### `Transfer`
+
+`Transfer(address,address,uint256)`, topic0 `0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef`
+
+| Param | Type | Indexed |
+| --- | --- | --- |
+| `from` | `address` | true |
+| `to` | `address` | true |
+| #2 | `uint256` | false |
+
+## Errors
+
+### `Unauthorized`
+
+`Unauthorized(address)`, selector `0x8e4a23d6`
+
+| Input | Type |
+| --- | --- |
+| `caller` | `address` |
+";
+		assert_eq!(markdown("Token", &contract), expected);
+	}
+}
@@ -6,11 +6,12 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use ethabi::ParamType;
 use heck::{CamelCase, SnakeCase};
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 
-use super::{from_token, get_template_names, rust_type, to_syntax_string, to_token};
+use super::{from_token, get_template_names, mock_token, rust_type, to_syntax_string, to_token};
 
 /// Structure used to generate contract's event interface.
 pub struct Event {
@@ -21,8 +22,13 @@ pub struct Event {
 	wildcard_filter_params: Vec<TokenStream>,
 	filter_declarations: Vec<TokenStream>,
 	filter_definitions: Vec<TokenStream>,
-	filter_init: Vec<TokenStream>,
+	raw_filter: TokenStream,
 	anonymous: bool,
+	mock_inputs: Vec<TokenStream>,
+	/// Event as declared in the ABI.
+	abi: ethabi::Event,
+	/// Module generated for another contract which declares the same event.
+	shared: Option<syn::Path>,
 }
 
 impl<'a> From<&'a ethabi::Event> for Event {
@@ -43,13 +49,22 @@ impl<'a> From<&'a ethabi::Event> for Event {
 				}
 			})
 			.collect();
-		let kinds: Vec<_> = e.inputs.iter().map(|param| rust_type(&param.kind)).collect();
+		// indexed params of non-value types are logged as the hash of their value
+		let log_kinds: Vec<_> = e
+			.inputs
+			.iter()
+			.map(|param| match param.is_hashed() {
+				true => ParamType::FixedBytes(32),
+				false => param.kind.clone(),
+			})
+			.collect();
+		let kinds: Vec<_> = log_kinds.iter().map(rust_type).collect();
 		let log_fields =
 			names.iter().zip(kinds.iter()).map(|(param_name, kind)| quote! { pub #param_name: #kind }).collect();
 
 		let log_iter = quote! { log.next().expect(INTERNAL_ERR).value };
 
-		let to_log: Vec<_> = e.inputs.iter().map(|param| from_token(&param.kind, &log_iter)).collect();
+		let to_log: Vec<_> = log_kinds.iter().map(|kind| from_token(kind, &log_iter)).collect();
 
 		let log_init =
 			names.iter().zip(to_log.iter()).map(|(param_name, convert)| quote! { #param_name: #convert }).collect();
@@ -88,18 +103,25 @@ impl<'a> From<&'a ethabi::Event> for Event {
 		// The number of parameters that creates a filter which matches anything.
 		let wildcard_filter_params: Vec<_> = filter_definitions.iter().map(|_| quote! { ethabi::Topic::Any }).collect();
 
-		let filter_init: Vec<_> = topic_names
+		let mut topics: Vec<_> = topic_names
 			.iter()
 			.zip(e.inputs.iter().filter(|p| p.indexed))
-			.enumerate()
-			.take(3)
-			.map(|(index, (param_name, param))| {
-				let topic = syn::Ident::new(&format!("topic{}", index), Span::call_site());
+			.take(if e.anonymous { 4 } else { 3 })
+			.map(|(param_name, param)| {
 				let i = quote! { i };
 				let to_token = to_token(&i, &param.kind);
-				quote! { #topic: #param_name.into().map(|#i| #to_token), }
+				quote! { #param_name.into().map(|#i| #to_token) }
 			})
 			.collect();
+		let raw_filter = match topics.len() {
+			0 => quote! { ethabi::RawTopicFilter::default() },
+			len => {
+				let topic3 = (len == 4).then(|| topics.pop());
+				topics.resize(3, quote! { ethabi::Topic::Any });
+				let topic3 = topic3.map(|topic| quote! { .with_topic3(#topic) });
+				quote! { ethabi::RawTopicFilter::new(#(#topics),*)#topic3 }
+			}
+		};
 
 		let event_inputs = &e
 			.inputs
@@ -129,38 +151,111 @@ impl<'a> From<&'a ethabi::Event> for Event {
 			wildcard_filter_params,
 			filter_declarations,
 			filter_definitions,
-			filter_init,
+			raw_filter,
+			mock_inputs: e.inputs.iter().map(|param| mock_token(&param.kind)).collect(),
+			abi: e.clone(),
+			shared: None,
 		}
 	}
 }
 
 impl Event {
+	/// Reuses the event and log generated in `module` if it declares the same event.
+	///
+	/// `contract` is the ABI `module` was generated from.
+	pub fn share(&mut self, module: &syn::Path, contract: &ethabi::Contract) {
+		if contract.events().any(|event| *event == self.abi) {
+			self.shared = Some(module.clone());
+		}
+	}
+
 	/// Generates event log struct.
 	pub fn generate_log(&self) -> TokenStream {
 		let name = syn::Ident::new(&self.name.to_camel_case(), Span::call_site());
+		let module_name = self.module_name();
 		let log_fields = &self.log_fields;
 
+		if let Some(ref shared) = self.shared {
+			return quote! {
+				pub use #shared::logs::#name;
+			};
+		}
+
 		quote! {
 			#[derive(Debug, Clone, PartialEq)]
 			pub struct #name {
 				#(#log_fields),*
 			}
+
+			impl ethabi::EventLog for #name {
+				fn event() -> ethabi::Event {
+					super::events::#module_name::event()
+				}
+
+				fn parse_log(log: ethabi::RawLog) -> ethabi::Result<Self> {
+					super::events::#module_name::parse_log(log)
+				}
+			}
+		}
+	}
+
+	/// Name of the generated event module.
+	pub fn module_name(&self) -> syn::Ident {
+		syn::Ident::new(&self.name.to_snake_case(), Span::call_site())
+	}
+
+	/// Event name as recreated by the generated interface.
+	pub fn name(&self) -> String {
+		self.name.to_camel_case()
+	}
+
+	/// Generates a test parsing a log of mock values with the generated interface.
+	pub fn generate_test(&self) -> TokenStream {
+		let module_name = self.module_name();
+		let mock_inputs = &self.mock_inputs;
+
+		quote! {
+			#[test]
+			fn #module_name() {
+				let event = super::super::events::#module_name::event();
+				let values = vec![#(#mock_inputs),*];
+				let mut topics = Vec::new();
+				if !event.anonymous {
+					topics.push(event.topic0());
+				}
+				let mut data = Vec::new();
+				for (param, value) in event.inputs.iter().zip(values) {
+					match param.indexed {
+						true => topics.push(event.topic_for(&param.name, &value).expect("mock value must match the event")),
+						false => data.push(value),
+					}
+				}
+
+				let log = ethabi::RawLog { topics, data: ethabi::encode(&data) };
+				super::super::events::#module_name::parse_log(log).expect("mock log must parse");
+			}
 		}
 	}
 
 	/// Generates rust interface for contract's event.
 	pub fn generate_event(&self) -> TokenStream {
-		let name_as_string = &self.name.to_camel_case();
-		let name = syn::Ident::new(&self.name.to_snake_case(), Span::call_site());
+		let name_as_string = &self.name();
+		let name = self.module_name();
 		let camel_name = syn::Ident::new(&self.name.to_camel_case(), Span::call_site());
 		let recreate_inputs_quote = &self.recreate_inputs_quote;
 		let anonymous = &self.anonymous;
 		let log_init = &self.log_init;
-		let filter_init = &self.filter_init;
+		let raw_filter = &self.raw_filter;
 		let filter_declarations = &self.filter_declarations;
 		let filter_definitions = &self.filter_definitions;
 		let wildcard_filter_params = &self.wildcard_filter_params;
 
+		if let Some(ref shared) = self.shared {
+			return quote! {
+				pub use #shared::events::#name;
+			};
+		}
+
 		quote! {
 			pub mod #name {
 				use ethabi;
@@ -174,11 +269,14 @@ impl Event {
 					}
 				}
 
+				/// Returns the first topic of the event's logs, computed once.
+				pub fn topic0() -> ethabi::Hash {
+					static TOPIC0: std::sync::OnceLock<ethabi::Hash> = std::sync::OnceLock::new();
+					*TOPIC0.get_or_init(|| event().topic0())
+				}
+
 				pub fn filter<#(#filter_declarations),*>(#(#filter_definitions),*) -> ethabi::TopicFilter {
-					let raw = ethabi::RawTopicFilter {
-						#(#filter_init)*
-						..Default::default()
-					};
+					let raw = #raw_filter;
 
 					let e = event();
 					e.filter(raw).expect(INTERNAL_ERR)
@@ -215,11 +313,36 @@ mod tests {
 		let expected = quote! {
 			#[derive(Debug, Clone, PartialEq)]
 			pub struct Hello {}
+
+			impl ethabi::EventLog for Hello {
+				fn event() -> ethabi::Event {
+					super::events::hello::event()
+				}
+
+				fn parse_log(log: ethabi::RawLog) -> ethabi::Result<Self> {
+					super::events::hello::parse_log(log)
+				}
+			}
 		};
 
 		assert_eq!(expected.to_string(), e.generate_log().to_string());
 	}
 
+	#[test]
+	fn test_shared_event() {
+		let ethabi_event = ethabi::Event { name: "hello".into(), inputs: vec![], anonymous: false };
+		let other = ethabi::Event { name: "hello".into(), inputs: vec![], anonymous: true };
+		let module: syn::Path = syn::parse_str("crate::common").unwrap();
+
+		let mut e = Event::from(&ethabi_event);
+		e.share(&module, &ethabi::Contract::builder().add_event(other).build());
+		assert!(e.shared.is_none());
+
+		e.share(&module, &ethabi::Contract::builder().add_event(ethabi_event.clone()).build());
+		assert_eq!(e.generate_log().to_string(), quote! { pub use crate::common::logs::Hello; }.to_string());
+		assert_eq!(e.generate_event().to_string(), quote! { pub use crate::common::events::hello; }.to_string());
+	}
+
 	#[test]
 	fn test_empty_event() {
 		let ethabi_event = ethabi::Event { name: "hello".into(), inputs: vec![], anonymous: false };
@@ -239,10 +362,14 @@ mod tests {
 					}
 				}
 
+				/// Returns the first topic of the event's logs, computed once.
+				pub fn topic0() -> ethabi::Hash {
+					static TOPIC0: std::sync::OnceLock<ethabi::Hash> = std::sync::OnceLock::new();
+					*TOPIC0.get_or_init(|| event().topic0())
+				}
+
 				pub fn filter<>() -> ethabi::TopicFilter {
-					let raw = ethabi::RawTopicFilter {
-						..Default::default()
-					};
+					let raw = ethabi::RawTopicFilter::default();
 
 					let e = event();
 					e.filter(raw).expect(INTERNAL_ERR)
@@ -291,11 +418,18 @@ mod tests {
 					}
 				}
 
+				/// Returns the first topic of the event's logs, computed once.
+				pub fn topic0() -> ethabi::Hash {
+					static TOPIC0: std::sync::OnceLock<ethabi::Hash> = std::sync::OnceLock::new();
+					*TOPIC0.get_or_init(|| event().topic0())
+				}
+
 				pub fn filter<T0: Into<ethabi::Topic<ethabi::Address>>>(foo: T0) -> ethabi::TopicFilter {
-					let raw = ethabi::RawTopicFilter {
-						topic0: foo.into().map(|i| ethabi::Token::Address(i)),
-						..Default::default()
-					};
+					let raw = ethabi::RawTopicFilter::new(
+						foo.into().map(|i| ethabi::Token::Address(i)),
+						ethabi::Topic::Any,
+						ethabi::Topic::Any
+					);
 
 					let e = event();
 					e.filter(raw).expect(INTERNAL_ERR)
@@ -334,6 +468,16 @@ mod tests {
 			pub struct One {
 				pub foo: ethabi::Address
 			}
+
+			impl ethabi::EventLog for One {
+				fn event() -> ethabi::Event {
+					super::events::one::event()
+				}
+
+				fn parse_log(log: ethabi::RawLog) -> ethabi::Result<Self> {
+					super::events::one::parse_log(log)
+				}
+			}
 		};
 
 		assert_eq!(expected.to_string(), e.generate_log().to_string());
@@ -364,6 +508,16 @@ mod tests {
 				pub bar: Vec<String>,
 				pub xyz: ethabi::Uint
 			}
+
+			impl ethabi::EventLog for Many {
+				fn event() -> ethabi::Event {
+					super::events::many::event()
+				}
+
+				fn parse_log(log: ethabi::RawLog) -> ethabi::Result<Self> {
+					super::events::many::parse_log(log)
+				}
+			}
 		};
 
 		assert_eq!(expected.to_string(), e.generate_log().to_string());
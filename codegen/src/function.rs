@@ -6,13 +6,15 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::collections::HashMap;
+
 use heck::SnakeCase;
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 
 use super::{
-	from_template_param, from_token, get_output_kinds, get_template_names, input_names, rust_type, template_param_type,
-	to_ethabi_param_vec, to_token,
+	from_template_param, from_token, get_output_kinds, get_template_names, input_names, mock_token, mock_value,
+	quote_state_mutability, rust_type, template_param_type, to_ethabi_param_vec, to_token,
 };
 
 struct TemplateParam {
@@ -31,6 +33,16 @@ struct TemplateParam {
 }
 
 struct Inputs {
+	/// Param names as rust identifiers.
+	///
+	/// ```text
+	/// [param0, hello_world, param2]
+	/// ```
+	names: Vec<syn::Ident>,
+	/// Param names as declared in the ABI.
+	abi_names: Vec<String>,
+	/// Default values of params, parsed with `LenientTokenizer`.
+	defaults: Vec<Option<String>>,
 	/// Collects template params into vector.
 	///
 	/// ```text
@@ -41,6 +53,10 @@ struct Inputs {
 	template_params: Vec<TemplateParam>,
 	/// Quote used to recreate `Vec<ethabi::Param>`
 	recreate_quote: TokenStream,
+	/// Arbitrary tokens used by generated tests.
+	mock: Vec<TokenStream>,
+	/// Values of the template params with the same content as `mock`.
+	mock_values: Vec<TokenStream>,
 }
 
 struct Outputs {
@@ -50,6 +66,10 @@ struct Outputs {
 	result: TokenStream,
 	/// Quote used to recreate `Vec<ethabi::Param>`.
 	recreate_quote: TokenStream,
+	/// Arbitrary tokens used by generated tests.
+	mock: Vec<TokenStream>,
+	/// Decode result of `mock`.
+	mock_result: TokenStream,
 }
 
 /// Structure used to generate contract's function interface.
@@ -67,6 +87,8 @@ pub struct Function {
 	constant: bool,
 	/// Whether the function reads or modifies blockchain state
 	state_mutability: ethabi::StateMutability,
+	/// Whether the function does not modify blockchain state, also considering `constant`.
+	read_only: bool,
 }
 
 impl<'a> From<&'a ethabi::Function> for Function {
@@ -102,6 +124,13 @@ impl<'a> From<&'a ethabi::Function> for Function {
 			.collect();
 
 		let output_result = get_output_kinds(&f.outputs);
+		let get_mock_result = |outputs: &[ethabi::Param]| {
+			let values: Vec<_> = outputs.iter().map(|param| mock_value(&param.kind)).collect();
+			match values.len() {
+				1 => values[0].clone(),
+				_ => quote! { (#(#values),*) },
+			}
+		};
 
 		let output_implementation = match f.outputs.len() {
 			0 => quote! {
@@ -133,23 +162,178 @@ impl<'a> From<&'a ethabi::Function> for Function {
 		#[allow(deprecated)]
 		Function {
 			name: f.name.clone(),
-			inputs: Inputs { tokenize, template_params, recreate_quote: to_ethabi_param_vec(&f.inputs) },
+			inputs: Inputs {
+				names: input_names,
+				abi_names: f.inputs.iter().map(|param| param.name.clone()).collect(),
+				defaults: vec![None; f.inputs.len()],
+				tokenize,
+				template_params,
+				recreate_quote: to_ethabi_param_vec(&f.inputs),
+				mock: f.inputs.iter().map(|param| mock_token(&param.kind)).collect(),
+				mock_values: f.inputs.iter().map(|param| mock_value(&param.kind)).collect(),
+			},
 			outputs: Outputs {
 				implementation: output_implementation,
 				result: output_result,
 				recreate_quote: to_ethabi_param_vec(&f.outputs),
+				mock: f.outputs.iter().map(|param| mock_token(&param.kind)).collect(),
+				mock_result: get_mock_result(&f.outputs),
 			},
 			constant: f.constant,
 			state_mutability: f.state_mutability,
+			read_only: f.state_mutability().is_read_only(),
 		}
 	}
 }
 
 impl Function {
+	/// Name of the generated function module.
+	pub fn module_name(&self) -> syn::Ident {
+		syn::Ident::new(&self.name.to_snake_case(), Span::call_site())
+	}
+
+	/// Function name as declared in the ABI.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Sets default values of params by their name, which enables generating a call builder.
+	///
+	/// Values must be valid for `LenientTokenizer`.
+	pub fn set_defaults(&mut self, defaults: &HashMap<String, String>) {
+		for (name, default) in self.inputs.abi_names.iter().zip(self.inputs.defaults.iter_mut()) {
+			*default = defaults.get(name).cloned();
+		}
+	}
+
+	/// Decodes the output with `ethabi::decode_bool_or_empty`, for tokens which return
+	/// nothing instead of `true`.
+	///
+	/// The function must return a single `bool` or nothing.
+	pub fn lenient_bool(&mut self) {
+		self.outputs.implementation = quote! { ethabi::decode_bool_or_empty(output) };
+		self.outputs.result = quote! { ethabi::BoolOutput };
+		self.outputs.mock_result = match self.outputs.mock.is_empty() {
+			true => quote! { ethabi::BoolOutput::NoReturnData },
+			false => quote! { ethabi::BoolOutput::Success },
+		};
+	}
+
+	/// Generates a builder of the function input, which allows to omit params with defaults.
+	fn generate_builder(&self) -> Option<TokenStream> {
+		if self.inputs.defaults.iter().all(Option::is_none) {
+			return None;
+		}
+
+		let names = &self.inputs.names;
+		let setters = self.inputs.template_params.iter().zip(&self.inputs.tokenize).zip(names).map(
+			|((template_param, tokenize), name)| {
+				let declaration = &template_param.declaration;
+				let definition = &template_param.definition;
+				let doc = format!("Sets `{}`.", name);
+				quote! {
+					#[doc = #doc]
+					pub fn #name<#declaration>(mut self, #definition) -> Self {
+						self.#name = Some(#tokenize);
+						self
+					}
+				}
+			},
+		);
+		let default_tokens: Vec<_> = self
+			.inputs
+			.defaults
+			.iter()
+			.enumerate()
+			.map(|(index, default)| {
+				default.as_ref().map(|default| {
+					quote! {
+						<ethabi::token::LenientTokenizer as ethabi::token::Tokenizer>::tokenize(
+							&function().inputs[#index].kind,
+							#default,
+						)
+						.expect(INTERNAL_ERR)
+					}
+				})
+			})
+			.collect();
+		let default_setters = names.iter().zip(&default_tokens).filter_map(|(name, default_token)| {
+			let default_token = default_token.as_ref()?;
+			let setter = syn::Ident::new(&format!("{}_default", name), Span::call_site());
+			let doc = format!("Sets `{}` to its default value.", name);
+			Some(quote! {
+				#[doc = #doc]
+				pub fn #setter(mut self) -> Self {
+					self.#name = Some(#default_token);
+					self
+				}
+			})
+		});
+		let tokens = names.iter().zip(&default_tokens).map(|(name, default_token)| match default_token {
+			Some(default_token) => quote! { self.#name.unwrap_or_else(|| #default_token) },
+			None => {
+				let missing = name.to_string();
+				quote! { self.#name.ok_or_else(|| ethabi::Error::InvalidName(#missing.into()))? }
+			}
+		});
+
+		Some(quote! {
+			/// Starts building function input, params with defaults may be omitted.
+			pub fn builder() -> Builder {
+				Builder::default()
+			}
+
+			/// Function input builder.
+			#[derive(Debug, Clone, Default)]
+			pub struct Builder {
+				#(#names: Option<ethabi::Token>),*
+			}
+
+			impl Builder {
+				#(#setters)*
+
+				#(#default_setters)*
+
+				/// Encodes function input, using defaults of params which were not set.
+				///
+				/// Fails with the name of the first missing param without a default.
+				pub fn build(self) -> ethabi::Result<ethabi::Bytes> {
+					let tokens = vec![#(#tokens),*];
+					function().encode_input(&tokens)
+				}
+			}
+		})
+	}
+
+	/// Generates a test encoding and decoding mock values with the generated interface.
+	pub fn generate_test(&self) -> TokenStream {
+		let module_name = self.module_name();
+		let mock_inputs = &self.inputs.mock;
+		let mock_values = &self.inputs.mock_values;
+		let mock_outputs = &self.outputs.mock;
+		let mock_result = &self.outputs.mock_result;
+
+		quote! {
+			#[test]
+			fn #module_name() {
+				let calldata = super::super::functions::#module_name::encode_input(#(#mock_values),*);
+				let function = super::super::functions::#module_name::function();
+				assert_eq!(function.decode_calldata(&calldata).expect("calldata must decode"), vec![#(#mock_inputs),*]);
+
+				let outputs = vec![#(#mock_outputs),*];
+				assert_eq!(
+					super::super::functions::#module_name::decode_output(&ethabi::encode(&outputs))
+						.expect("mock outputs must decode"),
+					#mock_result
+				);
+			}
+		}
+	}
+
 	/// Generates the interface for contract's function.
 	pub fn generate(&self) -> TokenStream {
 		let name = &self.name;
-		let module_name = syn::Ident::new(&self.name.to_snake_case(), Span::call_site());
+		let module_name = self.module_name();
 		let tokenize = &self.inputs.tokenize;
 		let declarations: &Vec<_> = &self.inputs.template_params.iter().map(|i| &i.declaration).collect();
 		let definitions: &Vec<_> = &self.inputs.template_params.iter().map(|i| &i.definition).collect();
@@ -157,21 +341,18 @@ impl Function {
 		let recreate_outputs = &self.outputs.recreate_quote;
 		#[allow(deprecated)]
 		let constant = self.constant;
-		let state_mutability = match self.state_mutability {
-			ethabi::StateMutability::Pure => quote! { ::ethabi::StateMutability::Pure },
-			ethabi::StateMutability::Payable => quote! { ::ethabi::StateMutability::Payable },
-			ethabi::StateMutability::NonPayable => quote! { ::ethabi::StateMutability::NonPayable },
-			ethabi::StateMutability::View => quote! { ::ethabi::StateMutability::View },
-		};
+		let state_mutability = quote_state_mutability(self.state_mutability);
 		let outputs_result = &self.outputs.result;
 		let outputs_implementation = &self.outputs.implementation;
+		let read_only = self.read_only;
+		let builder = self.generate_builder();
 
 		quote! {
 			pub mod #module_name {
 				use ethabi;
 				use super::INTERNAL_ERR;
 
-				fn function() -> ethabi::Function {
+				pub fn function() -> ethabi::Function {
 					ethabi::Function {
 						name: #name.into(),
 						inputs: #recreate_inputs,
@@ -181,6 +362,9 @@ impl Function {
 					}
 				}
 
+				/// Whether the function does not modify blockchain state, so it can be executed with a call.
+				pub const READ_ONLY: bool = #read_only;
+
 				/// Generic function output decoder.
 				pub struct Decoder(ethabi::Function);
 
@@ -210,6 +394,15 @@ impl Function {
 					let tokens = vec![#(#tokenize),*];
 					(f.encode_input(&tokens).expect(INTERNAL_ERR), Decoder(f))
 				}
+
+				/// Encodes function input into a call which can only decode the output of this function.
+				pub fn encode_call<#(#declarations),*>(#(#definitions),*) -> ethabi::EncodedCall<Decoder> {
+					let f = function();
+					let tokens = vec![#(#tokenize),*];
+					ethabi::EncodedCall::new(f.encode_input(&tokens).expect(INTERNAL_ERR), Decoder(f))
+				}
+
+				#builder
 			}
 		}
 	}
@@ -238,7 +431,7 @@ mod tests {
 				use ethabi;
 				use super::INTERNAL_ERR;
 
-				fn function() -> ethabi::Function {
+				pub fn function() -> ethabi::Function {
 					ethabi::Function {
 						name: "empty".into(),
 						inputs: vec![],
@@ -248,6 +441,9 @@ mod tests {
 					}
 				}
 
+				/// Whether the function does not modify blockchain state, so it can be executed with a call.
+				pub const READ_ONLY: bool = false;
+
 				/// Generic function output decoder.
 				pub struct Decoder(ethabi::Function);
 
@@ -278,6 +474,13 @@ mod tests {
 					let tokens = vec![];
 					(f.encode_input(&tokens).expect(INTERNAL_ERR), Decoder(f))
 				}
+
+				/// Encodes function input into a call which can only decode the output of this function.
+				pub fn encode_call<>() -> ethabi::EncodedCall<Decoder> {
+					let f = function();
+					let tokens = vec![];
+					ethabi::EncodedCall::new(f.encode_input(&tokens).expect(INTERNAL_ERR), Decoder(f))
+				}
 			}
 		};
 
@@ -302,7 +505,7 @@ mod tests {
 				use ethabi;
 				use super::INTERNAL_ERR;
 
-				fn function() -> ethabi::Function {
+				pub fn function() -> ethabi::Function {
 					ethabi::Function {
 						name: "hello".into(),
 						inputs: vec![ethabi::Param {
@@ -318,6 +521,9 @@ mod tests {
 					}
 				}
 
+				/// Whether the function does not modify blockchain state, so it can be executed with a call.
+				pub const READ_ONLY: bool = false;
+
 				/// Generic function output decoder.
 				pub struct Decoder(ethabi::Function);
 
@@ -348,6 +554,13 @@ mod tests {
 					let tokens = vec![ethabi::Token::Address(foo.into())];
 					(f.encode_input(&tokens).expect(INTERNAL_ERR), Decoder(f))
 				}
+
+				/// Encodes function input into a call which can only decode the output of this function.
+				pub fn encode_call<T0: Into<ethabi::Address> >(foo: T0) -> ethabi::EncodedCall<Decoder> {
+					let f = function();
+					let tokens = vec![ethabi::Token::Address(foo.into())];
+					ethabi::EncodedCall::new(f.encode_input(&tokens).expect(INTERNAL_ERR), Decoder(f))
+				}
 			}
 		};
 
@@ -384,7 +597,7 @@ mod tests {
 				use ethabi;
 				use super::INTERNAL_ERR;
 
-				fn function() -> ethabi::Function {
+				pub fn function() -> ethabi::Function {
 					ethabi::Function {
 						name: "multi".into(),
 						inputs: vec![ethabi::Param {
@@ -406,6 +619,9 @@ mod tests {
 					}
 				}
 
+				/// Whether the function does not modify blockchain state, so it can be executed with a call.
+				pub const READ_ONLY: bool = false;
+
 				/// Generic function output decoder.
 				pub struct Decoder(ethabi::Function);
 
@@ -448,9 +664,133 @@ mod tests {
 					}];
 					(f.encode_input(&tokens).expect(INTERNAL_ERR), Decoder(f))
 				}
+
+				/// Encodes function input into a call which can only decode the output of this function.
+				pub fn encode_call<T0: Into<[U0; 2usize]>, U0: Into<ethabi::Address>, T1: IntoIterator<Item = U1>, U1: Into<ethabi::Uint> >(foo: T0, bar: T1) -> ethabi::EncodedCall<Decoder> {
+					let f = function();
+					let tokens = vec![{
+						let v = (Box::new(foo.into()) as Box<[_]>).into_vec().into_iter().map(Into::into).collect::<Vec<_>>().into_iter().map(|inner| ethabi::Token::Address(inner)).collect();
+						ethabi::Token::FixedArray(v)
+					}, {
+						let v = bar.into_iter().map(Into::into).collect::<Vec<_>>().into_iter().map(|inner| ethabi::Token::Uint(inner)).collect();
+						ethabi::Token::Array(v)
+					}];
+					ethabi::EncodedCall::new(f.encode_input(&tokens).expect(INTERNAL_ERR), Decoder(f))
+				}
 			}
 		};
 
 		assert_eq!(expected.to_string(), f.generate().to_string());
 	}
+
+	#[test]
+	fn test_generate_test() {
+		#[allow(deprecated)]
+		let ethabi_function = ethabi::Function {
+			name: "hello".into(),
+			inputs: vec![ethabi::Param { name: "foo".into(), kind: ethabi::ParamType::Address }],
+			outputs: vec![ethabi::Param { name: "bar".into(), kind: ethabi::ParamType::Uint(256) }],
+			constant: false,
+			state_mutability: ethabi::StateMutability::Payable,
+		};
+
+		let f = Function::from(&ethabi_function);
+
+		let expected = quote! {
+			#[test]
+			fn hello() {
+				let calldata = super::super::functions::hello::encode_input(ethabi::Address::repeat_byte(0x11));
+				let function = super::super::functions::hello::function();
+				assert_eq!(
+					function.decode_calldata(&calldata).expect("calldata must decode"),
+					vec![ethabi::Token::Address(ethabi::Address::repeat_byte(0x11))]
+				);
+
+				let outputs = vec![ethabi::Token::Uint(ethabi::Uint::from(1u64))];
+				assert_eq!(
+					super::super::functions::hello::decode_output(&ethabi::encode(&outputs))
+						.expect("mock outputs must decode"),
+					ethabi::Uint::from(1u64)
+				);
+			}
+		};
+
+		assert_eq!(expected.to_string(), f.generate_test().to_string());
+	}
+
+	#[test]
+	fn test_generate_builder() {
+		#[allow(deprecated)]
+		let ethabi_function = ethabi::Function {
+			name: "hello".into(),
+			inputs: vec![
+				ethabi::Param { name: "foo".into(), kind: ethabi::ParamType::Address },
+				ethabi::Param { name: "bar".into(), kind: ethabi::ParamType::Uint(256) },
+			],
+			outputs: vec![],
+			constant: false,
+			state_mutability: ethabi::StateMutability::Payable,
+		};
+
+		let mut f = Function::from(&ethabi_function);
+		assert!(f.generate_builder().is_none());
+
+		f.set_defaults(&vec![("bar".to_owned(), "10".to_owned())].into_iter().collect());
+		let expected = quote! {
+			/// Starts building function input, params with defaults may be omitted.
+			pub fn builder() -> Builder {
+				Builder::default()
+			}
+
+			/// Function input builder.
+			#[derive(Debug, Clone, Default)]
+			pub struct Builder {
+				foo: Option<ethabi::Token>,
+				bar: Option<ethabi::Token>
+			}
+
+			impl Builder {
+				#[doc = "Sets `foo`."]
+				pub fn foo<T0: Into<ethabi::Address> >(mut self, foo: T0) -> Self {
+					self.foo = Some(ethabi::Token::Address(foo.into()));
+					self
+				}
+
+				#[doc = "Sets `bar`."]
+				pub fn bar<T1: Into<ethabi::Uint> >(mut self, bar: T1) -> Self {
+					self.bar = Some(ethabi::Token::Uint(bar.into()));
+					self
+				}
+
+				#[doc = "Sets `bar` to its default value."]
+				pub fn bar_default(mut self) -> Self {
+					self.bar = Some(
+						<ethabi::token::LenientTokenizer as ethabi::token::Tokenizer>::tokenize(
+							&function().inputs[1usize].kind,
+							"10",
+						)
+						.expect(INTERNAL_ERR)
+					);
+					self
+				}
+
+				/// Encodes function input, using defaults of params which were not set.
+				///
+				/// Fails with the name of the first missing param without a default.
+				pub fn build(self) -> ethabi::Result<ethabi::Bytes> {
+					let tokens = vec![
+						self.foo.ok_or_else(|| ethabi::Error::InvalidName("foo".into()))?,
+						self.bar.unwrap_or_else(|| <ethabi::token::LenientTokenizer as ethabi::token::Tokenizer>::tokenize(
+							&function().inputs[1usize].kind,
+							"10",
+						)
+						.expect(INTERNAL_ERR))
+					];
+					function().encode_input(&tokens)
+				}
+			}
+		};
+
+		assert_eq!(expected.to_string(), f.generate_builder().unwrap().to_string());
+	}
 }
@@ -0,0 +1,424 @@
+// Copyright 2015-2019 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Generation of Rust bindings of contracts from their ABI, as used by `ethabi-derive` and the
+//! `codegen` command of `ethabi-cli`.
+
+#![recursion_limit = "256"]
+
+mod constructor;
+mod contract;
+mod docs;
+mod event;
+mod function;
+
+use anyhow::anyhow;
+use ethabi::{
+	token::{LenientTokenizer, Tokenizer},
+	Param, ParamType, Result,
+};
+use heck::SnakeCase;
+use proc_macro2::Span;
+use quote::quote;
+use std::{collections::HashMap, fs, path::Path};
+
+pub use self::{
+	contract::{AbiFormat, Contract},
+	docs::markdown,
+};
+
+/// Loads contract abi in json or as human-readable signatures.
+pub fn load_contract(path: &Path) -> Result<(ethabi::Contract, AbiFormat)> {
+	let source = fs::read_to_string(path).map_err(|_| anyhow!("Cannot load contract abi from `{}`", path.display()))?;
+	// json abi is an array, anything else is a list of human-readable signatures
+	match source.trim_start().starts_with('[') {
+		true => Ok((ethabi::Contract::load(source.as_bytes())?, AbiFormat::Json)),
+		false => Ok((ethabi::Contract::load_text(source.as_bytes())?, AbiFormat::Text)),
+	}
+}
+
+/// Loads default values of function params, e.g. `{ "swap": { "deadline": "1000" } }`.
+///
+/// Defaults must be valid values of their params and only trailing params may have them.
+pub fn load_defaults(path: &Path, contract: &ethabi::Contract) -> Result<HashMap<String, HashMap<String, String>>> {
+	let source = fs::read_to_string(path).map_err(|_| anyhow!("Cannot load defaults from `{}`", path.display()))?;
+	let defaults: HashMap<String, HashMap<String, String>> = serde_json::from_str(&source)?;
+
+	for (name, params) in &defaults {
+		for function in contract.functions_by_name(name)? {
+			if let Some(unknown) = params.keys().find(|param| function.inputs.iter().all(|input| &input.name != *param))
+			{
+				return Err(anyhow!("Function `{}` has no param `{}`", name, unknown).into());
+			}
+
+			let first_default = function.inputs.len() - params.len();
+			for (index, input) in function.inputs.iter().enumerate() {
+				match params.get(&input.name) {
+					Some(value) => {
+						LenientTokenizer::tokenize(&input.kind, value)
+							.map_err(|err| anyhow!("Invalid default of `{}` in `{}`: {}", input.name, name, err))?;
+					}
+					None if index >= first_default => {
+						return Err(anyhow!(
+							"Param `{}` of `{}` has no default, but is followed by params with defaults",
+							input.name,
+							name
+						)
+						.into());
+					}
+					None => (),
+				}
+			}
+		}
+	}
+
+	Ok(defaults)
+}
+
+/// Parses a comma separated list of functions, which must return a single `bool` or nothing.
+pub fn lenient_bool_functions(names: &str, contract: &ethabi::Contract) -> Result<Vec<String>> {
+	let names: Vec<String> = names.split(',').map(|name| name.trim().to_owned()).collect();
+	for name in &names {
+		for function in contract.functions_by_name(name)? {
+			if !matches!(function.outputs.as_slice(), [] | [Param { kind: ParamType::Bool, .. }]) {
+				return Err(anyhow!("`lenient_bool` function `{}` must return a single `bool` or nothing", name).into());
+			}
+		}
+	}
+	Ok(names)
+}
+
+fn to_syntax_string(param_type: &ethabi::ParamType) -> proc_macro2::TokenStream {
+	match *param_type {
+		ParamType::Address => quote! { ethabi::ParamType::Address },
+		ParamType::Bytes => quote! { ethabi::ParamType::Bytes },
+		ParamType::Int(x) => quote! { ethabi::ParamType::Int(#x) },
+		ParamType::Uint(x) => quote! { ethabi::ParamType::Uint(#x) },
+		ParamType::Fixed(x, y) => quote! { ethabi::ParamType::Fixed(#x, #y) },
+		ParamType::Ufixed(x, y) => quote! { ethabi::ParamType::Ufixed(#x, #y) },
+		ParamType::Bool => quote! { ethabi::ParamType::Bool },
+		ParamType::String => quote! { ethabi::ParamType::String },
+		ParamType::Array(ref param_type) => {
+			let param_type_quote = to_syntax_string(param_type);
+			quote! { ethabi::ParamType::Array(Box::new(#param_type_quote)) }
+		}
+		ParamType::FixedBytes(x) => quote! { ethabi::ParamType::FixedBytes(#x) },
+		ParamType::Function => quote! { ethabi::ParamType::Function },
+		ParamType::FixedArray(ref param_type, ref x) => {
+			let param_type_quote = to_syntax_string(param_type);
+			quote! { ethabi::ParamType::FixedArray(Box::new(#param_type_quote), #x) }
+		}
+		ParamType::Tuple(ref kinds) => {
+			let kinds = kinds.iter().map(to_syntax_string);
+			quote! { ethabi::ParamType::Tuple(vec![#(#kinds),*]) }
+		}
+	}
+}
+
+fn to_ethabi_param_vec<'a, P: 'a>(params: P) -> proc_macro2::TokenStream
+where
+	P: IntoIterator<Item = &'a Param>,
+{
+	let p = params
+		.into_iter()
+		.map(|x| {
+			let name = &x.name;
+			let kind = to_syntax_string(&x.kind);
+			quote! {
+				ethabi::Param {
+					name: #name.to_owned(),
+					kind: #kind
+				}
+			}
+		})
+		.collect::<Vec<_>>();
+
+	quote! { vec![ #(#p),* ] }
+}
+
+fn rust_type(input: &ParamType) -> proc_macro2::TokenStream {
+	match *input {
+		ParamType::Address => quote! { ethabi::Address },
+		ParamType::Bytes => quote! { ethabi::Bytes },
+		ParamType::FixedBytes(32) => quote! { ethabi::Hash },
+		ParamType::FixedBytes(size) => quote! { [u8; #size] },
+		ParamType::Function => rust_type(&ParamType::FixedBytes(24)),
+		ParamType::Int(_) | ParamType::Fixed(_, _) => quote! { ethabi::Int },
+		ParamType::Uint(_) | ParamType::Ufixed(_, _) => quote! { ethabi::Uint },
+		ParamType::Bool => quote! { bool },
+		ParamType::String => quote! { String },
+		ParamType::Array(ref kind) => {
+			let t = rust_type(&*kind);
+			quote! { Vec<#t> }
+		}
+		ParamType::FixedArray(ref kind, size) => {
+			let t = rust_type(&*kind);
+			quote! { [#t; #size] }
+		}
+		ParamType::Tuple(ref kinds) => {
+			let types = kinds.iter().map(rust_type);
+			quote! { (#(#types,)*) }
+		}
+	}
+}
+
+fn template_param_type(input: &ParamType, index: usize) -> proc_macro2::TokenStream {
+	let t_ident = syn::Ident::new(&format!("T{}", index), Span::call_site());
+	let u_ident = syn::Ident::new(&format!("U{}", index), Span::call_site());
+	match *input {
+		ParamType::Address => quote! { #t_ident: Into<ethabi::Address> },
+		ParamType::Bytes => quote! { #t_ident: Into<ethabi::Bytes> },
+		ParamType::FixedBytes(32) => quote! { #t_ident: Into<ethabi::Hash> },
+		ParamType::FixedBytes(size) => quote! { #t_ident: Into<[u8; #size]> },
+		ParamType::Function => template_param_type(&ParamType::FixedBytes(24), index),
+		ParamType::Int(_) | ParamType::Fixed(_, _) => quote! { #t_ident: Into<ethabi::Int> },
+		ParamType::Uint(_) | ParamType::Ufixed(_, _) => quote! { #t_ident: Into<ethabi::Uint> },
+		ParamType::Bool => quote! { #t_ident: Into<bool> },
+		ParamType::String => quote! { #t_ident: Into<String> },
+		ParamType::Array(ref kind) => {
+			let t = rust_type(&*kind);
+			quote! {
+				#t_ident: IntoIterator<Item = #u_ident>, #u_ident: Into<#t>
+			}
+		}
+		ParamType::FixedArray(ref kind, size) => {
+			let t = rust_type(&*kind);
+			quote! {
+				#t_ident: Into<[#u_ident; #size]>, #u_ident: Into<#t>
+			}
+		}
+		ParamType::Tuple(_) => {
+			let t = rust_type(input);
+			quote! { #t_ident: Into<#t> }
+		}
+	}
+}
+
+fn from_template_param(input: &ParamType, name: &syn::Ident) -> proc_macro2::TokenStream {
+	match *input {
+		ParamType::Array(_) => quote! { #name.into_iter().map(Into::into).collect::<Vec<_>>() },
+		ParamType::FixedArray(_, _) => {
+			quote! { (Box::new(#name.into()) as Box<[_]>).into_vec().into_iter().map(Into::into).collect::<Vec<_>>() }
+		}
+		_ => quote! {#name.into() },
+	}
+}
+
+fn to_token(name: &proc_macro2::TokenStream, kind: &ParamType) -> proc_macro2::TokenStream {
+	match *kind {
+		ParamType::Address => quote! { ethabi::Token::Address(#name) },
+		ParamType::Bytes => quote! { ethabi::Token::Bytes(#name) },
+		ParamType::FixedBytes(_) | ParamType::Function => {
+			quote! { ethabi::Token::FixedBytes(#name.as_ref().to_vec()) }
+		}
+		ParamType::Int(_) | ParamType::Fixed(_, _) => quote! { ethabi::Token::Int(#name) },
+		ParamType::Uint(_) | ParamType::Ufixed(_, _) => quote! { ethabi::Token::Uint(#name) },
+		ParamType::Bool => quote! { ethabi::Token::Bool(#name) },
+		ParamType::String => quote! { ethabi::Token::String(#name) },
+		ParamType::Array(ref kind) => {
+			let inner_name = quote! { inner };
+			let inner_loop = to_token(&inner_name, kind);
+			quote! {
+				// note the double {{
+				{
+					let v = #name.into_iter().map(|#inner_name| #inner_loop).collect();
+					ethabi::Token::Array(v)
+				}
+			}
+		}
+		ParamType::FixedArray(ref kind, _) => {
+			let inner_name = quote! { inner };
+			let inner_loop = to_token(&inner_name, kind);
+			quote! {
+				// note the double {{
+				{
+					let v = #name.into_iter().map(|#inner_name| #inner_loop).collect();
+					ethabi::Token::FixedArray(v)
+				}
+			}
+		}
+		ParamType::Tuple(ref kinds) => {
+			let names: Vec<_> =
+				(0..kinds.len()).map(|i| syn::Ident::new(&format!("field{}", i), Span::call_site())).collect();
+			let tokens = names.iter().zip(kinds).map(|(name, kind)| to_token(&quote! { #name }, kind));
+			quote! {
+				{
+					let (#(#names,)*) = #name;
+					ethabi::Token::Tuple(vec![#(#tokens),*])
+				}
+			}
+		}
+	}
+}
+
+fn from_token(kind: &ParamType, token: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+	match *kind {
+		ParamType::Address => quote! { #token.into_address().expect(INTERNAL_ERR) },
+		ParamType::Bytes => quote! { #token.into_bytes().expect(INTERNAL_ERR) },
+		ParamType::FixedBytes(32) => quote! {
+			{
+				let mut result = [0u8; 32];
+				let v = #token.into_fixed_bytes().expect(INTERNAL_ERR);
+				result.copy_from_slice(&v);
+				ethabi::Hash::from(result)
+			}
+		},
+		ParamType::FixedBytes(size) => {
+			let size: syn::Index = size.into();
+			quote! {
+				{
+					let mut result = [0u8; #size];
+					let v = #token.into_fixed_bytes().expect(INTERNAL_ERR);
+					result.copy_from_slice(&v);
+					result
+				}
+			}
+		}
+		ParamType::Function => from_token(&ParamType::FixedBytes(24), token),
+		ParamType::Int(_) | ParamType::Fixed(_, _) => quote! { #token.into_int().expect(INTERNAL_ERR) },
+		ParamType::Uint(_) | ParamType::Ufixed(_, _) => quote! { #token.into_uint().expect(INTERNAL_ERR) },
+		ParamType::Bool => quote! { #token.into_bool().expect(INTERNAL_ERR) },
+		ParamType::String => quote! { #token.into_string().expect(INTERNAL_ERR) },
+		ParamType::Array(ref kind) => {
+			let inner = quote! { inner };
+			let inner_loop = from_token(kind, &inner);
+			quote! {
+				#token.into_array().expect(INTERNAL_ERR).into_iter()
+					.map(|#inner| #inner_loop)
+					.collect()
+			}
+		}
+		ParamType::FixedArray(ref kind, size) => {
+			let inner = quote! { inner };
+			let inner_loop = from_token(kind, &inner);
+			let to_array = vec![quote! { iter.next().expect(INTERNAL_ERR) }; size];
+			quote! {
+				{
+					let mut iter = #token.into_fixed_array().expect(INTERNAL_ERR).into_iter()
+						.map(|#inner| #inner_loop);
+					[#(#to_array),*]
+				}
+			}
+		}
+		ParamType::Tuple(ref kinds) => {
+			let fields = kinds.iter().map(|kind| from_token(kind, &quote! { iter.next().expect(INTERNAL_ERR) }));
+			quote! {
+				{
+					let mut iter = #token.into_tuple().expect(INTERNAL_ERR).into_iter();
+					(#(#fields,)*)
+				}
+			}
+		}
+	}
+}
+
+/// Recreates the given state mutability.
+fn quote_state_mutability(state_mutability: ethabi::StateMutability) -> proc_macro2::TokenStream {
+	match state_mutability {
+		ethabi::StateMutability::Pure => quote! { ::ethabi::StateMutability::Pure },
+		ethabi::StateMutability::Payable => quote! { ::ethabi::StateMutability::Payable },
+		ethabi::StateMutability::NonPayable => quote! { ::ethabi::StateMutability::NonPayable },
+		ethabi::StateMutability::View => quote! { ::ethabi::StateMutability::View },
+	}
+}
+
+/// Creates an arbitrary token of the given type, used by generated tests.
+fn mock_token(kind: &ParamType) -> proc_macro2::TokenStream {
+	match *kind {
+		ParamType::Address => quote! { ethabi::Token::Address(ethabi::Address::repeat_byte(0x11)) },
+		ParamType::Bytes => quote! { ethabi::Token::Bytes(vec![0x11; 33]) },
+		ParamType::FixedBytes(size) => quote! { ethabi::Token::FixedBytes(vec![0x11; #size]) },
+		ParamType::Function => quote! { ethabi::Token::FixedBytes(vec![0x11; 24]) },
+		ParamType::Int(_) | ParamType::Fixed(_, _) => quote! { ethabi::Token::Int(ethabi::Int::from(1u64)) },
+		ParamType::Uint(_) | ParamType::Ufixed(_, _) => quote! { ethabi::Token::Uint(ethabi::Uint::from(1u64)) },
+		ParamType::Bool => quote! { ethabi::Token::Bool(true) },
+		ParamType::String => quote! { ethabi::Token::String("mock".into()) },
+		ParamType::Array(ref kind) => {
+			let mock = mock_token(kind);
+			quote! { ethabi::Token::Array(vec![#mock, #mock]) }
+		}
+		ParamType::FixedArray(ref kind, size) => {
+			let mock = mock_token(kind);
+			quote! { ethabi::Token::FixedArray(vec![#mock; #size]) }
+		}
+		ParamType::Tuple(ref kinds) => {
+			let mocks = kinds.iter().map(mock_token);
+			quote! { ethabi::Token::Tuple(vec![#(#mocks),*]) }
+		}
+	}
+}
+
+/// Creates an arbitrary value of the rust type of the given type, with the content of `mock_token`.
+fn mock_value(kind: &ParamType) -> proc_macro2::TokenStream {
+	match *kind {
+		ParamType::Address => quote! { ethabi::Address::repeat_byte(0x11) },
+		ParamType::Bytes => quote! { vec![0x11u8; 33] },
+		ParamType::FixedBytes(32) => quote! { ethabi::Hash::repeat_byte(0x11) },
+		ParamType::FixedBytes(size) => quote! { [0x11u8; #size] },
+		ParamType::Function => mock_value(&ParamType::FixedBytes(24)),
+		ParamType::Int(_) | ParamType::Fixed(_, _) => quote! { ethabi::Int::from(1u64) },
+		ParamType::Uint(_) | ParamType::Ufixed(_, _) => quote! { ethabi::Uint::from(1u64) },
+		ParamType::Bool => quote! { true },
+		ParamType::String => quote! { String::from("mock") },
+		ParamType::Array(ref kind) => {
+			let mock = mock_value(kind);
+			quote! { vec![#mock, #mock] }
+		}
+		ParamType::FixedArray(ref kind, size) => {
+			let mocks = vec![mock_value(kind); size];
+			quote! { [#(#mocks),*] }
+		}
+		ParamType::Tuple(ref kinds) => {
+			let mocks = kinds.iter().map(mock_value);
+			quote! { (#(#mocks,)*) }
+		}
+	}
+}
+
+fn input_names(inputs: &[Param]) -> Vec<syn::Ident> {
+	inputs
+		.iter()
+		.enumerate()
+		.map(|(index, param)| {
+			if param.name.is_empty() {
+				syn::Ident::new(&format!("param{}", index), Span::call_site())
+			} else {
+				syn::Ident::new(&rust_variable(&param.name), Span::call_site())
+			}
+		})
+		.collect()
+}
+
+fn get_template_names(kinds: &[proc_macro2::TokenStream]) -> Vec<syn::Ident> {
+	kinds.iter().enumerate().map(|(index, _)| syn::Ident::new(&format!("T{}", index), Span::call_site())).collect()
+}
+
+fn get_output_kinds(outputs: &[Param]) -> proc_macro2::TokenStream {
+	match outputs.len() {
+		0 => quote! {()},
+		1 => {
+			let t = rust_type(&outputs[0].kind);
+			quote! { #t }
+		}
+		_ => {
+			let outs: Vec<_> = outputs.iter().map(|param| rust_type(&param.kind)).collect();
+			quote! { (#(#outs),*) }
+		}
+	}
+}
+
+/// Convert input into a rust variable name.
+///
+/// Avoid using keywords by escaping them.
+fn rust_variable(name: &str) -> String {
+	// avoid keyword parameters
+	match name {
+		"self" => "_self".to_string(),
+		other => other.to_snake_case(),
+	}
+}
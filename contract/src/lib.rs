@@ -20,4 +20,16 @@ macro_rules! use_contract {
 			struct _Dummy;
 		}
 	};
+	($module: ident, $path: expr, $($option: ident = $value: expr),+) => {
+		#[allow(dead_code)]
+		#[allow(missing_docs)]
+		#[allow(unused_imports)]
+		#[allow(unused_mut)]
+		#[allow(unused_variables)]
+		pub mod $module {
+			#[derive(ethabi_derive::EthabiContract)]
+			#[ethabi_contract_options(path = $path, $($option = $value),+)]
+			struct _Dummy;
+		}
+	};
 }
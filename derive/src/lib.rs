@@ -10,17 +10,12 @@
 
 extern crate proc_macro;
 
-mod constructor;
-mod contract;
-mod event;
-mod function;
+mod tokenizable;
 
 use anyhow::anyhow;
-use ethabi::{Contract, Param, ParamType, Result};
-use heck::SnakeCase;
-use proc_macro2::Span;
-use quote::quote;
-use std::{env, fs, path::PathBuf};
+use ethabi::Result;
+use ethabi_codegen::{lenient_bool_functions, load_contract, load_defaults, markdown, Contract};
+use std::{env, path::PathBuf};
 
 const ERROR_MSG: &str = "`derive(EthabiContract)` failed";
 
@@ -31,14 +26,48 @@ pub fn ethabi_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 	gen.into()
 }
 
+#[proc_macro_derive(Tokenizable)]
+pub fn tokenizable_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let ast = syn::parse(input).expect("`derive(Tokenizable)` failed");
+	let gen = tokenizable::impl_tokenizable(&ast).expect("`derive(Tokenizable)` failed");
+	gen.into()
+}
+
 fn impl_ethabi_derive(ast: &syn::DeriveInput) -> Result<proc_macro2::TokenStream> {
 	let options = get_options(&ast.attrs, "ethabi_contract_options")?;
 	let path = get_option(&options, "path")?;
 	let normalized_path = normalize_path(&path)?;
-	let source_file = fs::File::open(&normalized_path)
-		.map_err(|_| anyhow!("Cannot load contract abi from `{}`", normalized_path.display()))?;
-	let contract = Contract::load(source_file)?;
-	let c = contract::Contract::from(&contract);
+	let (contract, format) = load_contract(&normalized_path)?;
+	let mut c = Contract::from(&contract);
+	if get_bool_option(&options, "embed")? {
+		c = c.embed_abi(normalized_path.display().to_string(), format);
+	}
+	if let Some(defaults_path) = find_option(&options, "defaults") {
+		let defaults_path = normalize_path(&str_value_of_meta_item(defaults_path, "defaults")?)?;
+		c = c.defaults(&load_defaults(&defaults_path, &contract)?);
+	}
+	if let Some(names) = find_option(&options, "lenient_bool") {
+		let names = str_value_of_meta_item(names, "lenient_bool")?;
+		c = c.lenient_bool(&lenient_bool_functions(&names, &contract)?);
+	}
+	match (find_option(&options, "common_events"), find_option(&options, "common_events_path")) {
+		(Some(module), Some(path)) => {
+			let module = str_value_of_meta_item(module, "common_events")?;
+			let module: syn::Path = syn::parse_str(&module)
+				.map_err(|_| anyhow!("`common_events` must be a module path, e.g. `crate::erc20`"))?;
+			let common_path = normalize_path(&str_value_of_meta_item(path, "common_events_path")?)?;
+			c = c.share_events(&module, &load_contract(&common_path)?.0);
+		}
+		(None, None) => (),
+		_ => return Err(anyhow!("`common_events` and `common_events_path` must be set together").into()),
+	}
+	if get_bool_option(&options, "docs")? {
+		let title = normalized_path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+		c = c.docs(markdown(title.split('.').next().unwrap_or_default(), &contract));
+	}
+	if get_bool_option(&options, "generate_tests")? {
+		c = c.generate_tests();
+	}
 	Ok(c.generate())
 }
 
@@ -50,20 +79,38 @@ fn get_options(attrs: &[syn::Attribute], name: &str) -> Result<Vec<syn::NestedMe
 		_ => Err(anyhow!("Unexpected meta item").into()),
 	}
 }
-
-fn get_option(options: &[syn::NestedMeta], name: &str) -> Result<String> {
-	let item = options
+fn find_option<'a>(options: &'a [syn::NestedMeta], name: &str) -> Option<&'a syn::Meta> {
+	options
 		.iter()
 		.flat_map(|nested| match *nested {
 			syn::NestedMeta::Meta(ref meta) => Some(meta),
 			_ => None,
 		})
 		.find(|meta| meta.path().is_ident(name))
-		.ok_or_else(|| anyhow!("Expected to find option {}", name))?;
+}
 
+fn get_option(options: &[syn::NestedMeta], name: &str) -> Result<String> {
+	let item = find_option(options, name).ok_or_else(|| anyhow!("Expected to find option {}", name))?;
 	str_value_of_meta_item(item, name)
 }
 
+fn get_bool_option(options: &[syn::NestedMeta], name: &str) -> Result<bool> {
+	match find_option(options, name) {
+		Some(item) => bool_value_of_meta_item(item, name),
+		None => Ok(false),
+	}
+}
+
+fn bool_value_of_meta_item(item: &syn::Meta, name: &str) -> Result<bool> {
+	if let syn::Meta::NameValue(ref name_value) = *item {
+		if let syn::Lit::Bool(ref value) = name_value.lit {
+			return Ok(value.value);
+		}
+	}
+
+	Err(anyhow!(r#"`{}` must be in the form `#[{}=true]`"#, name, name).into())
+}
+
 fn str_value_of_meta_item(item: &syn::Meta, name: &str) -> Result<String> {
 	if let syn::Meta::NameValue(ref name_value) = *item {
 		if let syn::Lit::Str(ref value) = name_value.lit {
@@ -81,245 +128,3 @@ fn normalize_path(relative_path: &str) -> Result<PathBuf> {
 	path.push(relative_path);
 	Ok(path)
 }
-
-fn to_syntax_string(param_type: &ethabi::ParamType) -> proc_macro2::TokenStream {
-	match *param_type {
-		ParamType::Address => quote! { ethabi::ParamType::Address },
-		ParamType::Bytes => quote! { ethabi::ParamType::Bytes },
-		ParamType::Int(x) => quote! { ethabi::ParamType::Int(#x) },
-		ParamType::Uint(x) => quote! { ethabi::ParamType::Uint(#x) },
-		ParamType::Bool => quote! { ethabi::ParamType::Bool },
-		ParamType::String => quote! { ethabi::ParamType::String },
-		ParamType::Array(ref param_type) => {
-			let param_type_quote = to_syntax_string(param_type);
-			quote! { ethabi::ParamType::Array(Box::new(#param_type_quote)) }
-		}
-		ParamType::FixedBytes(x) => quote! { ethabi::ParamType::FixedBytes(#x) },
-		ParamType::FixedArray(ref param_type, ref x) => {
-			let param_type_quote = to_syntax_string(param_type);
-			quote! { ethabi::ParamType::FixedArray(Box::new(#param_type_quote), #x) }
-		}
-		ParamType::Tuple(_) => {
-			unimplemented!("Tuples are not supported. https://github.com/openethereum/ethabi/issues/175")
-		}
-	}
-}
-
-fn to_ethabi_param_vec<'a, P: 'a>(params: P) -> proc_macro2::TokenStream
-where
-	P: IntoIterator<Item = &'a Param>,
-{
-	let p = params
-		.into_iter()
-		.map(|x| {
-			let name = &x.name;
-			let kind = to_syntax_string(&x.kind);
-			quote! {
-				ethabi::Param {
-					name: #name.to_owned(),
-					kind: #kind
-				}
-			}
-		})
-		.collect::<Vec<_>>();
-
-	quote! { vec![ #(#p),* ] }
-}
-
-fn rust_type(input: &ParamType) -> proc_macro2::TokenStream {
-	match *input {
-		ParamType::Address => quote! { ethabi::Address },
-		ParamType::Bytes => quote! { ethabi::Bytes },
-		ParamType::FixedBytes(32) => quote! { ethabi::Hash },
-		ParamType::FixedBytes(size) => quote! { [u8; #size] },
-		ParamType::Int(_) => quote! { ethabi::Int },
-		ParamType::Uint(_) => quote! { ethabi::Uint },
-		ParamType::Bool => quote! { bool },
-		ParamType::String => quote! { String },
-		ParamType::Array(ref kind) => {
-			let t = rust_type(&*kind);
-			quote! { Vec<#t> }
-		}
-		ParamType::FixedArray(ref kind, size) => {
-			let t = rust_type(&*kind);
-			quote! { [#t, #size] }
-		}
-		ParamType::Tuple(_) => {
-			unimplemented!("Tuples are not supported. https://github.com/openethereum/ethabi/issues/175")
-		}
-	}
-}
-
-fn template_param_type(input: &ParamType, index: usize) -> proc_macro2::TokenStream {
-	let t_ident = syn::Ident::new(&format!("T{}", index), Span::call_site());
-	let u_ident = syn::Ident::new(&format!("U{}", index), Span::call_site());
-	match *input {
-		ParamType::Address => quote! { #t_ident: Into<ethabi::Address> },
-		ParamType::Bytes => quote! { #t_ident: Into<ethabi::Bytes> },
-		ParamType::FixedBytes(32) => quote! { #t_ident: Into<ethabi::Hash> },
-		ParamType::FixedBytes(size) => quote! { #t_ident: Into<[u8; #size]> },
-		ParamType::Int(_) => quote! { #t_ident: Into<ethabi::Int> },
-		ParamType::Uint(_) => quote! { #t_ident: Into<ethabi::Uint> },
-		ParamType::Bool => quote! { #t_ident: Into<bool> },
-		ParamType::String => quote! { #t_ident: Into<String> },
-		ParamType::Array(ref kind) => {
-			let t = rust_type(&*kind);
-			quote! {
-				#t_ident: IntoIterator<Item = #u_ident>, #u_ident: Into<#t>
-			}
-		}
-		ParamType::FixedArray(ref kind, size) => {
-			let t = rust_type(&*kind);
-			quote! {
-				#t_ident: Into<[#u_ident; #size]>, #u_ident: Into<#t>
-			}
-		}
-		ParamType::Tuple(_) => {
-			unimplemented!("Tuples are not supported. https://github.com/openethereum/ethabi/issues/175")
-		}
-	}
-}
-
-fn from_template_param(input: &ParamType, name: &syn::Ident) -> proc_macro2::TokenStream {
-	match *input {
-		ParamType::Array(_) => quote! { #name.into_iter().map(Into::into).collect::<Vec<_>>() },
-		ParamType::FixedArray(_, _) => {
-			quote! { (Box::new(#name.into()) as Box<[_]>).into_vec().into_iter().map(Into::into).collect::<Vec<_>>() }
-		}
-		_ => quote! {#name.into() },
-	}
-}
-
-fn to_token(name: &proc_macro2::TokenStream, kind: &ParamType) -> proc_macro2::TokenStream {
-	match *kind {
-		ParamType::Address => quote! { ethabi::Token::Address(#name) },
-		ParamType::Bytes => quote! { ethabi::Token::Bytes(#name) },
-		ParamType::FixedBytes(_) => quote! { ethabi::Token::FixedBytes(#name.as_ref().to_vec()) },
-		ParamType::Int(_) => quote! { ethabi::Token::Int(#name) },
-		ParamType::Uint(_) => quote! { ethabi::Token::Uint(#name) },
-		ParamType::Bool => quote! { ethabi::Token::Bool(#name) },
-		ParamType::String => quote! { ethabi::Token::String(#name) },
-		ParamType::Array(ref kind) => {
-			let inner_name = quote! { inner };
-			let inner_loop = to_token(&inner_name, kind);
-			quote! {
-				// note the double {{
-				{
-					let v = #name.into_iter().map(|#inner_name| #inner_loop).collect();
-					ethabi::Token::Array(v)
-				}
-			}
-		}
-		ParamType::FixedArray(ref kind, _) => {
-			let inner_name = quote! { inner };
-			let inner_loop = to_token(&inner_name, kind);
-			quote! {
-				// note the double {{
-				{
-					let v = #name.into_iter().map(|#inner_name| #inner_loop).collect();
-					ethabi::Token::FixedArray(v)
-				}
-			}
-		}
-		ParamType::Tuple(_) => {
-			unimplemented!("Tuples are not supported. https://github.com/openethereum/ethabi/issues/175")
-		}
-	}
-}
-
-fn from_token(kind: &ParamType, token: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
-	match *kind {
-		ParamType::Address => quote! { #token.into_address().expect(INTERNAL_ERR) },
-		ParamType::Bytes => quote! { #token.into_bytes().expect(INTERNAL_ERR) },
-		ParamType::FixedBytes(32) => quote! {
-			{
-				let mut result = [0u8; 32];
-				let v = #token.into_fixed_bytes().expect(INTERNAL_ERR);
-				result.copy_from_slice(&v);
-				ethabi::Hash::from(result)
-			}
-		},
-		ParamType::FixedBytes(size) => {
-			let size: syn::Index = size.into();
-			quote! {
-				{
-					let mut result = [0u8; #size];
-					let v = #token.into_fixed_bytes().expect(INTERNAL_ERR);
-					result.copy_from_slice(&v);
-					result
-				}
-			}
-		}
-		ParamType::Int(_) => quote! { #token.into_int().expect(INTERNAL_ERR) },
-		ParamType::Uint(_) => quote! { #token.into_uint().expect(INTERNAL_ERR) },
-		ParamType::Bool => quote! { #token.into_bool().expect(INTERNAL_ERR) },
-		ParamType::String => quote! { #token.into_string().expect(INTERNAL_ERR) },
-		ParamType::Array(ref kind) => {
-			let inner = quote! { inner };
-			let inner_loop = from_token(kind, &inner);
-			quote! {
-				#token.into_array().expect(INTERNAL_ERR).into_iter()
-					.map(|#inner| #inner_loop)
-					.collect()
-			}
-		}
-		ParamType::FixedArray(ref kind, size) => {
-			let inner = quote! { inner };
-			let inner_loop = from_token(kind, &inner);
-			let to_array = vec![quote! { iter.next() }; size];
-			quote! {
-				{
-					let iter = #token.to_array().expect(INTERNAL_ERR).into_iter()
-						.map(|#inner| #inner_loop);
-					[#(#to_array),*]
-				}
-			}
-		}
-		ParamType::Tuple(_) => {
-			unimplemented!("Tuples are not supported. https://github.com/openethereum/ethabi/issues/175")
-		}
-	}
-}
-
-fn input_names(inputs: &[Param]) -> Vec<syn::Ident> {
-	inputs
-		.iter()
-		.enumerate()
-		.map(|(index, param)| {
-			if param.name.is_empty() {
-				syn::Ident::new(&format!("param{}", index), Span::call_site())
-			} else {
-				syn::Ident::new(&rust_variable(&param.name), Span::call_site())
-			}
-		})
-		.collect()
-}
-
-fn get_template_names(kinds: &[proc_macro2::TokenStream]) -> Vec<syn::Ident> {
-	kinds.iter().enumerate().map(|(index, _)| syn::Ident::new(&format!("T{}", index), Span::call_site())).collect()
-}
-
-fn get_output_kinds(outputs: &[Param]) -> proc_macro2::TokenStream {
-	match outputs.len() {
-		0 => quote! {()},
-		1 => {
-			let t = rust_type(&outputs[0].kind);
-			quote! { #t }
-		}
-		_ => {
-			let outs: Vec<_> = outputs.iter().map(|param| rust_type(&param.kind)).collect();
-			quote! { (#(#outs),*) }
-		}
-	}
-}
-
-/// Convert input into a rust variable name.
-///
-/// Avoid using keywords by escaping them.
-fn rust_variable(name: &str) -> String {
-	// avoid keyword parameters
-	match name {
-		"self" => "_self".to_string(),
-		other => other.to_snake_case(),
-	}
-}
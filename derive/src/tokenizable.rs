@@ -0,0 +1,129 @@
+// Copyright 2015-2019 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use anyhow::anyhow;
+use ethabi::Result;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Generates `ethabi::Tokenizable` implementation mapping struct fields to tuple members.
+pub fn impl_tokenizable(ast: &syn::DeriveInput) -> Result<TokenStream> {
+	let fields = match ast.data {
+		syn::Data::Struct(ref data) => &data.fields,
+		_ => return Err(anyhow!("`derive(Tokenizable)` is only supported for structs").into()),
+	};
+
+	let name = &ast.ident;
+	let count = fields.len();
+	let kinds: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+	let members: Vec<_> = fields
+		.iter()
+		.enumerate()
+		.map(|(index, field)| match field.ident {
+			Some(ref ident) => quote! { #ident },
+			None => {
+				let index = syn::Index::from(index);
+				quote! { #index }
+			}
+		})
+		.collect();
+
+	let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+	let mut where_clause = where_clause.cloned().unwrap_or_else(|| syn::parse_quote! { where });
+	for kind in &kinds {
+		where_clause.predicates.push(syn::parse_quote! { #kind: ethabi::Tokenizable });
+	}
+
+	Ok(quote! {
+		impl #impl_generics ethabi::Tokenizable for #name #ty_generics #where_clause {
+			fn from_token(token: ethabi::Token) -> ::core::result::Result<Self, ethabi::Error> {
+				let tokens = match token {
+					ethabi::Token::Tuple(tokens) if tokens.len() == #count => tokens,
+					_ => return Err(ethabi::Error::InvalidData),
+				};
+
+				let mut tokens = tokens.into_iter();
+				Ok(#name {
+					#(#members: ethabi::Tokenizable::from_token(tokens.next().ok_or(ethabi::Error::InvalidData)?)?,)*
+				})
+			}
+
+			fn into_token(self) -> ethabi::Token {
+				ethabi::Token::Tuple(vec![#(ethabi::Tokenizable::into_token(self.#members)),*])
+			}
+		}
+
+		impl #impl_generics ethabi::TokenizableItem for #name #ty_generics #where_clause {}
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use quote::quote;
+
+	use super::impl_tokenizable;
+
+	#[test]
+	fn test_named_fields() {
+		let ast = syn::parse2(quote! {
+			struct Transfer {
+				to: ethabi::Address,
+				amount: ethabi::Uint,
+			}
+		})
+		.unwrap();
+
+		let expected = quote! {
+			impl ethabi::Tokenizable for Transfer
+			where
+				ethabi::Address: ethabi::Tokenizable,
+				ethabi::Uint: ethabi::Tokenizable
+			{
+				fn from_token(token: ethabi::Token) -> ::core::result::Result<Self, ethabi::Error> {
+					let tokens = match token {
+						ethabi::Token::Tuple(tokens) if tokens.len() == 2usize => tokens,
+						_ => return Err(ethabi::Error::InvalidData),
+					};
+
+					let mut tokens = tokens.into_iter();
+					Ok(Transfer {
+						to: ethabi::Tokenizable::from_token(tokens.next().ok_or(ethabi::Error::InvalidData)?)?,
+						amount: ethabi::Tokenizable::from_token(tokens.next().ok_or(ethabi::Error::InvalidData)?)?,
+					})
+				}
+
+				fn into_token(self) -> ethabi::Token {
+					ethabi::Token::Tuple(vec![
+						ethabi::Tokenizable::into_token(self.to),
+						ethabi::Tokenizable::into_token(self.amount)
+					])
+				}
+			}
+
+			impl ethabi::TokenizableItem for Transfer
+			where
+				ethabi::Address: ethabi::Tokenizable,
+				ethabi::Uint: ethabi::Tokenizable
+			{}
+		};
+
+		assert_eq!(expected.to_string(), impl_tokenizable(&ast).unwrap().to_string());
+	}
+
+	#[test]
+	fn test_enum_is_rejected() {
+		let ast = syn::parse2(quote! {
+			enum Foo {
+				A,
+			}
+		})
+		.unwrap();
+
+		assert!(impl_tokenizable(&ast).is_err());
+	}
+}
@@ -0,0 +1,123 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Contract custom error.
+
+use crate::{
+	decode,
+	encoder::write_tokens,
+	signature::{format_signature, short_signature},
+	Bytes, Error, Param, ParamType, Result, Token,
+};
+use serde::{Deserialize, Serialize};
+
+/// Custom error specification, e.g. `error InsufficientBalance(uint256 available, uint256 required)`,
+/// which contracts compiled with Solidity 0.8.4 or later revert with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbiError {
+	/// Error name.
+	#[serde(deserialize_with = "crate::util::sanitize_name::deserialize")]
+	pub name: String,
+	/// Error input.
+	pub inputs: Vec<Param>,
+}
+
+impl AbiError {
+	/// Returns all input params of given error.
+	fn param_types(&self) -> Vec<ParamType> {
+		self.inputs.iter().map(|p| p.kind.clone()).collect()
+	}
+
+	/// Returns the canonical signature of the error, e.g. `InsufficientBalance(uint256,uint256)`.
+	pub fn signature(&self) -> String {
+		format_signature(&self.name, self.inputs.iter().map(|param| &param.kind))
+	}
+
+	/// Returns the error selector, the first 4 bytes of the revert data.
+	pub fn selector(&self) -> [u8; 4] {
+		short_signature(&self.name, &self.param_types())
+	}
+
+	/// Prepares revert data of the error with given input params.
+	pub fn encode(&self, tokens: &[Token]) -> Result<Bytes> {
+		Token::check_types(tokens, &self.param_types())?;
+
+		let mut result = self.selector().to_vec();
+		write_tokens(&mut result, tokens);
+		Ok(result)
+	}
+
+	/// Parses revert data, i.e. the error selector followed by the ABI encoded input, to a list of tokens.
+	///
+	/// Fails if the selector does not match this error.
+	pub fn decode(&self, data: &[u8]) -> Result<Vec<Token>> {
+		if data.len() < 4 || data[..4] != self.selector() {
+			return Err(Error::InvalidData);
+		}
+
+		decode(&self.param_types(), &data[4..])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::AbiError;
+	use crate::{tests::assert_ser_de, Param, ParamType, Token};
+	use hex_literal::hex;
+
+	fn insufficient_balance() -> AbiError {
+		AbiError {
+			name: "InsufficientBalance".to_owned(),
+			inputs: vec![
+				Param { name: "available".to_owned(), kind: ParamType::Uint(256) },
+				Param { name: "required".to_owned(), kind: ParamType::Uint(256) },
+			],
+		}
+	}
+
+	#[test]
+	fn deserialize() {
+		let json = r#"{
+			"inputs": [
+				{ "name": "available", "type": "uint256" },
+				{ "name": "required", "type": "uint256" }
+			],
+			"name": "InsufficientBalance"
+		}"#;
+
+		let deserialized: AbiError = serde_json::from_str(json).unwrap();
+		assert_eq!(deserialized, insufficient_balance());
+
+		assert_ser_de(&deserialized);
+	}
+
+	#[test]
+	fn signature_and_selector() {
+		let error = insufficient_balance();
+		assert_eq!(error.signature(), "InsufficientBalance(uint256,uint256)");
+		assert_eq!(error.selector(), hex!("cf479181"));
+
+		let error_string = AbiError {
+			name: "Error".to_owned(),
+			inputs: vec![Param { name: "message".to_owned(), kind: ParamType::String }],
+		};
+		assert_eq!(error_string.selector(), hex!("08c379a0"));
+	}
+
+	#[test]
+	fn encode_decode() {
+		let error = insufficient_balance();
+		let tokens = vec![Token::Uint(1.into()), Token::Uint(2.into())];
+
+		let data = error.encode(&tokens).unwrap();
+		assert_eq!(data[..4], hex!("cf479181"));
+		assert_eq!(error.decode(&data).unwrap(), tokens);
+		assert!(error.decode(&data[4..]).is_err());
+		assert!(error.encode(&[Token::Bool(true)]).is_err());
+	}
+}
@@ -0,0 +1,122 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reference encoder used to audit the optimized one.
+//!
+//! Enabled with the `audit` feature. Every call to `encode` is repeated with the
+//! naive implementation below, which follows the specification literally, and
+//! any difference is reported with a panic.
+
+use crate::{Token, ADDRESS_LEN};
+
+/// Panics if `actual` differs from the reference encoding of `tokens`.
+pub(crate) fn check(tokens: &[Token], actual: &[u8]) {
+	let expected = encode(tokens);
+
+	if actual != expected {
+		panic!(
+			"ethabi audit: encoding mismatch for {:?}\n  encoder:   {}\n  reference: {}",
+			tokens,
+			hex::encode(actual),
+			hex::encode(&expected),
+		);
+	}
+}
+
+/// Encodes a sequence of tokens as described by the specification: static values
+/// in place, dynamic values as offsets into the tail.
+pub(crate) fn encode(tokens: &[Token]) -> Vec<u8> {
+	let encoded: Vec<_> = tokens.iter().map(encode_token).collect();
+	let heads_len: usize =
+		tokens.iter().zip(&encoded).map(|(token, encoded)| if token.is_dynamic() { 32 } else { encoded.len() }).sum();
+
+	let mut head = Vec::with_capacity(heads_len);
+	let mut tail = Vec::new();
+	for (token, encoded) in tokens.iter().zip(encoded) {
+		if token.is_dynamic() {
+			head.extend_from_slice(&word((heads_len + tail.len()) as u64));
+			tail.extend(encoded);
+		} else {
+			head.extend(encoded);
+		}
+	}
+
+	head.extend(tail);
+	head
+}
+
+fn encode_token(token: &Token) -> Vec<u8> {
+	match *token {
+		Token::Address(ref address) => {
+			let mut result = vec![0u8; 32 - ADDRESS_LEN];
+			result.extend_from_slice(address.as_bytes());
+			result
+		}
+		Token::Int(value) | Token::Uint(value) => {
+			let mut result = vec![0u8; 32];
+			value.to_big_endian(&mut result);
+			result
+		}
+		Token::Bool(value) => word(value as u64).to_vec(),
+		Token::FixedBytes(ref bytes) => padded(bytes),
+		Token::Bytes(ref bytes) => [word(bytes.len() as u64).to_vec(), padded(bytes)].concat(),
+		Token::String(ref s) => [word(s.len() as u64).to_vec(), padded(s.as_bytes())].concat(),
+		Token::Array(ref tokens) => [word(tokens.len() as u64).to_vec(), encode(tokens)].concat(),
+		Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) => encode(tokens),
+	}
+}
+
+fn word(value: u64) -> [u8; 32] {
+	let mut result = [0u8; 32];
+	result[24..].copy_from_slice(&value.to_be_bytes());
+	result
+}
+
+fn padded(bytes: &[u8]) -> Vec<u8> {
+	let mut result = bytes.to_vec();
+	result.resize(32 * bytes.len().div_ceil(32), 0);
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use hex_literal::hex;
+
+	use super::{check, encode};
+	use crate::{encoder, Token, Word};
+
+	#[test]
+	fn reference_matches_encoder() {
+		let tokens = vec![
+			Token::Address([0x11u8; 20].into()),
+			Token::FixedArray(vec![Token::String("foo".into()), Token::Bytes(vec![0xab; 40])]),
+			Token::Tuple(vec![Token::Uint(5.into()), Token::FixedBytes(vec![1, 2, 3])]),
+			Token::Array(vec![Token::Array(vec![Token::Bool(true)]), Token::Array(vec![])]),
+		];
+		assert_eq!(encode(&tokens), encoder::encode(&tokens));
+	}
+
+	#[test]
+	fn reference_encoding() {
+		let tokens = [Token::String("gavofyork".into())];
+		let expected = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000009
+			6761766f66796f726b0000000000000000000000000000000000000000000000
+		"
+		);
+		assert_eq!(encode(&tokens), expected.to_vec());
+	}
+
+	#[test]
+	#[should_panic(expected = "encoding mismatch")]
+	fn check_panics_on_mismatch() {
+		check(&[Token::Bool(true)], Word::from(false).as_ref());
+	}
+}
@@ -0,0 +1,63 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lenient decoding of `bool` outputs of tokens which do not follow ERC-20.
+
+use crate::{Error, Result};
+
+/// Output of a function declared to return `bool`, e.g. ERC-20 `transfer`, which
+/// some tokens implement without returning anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoolOutput {
+	/// The function returned `true`, or another non-zero word.
+	Success,
+	/// The function returned `false`.
+	ReturnedFalse,
+	/// The function returned no data, which such tokens do on success.
+	NoReturnData,
+}
+
+impl BoolOutput {
+	/// Returns whether the call succeeded, i.e. returned `true` or nothing, as assumed
+	/// by e.g. OpenZeppelin's `SafeERC20`.
+	pub fn is_success(self) -> bool {
+		self != BoolOutput::ReturnedFalse
+	}
+}
+
+/// Decodes the output of a function declared to return `bool`, accepting empty output and
+/// reading any non-zero first word as `true`.
+///
+/// Fails only if the output is not empty but shorter than a word.
+pub fn decode_bool_or_empty(output: &[u8]) -> Result<BoolOutput> {
+	match output.len() {
+		0 => Ok(BoolOutput::NoReturnData),
+		len if len < 32 => Err(Error::InvalidData),
+		_ if output[..32].iter().all(|byte| *byte == 0) => Ok(BoolOutput::ReturnedFalse),
+		_ => Ok(BoolOutput::Success),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{decode_bool_or_empty, BoolOutput};
+	use crate::{encode, Token};
+
+	#[test]
+	fn test_decode_bool_or_empty() {
+		assert_eq!(decode_bool_or_empty(&encode(&[Token::Bool(true)])).unwrap(), BoolOutput::Success);
+		assert_eq!(decode_bool_or_empty(&encode(&[Token::Bool(false)])).unwrap(), BoolOutput::ReturnedFalse);
+		assert_eq!(decode_bool_or_empty(&[]).unwrap(), BoolOutput::NoReturnData);
+		assert_eq!(decode_bool_or_empty(&encode(&[Token::Uint(7.into())])).unwrap(), BoolOutput::Success);
+		assert!(decode_bool_or_empty(&[1]).is_err());
+
+		assert!(BoolOutput::Success.is_success());
+		assert!(BoolOutput::NoReturnData.is_success());
+		assert!(!BoolOutput::ReturnedFalse.is_success());
+	}
+}
@@ -0,0 +1,154 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Calldata statistics for estimating data fees.
+
+use crate::{decoder::as_usize, Function, Result, Word};
+
+/// Byte statistics of calldata.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CalldataProfile {
+	/// Number of bytes.
+	pub len: usize,
+	/// Number of zero bytes.
+	pub zero_bytes: usize,
+	/// Number of non-zero bytes.
+	pub non_zero_bytes: usize,
+	/// Number of 32 byte words which are all zero, not counting the selector.
+	pub zero_words: usize,
+	/// Whether the data is a 4 byte selector followed by whole words, or whole words only.
+	pub word_aligned: bool,
+}
+
+impl CalldataProfile {
+	/// Gas charged for the data on L1, 4 per zero byte and 16 per non-zero byte (EIP-2028).
+	pub fn gas(&self) -> u64 {
+		4 * self.zero_bytes as u64 + 16 * self.non_zero_bytes as u64
+	}
+
+	/// Estimated size of the data once compressed, as used by L1 data fee formulas of
+	/// rollups which assume a zero byte compresses to a quarter of a non-zero one, i.e. `gas / 16`.
+	pub fn estimated_compressed_len(&self) -> u64 {
+		self.gas() / 16
+	}
+}
+
+/// Computes the byte statistics of calldata.
+pub fn analyze_calldata(data: &[u8]) -> CalldataProfile {
+	let zero_bytes = data.iter().filter(|byte| **byte == 0).count();
+	let words = match data.len() % 32 {
+		4 => &data[4..],
+		_ => data,
+	};
+
+	CalldataProfile {
+		len: data.len(),
+		zero_bytes,
+		non_zero_bytes: data.len() - zero_bytes,
+		zero_words: words.chunks_exact(32).filter(|word| word.iter().all(|byte| *byte == 0)).count(),
+		word_aligned: words.len() % 32 == 0,
+	}
+}
+
+/// Computes the byte statistics of each param of a call of `function`.
+///
+/// A param owns its words in the head and, if it is dynamic, its tail, which extends
+/// to the start of the next tail. The selector is not attributed to any param.
+pub fn analyze_calldata_params(function: &Function, data: &[u8]) -> Result<Vec<(String, CalldataProfile)>> {
+	function.decode_calldata(data)?;
+	let args = &data[4..];
+
+	let mut heads = Vec::with_capacity(function.inputs.len());
+	let mut tails = Vec::new();
+	let mut head_offset = 0;
+	for (index, param) in function.inputs.iter().enumerate() {
+		let slots = crate::util::head_slots(&param.kind);
+		heads.push(head_offset..head_offset + 32 * slots);
+		if param.kind.is_dynamic() {
+			let mut word = Word::default();
+			word.copy_from_slice(&args[head_offset..head_offset + 32]);
+			tails.push((as_usize(&word)?, index));
+		}
+		head_offset += 32 * slots;
+	}
+
+	tails.sort_unstable();
+	let mut tail_ranges = vec![None; function.inputs.len()];
+	for (position, &(start, index)) in tails.iter().enumerate() {
+		let end = tails.get(position + 1).map_or(args.len(), |&(next, _)| next);
+		tail_ranges[index] = Some(start..end);
+	}
+
+	let result = function
+		.inputs
+		.iter()
+		.zip(heads)
+		.zip(tail_ranges)
+		.map(|((param, head), tail)| {
+			let mut bytes = args[head].to_vec();
+			if let Some(tail) = tail {
+				bytes.extend_from_slice(&args[tail]);
+			}
+			(param.name.clone(), analyze_calldata(&bytes))
+		})
+		.collect();
+	Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{analyze_calldata, analyze_calldata_params, CalldataProfile};
+	use crate::{Address, Function, Param, ParamType, StateMutability, Token};
+
+	#[test]
+	fn test_analyze_calldata() {
+		let mut data = vec![0xa9, 0x05, 0x9c, 0xbb];
+		data.extend_from_slice(&[0u8; 32]);
+		data.extend_from_slice(&[1u8; 32]);
+
+		let profile = analyze_calldata(&data);
+		assert_eq!(
+			profile,
+			CalldataProfile { len: 68, zero_bytes: 32, non_zero_bytes: 36, zero_words: 1, word_aligned: true }
+		);
+		assert_eq!(profile.gas(), 4 * 32 + 16 * 36);
+		assert_eq!(profile.estimated_compressed_len(), 44);
+		assert!(!analyze_calldata(&data[..67]).word_aligned);
+	}
+
+	#[test]
+	fn test_analyze_calldata_params() {
+		#[allow(deprecated)]
+		let function = Function {
+			name: "f".to_owned(),
+			inputs: vec![
+				Param { name: "data".to_owned(), kind: ParamType::Bytes },
+				Param { name: "to".to_owned(), kind: ParamType::Address },
+				Param { name: "ids".to_owned(), kind: ParamType::Array(Box::new(ParamType::Uint(256))) },
+			],
+			outputs: vec![],
+			constant: false,
+			state_mutability: StateMutability::NonPayable,
+		};
+		let calldata = function
+			.encode_input(&[
+				Token::Bytes(vec![0xff; 33]),
+				Token::Address(Address::repeat_byte(0x11)),
+				Token::Array(vec![Token::Uint(1.into())]),
+			])
+			.unwrap();
+
+		let params = analyze_calldata_params(&function, &calldata).unwrap();
+		let lens: Vec<_> = params.iter().map(|(name, profile)| (name.as_str(), profile.len)).collect();
+		// `data` owns its offset, length and two words of content, `ids` its offset, length and element
+		assert_eq!(lens, [("data", 128), ("to", 32), ("ids", 96)]);
+		assert_eq!(params.iter().map(|(_, profile)| profile.len).sum::<usize>() + 4, calldata.len());
+		assert_eq!(params[1].1.non_zero_bytes, 20);
+		assert!(analyze_calldata_params(&function, &calldata[..calldata.len() - 1]).is_err());
+	}
+}
@@ -0,0 +1,69 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! EIP-55 mixed-case checksums of addresses.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::Address;
+use sha3::{Digest, Keccak256};
+
+/// Formats the address as hex with an EIP-55 checksum, without `0x` prefix like `Token` displays
+/// addresses, e.g. `5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed`.
+pub fn checksum_address(address: &Address) -> String {
+	let hex = hex::encode(address);
+	let hash = Keccak256::digest(hex.as_bytes());
+	hex.char_indices()
+		.map(|(index, ch)| {
+			let nibble = match index % 2 {
+				0 => hash[index / 2] >> 4,
+				_ => hash[index / 2] & 0xf,
+			};
+			match nibble >= 8 {
+				true => ch.to_ascii_uppercase(),
+				false => ch,
+			}
+		})
+		.collect()
+}
+
+/// Returns whether the hex of an address has a valid checksum or none, i.e. is all lower or
+/// upper case.
+pub(crate) fn has_valid_checksum(hex: &str, address: &Address) -> bool {
+	let lower = hex.bytes().any(|byte| byte.is_ascii_lowercase());
+	let upper = hex.bytes().any(|byte| byte.is_ascii_uppercase());
+	!(lower && upper) || checksum_address(address) == hex
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{checksum_address, has_valid_checksum};
+	use crate::Address;
+
+	#[test]
+	fn test_checksum_address() {
+		// test vectors of EIP-55
+		for expected in [
+			"5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+			"fB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+			"dbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+			"D1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+		] {
+			let address: Address = expected.parse().unwrap();
+			assert_eq!(checksum_address(&address), expected);
+			assert!(has_valid_checksum(expected, &address));
+			assert!(has_valid_checksum(&expected.to_lowercase(), &address));
+			assert!(has_valid_checksum(&expected.to_uppercase(), &address));
+
+			let mut invalid = expected.to_owned();
+			let upper = invalid.find(|c: char| c.is_ascii_uppercase()).unwrap();
+			invalid[upper..upper + 1].make_ascii_lowercase();
+			assert!(!has_valid_checksum(&invalid, &address));
+		}
+	}
+}
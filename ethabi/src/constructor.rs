@@ -7,7 +7,7 @@
 // except according to those terms.
 
 //! Contract constructor call builder.
-use crate::{encode, Bytes, Error, Param, ParamType, Result, Token};
+use crate::{decode, encoder::write_tokens, Bytes, Param, ParamType, Result, Token};
 use serde::{Deserialize, Serialize};
 
 /// Contract constructor specification.
@@ -27,10 +27,30 @@ impl Constructor {
 	pub fn encode_input(&self, code: Bytes, tokens: &[Token]) -> Result<Bytes> {
 		let params = self.param_types();
 
-		if Token::types_check(tokens, &params) {
-			Ok(code.into_iter().chain(encode(tokens)).collect())
-		} else {
-			Err(Error::InvalidData)
-		}
+		Token::check_types(tokens, &params)?;
+		let mut result = code;
+		write_tokens(&mut result, tokens);
+		Ok(result)
+	}
+
+	/// Parses the ABI constructor input, i.e. the contract creation input following the code,
+	/// to a list of tokens.
+	pub fn decode_input(&self, data: &[u8]) -> Result<Vec<Token>> {
+		decode(&self.param_types(), data)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Constructor;
+	use crate::{Param, ParamType, Token};
+
+	#[test]
+	fn test_decode_input() {
+		let constructor = Constructor { inputs: vec![Param { name: "a".to_owned(), kind: ParamType::Uint(256) }] };
+		let code = vec![0x60, 0x80];
+		let input = constructor.encode_input(code.clone(), &[Token::Uint(42.into())]).unwrap();
+		assert_eq!(constructor.decode_input(&input[code.len()..]).unwrap(), vec![Token::Uint(42.into())]);
+		assert!(constructor.decode_input(&input[code.len()..code.len() + 31]).is_err());
 	}
 }
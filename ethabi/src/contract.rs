@@ -6,7 +6,10 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::{errors, operation::Operation, Constructor, Error, Event, Function};
+use crate::{
+	errors, human_readable, operation::Operation, AbiError, Constructor, Error, Event, Function, Hash, Log, RawLog,
+	StateMutability, Token,
+};
 use serde::{
 	de::{SeqAccess, Visitor},
 	ser::SerializeSeq,
@@ -27,10 +30,12 @@ pub struct Contract {
 	pub functions: HashMap<String, Vec<Function>>,
 	/// Contract events, maps signature to event.
 	pub events: HashMap<String, Vec<Event>>,
+	/// Contract custom errors, maps name to error.
+	pub errors: HashMap<String, Vec<AbiError>>,
 	/// Contract has receive function.
 	pub receive: bool,
-	/// Contract has fallback function.
-	pub fallback: bool,
+	/// State mutability of the contract's fallback function, `payable` or `nonpayable`, if it has one.
+	pub fallback: Option<StateMutability>,
 }
 
 impl<'a> Deserialize<'a> for Contract {
@@ -57,23 +62,7 @@ impl<'a> Visitor<'a> for ContractVisitor {
 	{
 		let mut result = Contract::default();
 		while let Some(operation) = seq.next_element()? {
-			match operation {
-				Operation::Constructor(constructor) => {
-					result.constructor = Some(constructor);
-				}
-				Operation::Function(func) => {
-					result.functions.entry(func.name.clone()).or_default().push(func);
-				}
-				Operation::Event(event) => {
-					result.events.entry(event.name.clone()).or_default().push(event);
-				}
-				Operation::Fallback => {
-					result.fallback = true;
-				}
-				Operation::Receive => {
-					result.receive = true;
-				}
-			}
+			result.insert(operation);
 		}
 
 		Ok(result)
@@ -98,11 +87,20 @@ impl Serialize for Contract {
 			#[serde(rename = "event")]
 			Event(&'a Event),
 
+			#[serde(rename = "error")]
+			Error(&'a AbiError),
+
 			#[serde(rename = "fallback")]
-			Fallback,
+			Fallback {
+				#[serde(rename = "stateMutability")]
+				state_mutability: StateMutability,
+			},
 
 			#[serde(rename = "receive")]
-			Receive,
+			Receive {
+				#[serde(rename = "stateMutability")]
+				state_mutability: StateMutability,
+			},
 		}
 
 		let mut seq = serializer.serialize_seq(None)?;
@@ -111,24 +109,32 @@ impl Serialize for Contract {
 			seq.serialize_element(&OperationRef::Constructor(constructor))?;
 		}
 
-		for functions in self.functions.values() {
-			for function in functions {
-				seq.serialize_element(&OperationRef::Function(function))?;
-			}
+		// sorted by name, so that the output does not depend on the hash map order
+		let mut functions: Vec<_> = self.functions.iter().collect();
+		functions.sort_by_key(|(name, _)| *name);
+		for function in functions.into_iter().flat_map(|(_, functions)| functions) {
+			seq.serialize_element(&OperationRef::Function(function))?;
 		}
 
-		for events in self.events.values() {
-			for event in events {
-				seq.serialize_element(&OperationRef::Event(event))?;
-			}
+		let mut events: Vec<_> = self.events.iter().collect();
+		events.sort_by_key(|(name, _)| *name);
+		for event in events.into_iter().flat_map(|(_, events)| events) {
+			seq.serialize_element(&OperationRef::Event(event))?;
+		}
+
+		let mut errors: Vec<_> = self.errors.iter().collect();
+		errors.sort_by_key(|(name, _)| *name);
+		for error in errors.into_iter().flat_map(|(_, errors)| errors) {
+			seq.serialize_element(&OperationRef::Error(error))?;
 		}
 
 		if self.receive {
-			seq.serialize_element(&OperationRef::Receive)?;
+			// receive functions are always payable
+			seq.serialize_element(&OperationRef::Receive { state_mutability: StateMutability::Payable })?;
 		}
 
-		if self.fallback {
-			seq.serialize_element(&OperationRef::Fallback)?;
+		if let Some(state_mutability) = self.fallback {
+			seq.serialize_element(&OperationRef::Fallback { state_mutability })?;
 		}
 
 		seq.end()
@@ -136,11 +142,119 @@ impl Serialize for Contract {
 }
 
 impl Contract {
+	/// Creates builder of a contract defined in code.
+	pub fn builder() -> ContractBuilder {
+		ContractBuilder::default()
+	}
+
 	/// Loads contract from json.
 	pub fn load<T: io::Read>(reader: T) -> errors::Result<Self> {
 		serde_json::from_reader(reader).map_err(From::from)
 	}
 
+	/// Loads contract from json, skipping malformed entries.
+	///
+	/// Returns the contract built from the valid entries together with the
+	/// errors of all the others, each paired with the index of its entry.
+	/// Fails only if the json is not an array.
+	pub fn load_lenient<T: io::Read>(reader: T) -> errors::Result<(Self, Vec<(usize, Error)>)> {
+		let entries: Vec<serde_json::Value> = serde_json::from_reader(reader)?;
+		let mut result = Contract::default();
+		let mut errors = Vec::new();
+		for (index, entry) in entries.into_iter().enumerate() {
+			match serde_json::from_value(entry) {
+				Ok(operation) => result.insert(operation),
+				Err(err) => errors.push((index, err.into())),
+			}
+		}
+
+		Ok((result, errors))
+	}
+
+	/// Loads contract from human-readable signatures, one per line.
+	///
+	/// Blank lines and comments starting with `//` or `#` are ignored, e.g.
+	///
+	/// ```text
+	/// // ERC20 subset
+	/// function transfer(address to, uint256 amount) external returns (bool)
+	/// event Transfer(address indexed from, address indexed to, uint256 value)
+	/// ```
+	pub fn load_text<T: io::Read>(mut reader: T) -> errors::Result<Self> {
+		let mut text = String::new();
+		reader.read_to_string(&mut text).map_err(|err| Error::Other(err.into()))?;
+
+		let mut result = Contract::default();
+		for (index, line) in text.lines().enumerate() {
+			let line = line.split("//").next().and_then(|line| line.split('#').next()).unwrap_or_default().trim();
+			if line.is_empty() {
+				continue;
+			}
+
+			let operation = human_readable::parse_operation(line)
+				.map_err(|err| Error::Other(anyhow::anyhow!("Invalid signature on line {}: {}", index + 1, err)))?;
+			result.insert(operation);
+		}
+
+		Ok(result)
+	}
+
+	/// Formats the contract as human-readable signatures, one per line, which `load_text` loads.
+	///
+	/// Items are ordered as in the json serialization, the functions, events and errors sorted by name.
+	pub fn to_text(&self) -> String {
+		let mut lines = Vec::new();
+		if let Some(constructor) = &self.constructor {
+			lines.push(human_readable::format_constructor(constructor));
+		}
+
+		let mut functions: Vec<_> = self.functions.iter().collect();
+		functions.sort_by_key(|(name, _)| *name);
+		lines.extend(functions.into_iter().flat_map(|(_, functions)| functions).map(human_readable::format_function));
+
+		let mut events: Vec<_> = self.events.iter().collect();
+		events.sort_by_key(|(name, _)| *name);
+		lines.extend(events.into_iter().flat_map(|(_, events)| events).map(human_readable::format_event));
+
+		let mut errors: Vec<_> = self.errors.iter().collect();
+		errors.sort_by_key(|(name, _)| *name);
+		lines.extend(errors.into_iter().flat_map(|(_, errors)| errors).map(human_readable::format_error));
+
+		if self.receive {
+			lines.push("receive() external payable".to_owned());
+		}
+		match self.fallback {
+			Some(StateMutability::Payable) => lines.push("fallback() payable".to_owned()),
+			Some(_) => lines.push("fallback()".to_owned()),
+			None => (),
+		}
+
+		lines.join("\n")
+	}
+
+	fn insert(&mut self, operation: Operation) {
+		match operation {
+			Operation::Constructor(constructor) => {
+				self.constructor = Some(constructor);
+			}
+			Operation::Function(func) => {
+				self.functions.entry(func.name.clone()).or_default().push(func);
+			}
+			Operation::Event(event) => {
+				self.events.entry(event.name.clone()).or_default().push(event);
+			}
+			Operation::Error(error) => {
+				self.errors.entry(error.name.clone()).or_default().push(error);
+			}
+			Operation::Fallback { state_mutability } => {
+				self.fallback = Some(state_mutability);
+			}
+			Operation::Receive => {
+				self.receive = true;
+			}
+		}
+	}
+
 	/// Creates constructor call builder.
 	pub fn constructor(&self) -> Option<&Constructor> {
 		self.constructor.as_ref()
@@ -162,11 +276,152 @@ impl Contract {
 		self.events.get(name).ok_or_else(|| Error::InvalidName(name.to_owned()))
 	}
 
+	/// Get the custom error named `name`, the first if there are overloaded versions of the same error.
+	pub fn error(&self, name: &str) -> errors::Result<&AbiError> {
+		self.errors.get(name).into_iter().flatten().next().ok_or_else(|| Error::InvalidName(name.to_owned()))
+	}
+
+	/// Get all custom errors named `name`.
+	pub fn errors_by_name(&self, name: &str) -> errors::Result<&Vec<AbiError>> {
+		self.errors.get(name).ok_or_else(|| Error::InvalidName(name.to_owned()))
+	}
+
+	/// Get the custom error with the given selector, the first 4 bytes of its revert data.
+	pub fn error_by_selector(&self, selector: [u8; 4]) -> errors::Result<&AbiError> {
+		self.errors()
+			.find(|error| error.selector() == selector)
+			.ok_or_else(|| Error::InvalidName(format!("0x{}", hex::encode(selector))))
+	}
+
+	/// Decodes revert data of a custom error of the contract, returning the error and its arguments.
+	pub fn decode_error(&self, data: &[u8]) -> errors::Result<(&AbiError, Vec<Token>)> {
+		if data.len() < 4 {
+			return Err(Error::InvalidData);
+		}
+
+		let mut selector = [0u8; 4];
+		selector.copy_from_slice(&data[..4]);
+		let error = self.error_by_selector(selector)?;
+		Ok((error, error.decode(data)?))
+	}
+
 	/// Get all functions named `name`.
 	pub fn functions_by_name(&self, name: &str) -> errors::Result<&Vec<Function>> {
 		self.functions.get(name).ok_or_else(|| Error::InvalidName(name.to_owned()))
 	}
 
+	/// Get the function with the given selector, the first 4 bytes of its calldata.
+	pub fn function_by_selector(&self, selector: [u8; 4]) -> errors::Result<&Function> {
+		self.functions()
+			.find(|function| function.selector() == selector)
+			.ok_or_else(|| Error::InvalidName(format!("0x{}", hex::encode(selector))))
+	}
+
+	/// Decodes transaction calldata, returning the called function and its arguments.
+	pub fn decode_input(&self, data: &[u8]) -> errors::Result<(&Function, Vec<Token>)> {
+		if data.len() < 4 {
+			return Err(Error::InvalidData);
+		}
+
+		let mut selector = [0u8; 4];
+		selector.copy_from_slice(&data[..4]);
+		let function = self.function_by_selector(selector)?;
+		Ok((function, function.decode_input(&data[4..])?))
+	}
+
+	/// Get the non-anonymous event whose logs have the given first topic.
+	pub fn event_by_topic0(&self, topic0: Hash) -> errors::Result<&Event> {
+		self.events()
+			.find(|event| !event.anonymous && event.topic0() == topic0)
+			.ok_or_else(|| Error::InvalidName(format!("{:x}", topic0)))
+	}
+
+	/// Parses the log of any non-anonymous event of the contract, returning the event and the parsed log.
+	pub fn parse_log(&self, log: RawLog) -> errors::Result<(&Event, Log)> {
+		let topic0 = *log.topics.first().ok_or(Error::InvalidData)?;
+		let event = self.event_by_topic0(topic0)?;
+		Ok((event, event.parse_log(log)?))
+	}
+
+	/// Combines the ABIs of two contracts, e.g. of a proxy and its implementation.
+	///
+	/// Fails if both contracts define a function or custom error with the same selector, an
+	/// event with the same signature or a constructor, unless the definitions are equal.
+	pub fn merge(&self, other: &Contract) -> errors::Result<Contract> {
+		self.merge_with(other, MergePolicy::Fail)
+	}
+
+	/// Combines the ABIs of two contracts, resolving conflicts according to `policy`.
+	pub fn merge_with(&self, other: &Contract, policy: MergePolicy) -> errors::Result<Contract> {
+		let conflict = |message: String| match policy {
+			MergePolicy::Fail => Err(Error::Other(anyhow::anyhow!("Cannot merge contracts, {}", message))),
+			MergePolicy::PreferLeft => Ok(()),
+		};
+
+		let mut result = self.clone();
+		for function in other.functions() {
+			match self.functions().find(|existing| existing.selector() == function.selector()) {
+				Some(existing) if existing == function => (),
+				Some(existing) => {
+					conflict(format!("function `{}` collides with `{}`", function.signature(), existing.signature()))?
+				}
+				None => result.insert(Operation::Function(function.clone())),
+			}
+		}
+
+		for event in other.events() {
+			match self.events().find(|existing| existing.signature() == event.signature()) {
+				Some(existing) if existing == event => (),
+				Some(_) => conflict(format!("event `{}` is defined differently", event.name))?,
+				None => result.insert(Operation::Event(event.clone())),
+			}
+		}
+
+		for error in other.errors() {
+			match self.errors().find(|existing| existing.selector() == error.selector()) {
+				Some(existing) if existing == error => (),
+				Some(existing) => {
+					conflict(format!("error `{}` collides with `{}`", error.signature(), existing.signature()))?
+				}
+				None => result.insert(Operation::Error(error.clone())),
+			}
+		}
+
+		match (&self.constructor, &other.constructor) {
+			(Some(existing), Some(constructor)) if existing != constructor => {
+				conflict("constructors are defined differently".to_owned())?
+			}
+			(None, Some(constructor)) => result.constructor = Some(constructor.clone()),
+			_ => (),
+		}
+
+		match (self.fallback, other.fallback) {
+			(Some(existing), Some(fallback)) if existing != fallback => {
+				conflict("fallback functions are defined differently".to_owned())?
+			}
+			(None, Some(fallback)) => result.fallback = Some(fallback),
+			_ => (),
+		}
+
+		result.receive |= other.receive;
+		Ok(result)
+	}
+
+	/// Returns the state mutability of the fallback function, `payable` or `nonpayable`,
+	/// or `None` if the contract has none.
+	pub fn fallback(&self) -> Option<StateMutability> {
+		self.fallback
+	}
+
+	/// Returns the state mutability of the receive function, which is always `payable`,
+	/// or `None` if the contract has none.
+	pub fn receive(&self) -> Option<StateMutability> {
+		match self.receive {
+			true => Some(StateMutability::Payable),
+			false => None,
+		}
+	}
+
 	/// Iterate over all functions of the contract in arbitrary order.
 	pub fn functions(&self) -> Functions {
 		Functions(self.functions.values().flatten())
@@ -176,6 +431,70 @@ impl Contract {
 	pub fn events(&self) -> Events {
 		Events(self.events.values().flatten())
 	}
+
+	/// Iterate over all custom errors of the contract in arbitrary order.
+	pub fn errors(&self) -> AbiErrors<'_> {
+		AbiErrors(self.errors.values().flatten())
+	}
+}
+
+/// How `Contract::merge_with` resolves definitions which differ between the contracts.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+	/// Return an error.
+	#[default]
+	Fail,
+	/// Keep the definition of the contract `merge_with` is called on.
+	PreferLeft,
+}
+
+/// Builder of a contract defined in code, e.g. in tests.
+#[derive(Debug, Default, Clone)]
+pub struct ContractBuilder {
+	contract: Contract,
+}
+
+impl ContractBuilder {
+	/// Adds a function, which may overload the functions added before.
+	pub fn add_function(mut self, function: Function) -> Self {
+		self.contract.insert(Operation::Function(function));
+		self
+	}
+
+	/// Adds an event.
+	pub fn add_event(mut self, event: Event) -> Self {
+		self.contract.insert(Operation::Event(event));
+		self
+	}
+
+	/// Adds a custom error.
+	pub fn add_error(mut self, error: AbiError) -> Self {
+		self.contract.insert(Operation::Error(error));
+		self
+	}
+
+	/// Sets the constructor, replacing the previous one.
+	pub fn set_constructor(mut self, constructor: Constructor) -> Self {
+		self.contract.insert(Operation::Constructor(constructor));
+		self
+	}
+
+	/// Sets whether the contract has a receive function.
+	pub fn receive(mut self, receive: bool) -> Self {
+		self.contract.receive = receive;
+		self
+	}
+
+	/// Sets the state mutability of the fallback function of the contract, or removes it with `None`.
+	pub fn fallback(mut self, fallback: Option<StateMutability>) -> Self {
+		self.contract.fallback = fallback;
+		self
+	}
+
+	/// Returns the contract.
+	pub fn build(self) -> Contract {
+		self.contract
+	}
 }
 
 /// Contract functions iterator.
@@ -200,10 +519,24 @@ impl<'a> Iterator for Events<'a> {
 	}
 }
 
+/// Contract custom errors iterator.
+pub struct AbiErrors<'a>(Flatten<Values<'a, String, Vec<AbiError>>>);
+
+impl<'a> Iterator for AbiErrors<'a> {
+	type Item = &'a AbiError;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next()
+	}
+}
+
 #[cfg(test)]
 #[allow(deprecated)]
 mod test {
-	use crate::{tests::assert_ser_de, Constructor, Contract, Event, EventParam, Function, Param, ParamType};
+	use crate::{
+		tests::assert_ser_de, Constructor, Contract, Event, EventParam, Function, Hash, LogParam, MergePolicy, Param,
+		ParamType, RawLog, StateMutability, Token,
+	};
 	use std::{collections::HashMap, iter::FromIterator};
 
 	#[test]
@@ -218,8 +551,9 @@ mod test {
 				constructor: None,
 				functions: HashMap::new(),
 				events: HashMap::new(),
+				errors: HashMap::new(),
 				receive: false,
-				fallback: false,
+				fallback: None,
 			}
 		);
 
@@ -252,8 +586,9 @@ mod test {
 				}),
 				functions: HashMap::new(),
 				events: HashMap::new(),
+				errors: HashMap::new(),
 				receive: false,
-				fallback: false,
+				fallback: None,
 			}
 		);
 
@@ -318,8 +653,9 @@ mod test {
 					)
 				]),
 				events: HashMap::new(),
+				errors: HashMap::new(),
 				receive: false,
-				fallback: false,
+				fallback: None,
 			}
 		);
 
@@ -381,8 +717,9 @@ mod test {
 					]
 				)]),
 				events: HashMap::new(),
+				errors: HashMap::new(),
 				receive: false,
-				fallback: false,
+				fallback: None,
 			}
 		);
 
@@ -448,8 +785,9 @@ mod test {
 						}]
 					)
 				]),
+				errors: HashMap::new(),
 				receive: false,
-				fallback: false,
+				fallback: None,
 			}
 		);
 
@@ -512,8 +850,9 @@ mod test {
 						}
 					]
 				)]),
+				errors: HashMap::new(),
 				receive: false,
-				fallback: false,
+				fallback: None,
 			}
 		);
 
@@ -536,8 +875,9 @@ mod test {
 				constructor: None,
 				functions: HashMap::new(),
 				events: HashMap::new(),
+				errors: HashMap::new(),
 				receive: true,
-				fallback: false,
+				fallback: None,
 			}
 		);
 
@@ -560,11 +900,286 @@ mod test {
 				constructor: None,
 				functions: HashMap::new(),
 				events: HashMap::new(),
+				errors: HashMap::new(),
 				receive: false,
-				fallback: true,
+				fallback: Some(StateMutability::NonPayable),
 			}
 		);
+		assert_eq!(deserialized.fallback(), Some(StateMutability::NonPayable));
+		assert_eq!(deserialized.receive(), None);
+
+		assert_ser_de(&deserialized);
+	}
+
+	#[test]
+	fn payable_fallback_and_receive() {
+		let json = r#"
+			[
+				{ "type": "fallback", "stateMutability": "payable" },
+				{ "type": "receive", "stateMutability": "payable" }
+			]
+		"#;
+
+		let deserialized: Contract = serde_json::from_str(json).unwrap();
+		assert_eq!(deserialized.fallback(), Some(StateMutability::Payable));
+		assert_eq!(deserialized.receive(), Some(StateMutability::Payable));
 
 		assert_ser_de(&deserialized);
 	}
+
+	#[test]
+	fn custom_errors() {
+		let json = r#"
+			[
+				{ "type": "error", "name": "Unauthorized", "inputs": [{ "name": "caller", "type": "address" }] },
+				{ "type": "error", "name": "Expired", "inputs": [] }
+			]
+		"#;
+
+		let contract = Contract::load(json.as_bytes()).unwrap();
+		let unauthorized = contract.error("Unauthorized").unwrap();
+		assert_eq!(unauthorized.signature(), "Unauthorized(address)");
+		assert_eq!(contract.errors().count(), 2);
+		assert_eq!(contract.error_by_selector(unauthorized.selector()).unwrap(), unauthorized);
+
+		let data = unauthorized.encode(&[Token::Address([0x11; 20].into())]).unwrap();
+		let (error, tokens) = contract.decode_error(&data).unwrap();
+		assert_eq!(error, unauthorized);
+		assert_eq!(tokens, vec![Token::Address([0x11; 20].into())]);
+
+		assert!(contract.error("Missing").is_err());
+		assert!(contract.decode_error(&data[..3]).is_err());
+		assert_ser_de(&contract);
+	}
+
+	#[test]
+	fn decode_input() {
+		let json = r#"
+			[
+				{ "type": "function", "name": "foo", "inputs": [{ "name": "a", "type": "uint256" }], "outputs": [] },
+				{ "type": "function", "name": "foo", "inputs": [{ "name": "a", "type": "bool" }], "outputs": [] },
+				{ "type": "function", "name": "bar", "inputs": [], "outputs": [] }
+			]
+		"#;
+
+		let contract = Contract::load(json.as_bytes()).unwrap();
+		let foo =
+			contract.functions_by_name("foo").unwrap().iter().find(|f| f.inputs[0].kind == ParamType::Bool).unwrap();
+		let calldata = foo.encode_input(&[Token::Bool(true)]).unwrap();
+
+		assert_eq!(contract.function_by_selector(foo.selector()).unwrap(), foo);
+		let (function, tokens) = contract.decode_input(&calldata).unwrap();
+		assert_eq!(function, foo);
+		assert_eq!(tokens, vec![Token::Bool(true)]);
+
+		assert!(contract.function_by_selector([0; 4]).is_err());
+		assert!(contract.decode_input(&calldata[..3]).is_err());
+
+		// functions changed after loading are found too
+		let mut contract = contract;
+		let bar = contract.functions.get_mut("bar").unwrap().first_mut().unwrap();
+		bar.inputs.push(Param { name: "b".to_owned(), kind: ParamType::Address });
+		let bar = bar.clone();
+		assert_eq!(contract.function_by_selector(bar.selector()).unwrap(), &bar);
+		assert!(contract.function_by_selector(Function::from_signature("bar()").unwrap().selector()).is_err());
+	}
+
+	#[test]
+	fn parse_log() {
+		let json = r#"
+			[
+				{ "type": "event", "name": "Foo", "inputs": [{ "name": "a", "type": "uint256", "indexed": true }], "anonymous": false },
+				{ "type": "event", "name": "Bar", "inputs": [{ "name": "b", "type": "bool", "indexed": false }], "anonymous": false }
+			]
+		"#;
+
+		let contract = Contract::load(json.as_bytes()).unwrap();
+		let bar = contract.event("Bar").unwrap();
+		let log = RawLog { topics: vec![bar.topic0()], data: crate::encode(&[Token::Bool(true)]) };
+
+		let (event, parsed) = contract.parse_log(log).unwrap();
+		assert_eq!(event, bar);
+		assert_eq!(parsed.params, vec![LogParam { name: "b".into(), value: Token::Bool(true) }]);
+
+		assert!(contract.parse_log(RawLog { topics: vec![Hash::zero()], data: vec![] }).is_err());
+		assert!(contract.parse_log(RawLog { topics: vec![], data: vec![] }).is_err());
+	}
+
+	#[test]
+	fn load_lenient() {
+		let json = r#"
+			[
+				{ "type": "fallback" },
+				{ "type": "function", "name": "foo", "inputs": [{ "name": "a", "type": "uint257x" }], "outputs": [] },
+				{ "type": "function", "name": "bar", "inputs": [], "outputs": [] },
+				{ "type": "unknown" }
+			]
+		"#;
+
+		let (contract, errors) = Contract::load_lenient(json.as_bytes()).unwrap();
+
+		assert_eq!(contract.fallback, Some(StateMutability::NonPayable));
+		assert!(contract.function("bar").is_ok());
+		assert!(contract.function("foo").is_err());
+		assert_eq!(errors.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![1, 3]);
+		assert!(Contract::load_lenient(r#"{ "type": "fallback" }"#.as_bytes()).is_err());
+	}
+
+	#[test]
+	fn load_text() {
+		let text = r#"
+			// ERC20 subset
+			function transfer(address to, uint256 amount) external returns (bool)
+			function balanceOf(address owner) view returns (uint256) # overloads are fine too
+			function balanceOf(address owner, uint256 id) view returns (uint256)
+
+			event Transfer(address indexed from, address indexed to, uint256 value)
+			constructor(string name)
+			receive() external payable
+		"#;
+
+		let contract = Contract::load_text(text.as_bytes()).unwrap();
+		assert_eq!(contract.function("transfer").unwrap().signature(), "transfer(address,uint256):(bool)");
+		assert_eq!(contract.functions_by_name("balanceOf").unwrap().len(), 2);
+		assert_eq!(contract.event("Transfer").unwrap().inputs.len(), 3);
+		assert_eq!(contract.constructor().unwrap().inputs[0].kind, ParamType::String);
+		assert!(contract.receive);
+		assert_eq!(contract.fallback, None);
+
+		let err = Contract::load_text("function foo()\nfunction bar(strin)".as_bytes()).unwrap_err();
+		assert!(err.to_string().contains("line 2"), "{}", err);
+	}
+
+	#[test]
+	fn to_text() {
+		let contract = Contract::load(include_str!("../../res/eip20.abi").as_bytes()).unwrap();
+		let text = contract.to_text();
+		assert!(
+			text.starts_with("function allowance(address _owner, address _spender) view returns (uint256 remaining)\n")
+		);
+		assert!(text.ends_with("\nevent Transfer(address indexed from, address indexed to, uint256 value)"));
+		assert_eq!(Contract::load_text(text.as_bytes()).unwrap().to_text(), text);
+
+		let text = "constructor(string name)\nfunction a()\nreceive() external payable\nfallback() payable";
+		assert_eq!(Contract::load_text(text.as_bytes()).unwrap().to_text(), text);
+	}
+
+	#[test]
+	fn serialize() {
+		let text = "
+			function b(uint256 x) view returns (bool)
+			function a(address to)
+			event E(address indexed from)
+			constructor()
+			receive() external payable
+			fallback()
+		";
+
+		let contract = Contract::load_text(text.as_bytes()).unwrap();
+		let expected = serde_json::json!([
+			{ "type": "constructor", "inputs": [] },
+			{
+				"type": "function",
+				"name": "a",
+				"inputs": [{ "name": "to", "type": "address" }],
+				"outputs": [],
+				"constant": false,
+				"stateMutability": "nonpayable",
+			},
+			{
+				"type": "function",
+				"name": "b",
+				"inputs": [{ "name": "x", "type": "uint256" }],
+				"outputs": [{ "name": "", "type": "bool" }],
+				"constant": false,
+				"stateMutability": "view",
+			},
+			{
+				"type": "event",
+				"name": "E",
+				"inputs": [{ "name": "from", "type": "address", "indexed": true }],
+				"anonymous": false,
+			},
+			{ "type": "receive", "stateMutability": "payable" },
+			{ "type": "fallback", "stateMutability": "nonpayable" },
+		]);
+
+		assert_eq!(serde_json::to_value(&contract).unwrap(), expected);
+		assert_eq!(Contract::load(serde_json::to_string(&contract).unwrap().as_bytes()).unwrap(), contract);
+	}
+
+	#[test]
+	fn builder() {
+		#[allow(deprecated)]
+		let function = |inputs| Function {
+			name: "foo".to_owned(),
+			inputs,
+			outputs: vec![],
+			constant: false,
+			state_mutability: StateMutability::NonPayable,
+		};
+		let event = Event {
+			name: "Bar".to_owned(),
+			inputs: vec![EventParam { name: "a".to_owned(), kind: ParamType::Address, indexed: true }],
+			anonymous: false,
+		};
+
+		let contract = Contract::builder()
+			.add_function(function(vec![]))
+			.add_function(function(vec![Param { name: "a".to_owned(), kind: ParamType::Bool }]))
+			.add_event(event.clone())
+			.set_constructor(Constructor { inputs: vec![] })
+			.fallback(Some(StateMutability::NonPayable))
+			.build();
+
+		let text = "
+			function foo()
+			function foo(bool a)
+			event Bar(address indexed a)
+			constructor()
+			fallback()
+		";
+		assert_eq!(contract, Contract::load_text(text.as_bytes()).unwrap());
+		assert_eq!(contract.functions_by_name("foo").unwrap().len(), 2);
+		assert_eq!(contract.event("Bar").unwrap(), &event);
+		assert!(!contract.receive);
+	}
+
+	#[test]
+	fn merge() {
+		let proxy = Contract::load_text(
+			"
+			function upgradeTo(address implementation)
+			function owner() view returns (address)
+			event Upgraded(address indexed implementation)
+			fallback()
+			"
+			.as_bytes(),
+		)
+		.unwrap();
+		let implementation = Contract::load_text(
+			"
+			function transfer(address to, uint256 amount) returns (bool)
+			function owner() view returns (address)
+			event Transfer(address indexed from, address indexed to, uint256 value)
+			constructor(string name)
+			receive() external payable
+			"
+			.as_bytes(),
+		)
+		.unwrap();
+
+		let merged = proxy.merge(&implementation).unwrap();
+		let mut names: Vec<_> = merged.functions().map(|function| function.name.as_str()).collect();
+		names.sort();
+		assert_eq!(names, ["owner", "transfer", "upgradeTo"]);
+		assert_eq!(merged.events().count(), 2);
+		assert_eq!(merged.constructor, implementation.constructor);
+		assert!(merged.receive && merged.fallback.is_some());
+
+		let conflicting = Contract::load_text("function owner() returns (address, bool)".as_bytes()).unwrap();
+		assert!(proxy.merge(&conflicting).is_err());
+		let merged = proxy.merge_with(&conflicting, MergePolicy::PreferLeft).unwrap();
+		assert_eq!(merged, proxy);
+	}
 }
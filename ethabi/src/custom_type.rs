@@ -0,0 +1,206 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Extension point for types not known to this crate, e.g. chain-specific or experimental types.
+
+use std::{collections::HashMap, fmt};
+
+use crate::{
+	decode_single, encode_single,
+	param_type::Reader,
+	token::{split_items, unquote, LenientTokenizer, Tokenizer},
+	Bytes, Error, ParamType, Result, Token,
+};
+
+/// Type not known to this crate, represented on the wire as a standard ABI type.
+///
+/// Only `name` and `param_type` are required. The other methods default to the behaviour
+/// of the standard type and may be overridden, e.g. to validate or format values.
+pub trait CustomType: Send + Sync {
+	/// Name of the type as it appears in signatures, e.g. `percent`.
+	fn name(&self) -> &str;
+
+	/// Standard type the values are encoded as.
+	fn param_type(&self) -> ParamType;
+
+	/// Parses a value from a string.
+	fn tokenize(&self, value: &str) -> Result<Token> {
+		LenientTokenizer::tokenize(&self.param_type(), value)
+	}
+
+	/// Encodes a value.
+	fn encode(&self, token: Token) -> Result<Bytes> {
+		encode_single(&self.param_type(), token)
+	}
+
+	/// Decodes a value.
+	fn decode(&self, data: &[u8]) -> Result<Token> {
+		decode_single(&self.param_type(), data)
+	}
+
+	/// Formats a value, the inverse of `tokenize`.
+	fn stringify(&self, token: &Token) -> String {
+		token.to_string()
+	}
+}
+
+/// Custom types by name.
+#[derive(Default)]
+pub struct TypeRegistry {
+	types: HashMap<String, Box<dyn CustomType>>,
+}
+
+impl fmt::Debug for TypeRegistry {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("TypeRegistry").field("types", &self.types.keys().collect::<Vec<_>>()).finish()
+	}
+}
+
+impl TypeRegistry {
+	/// Creates a registry without custom types.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Registers the type under its name, replacing any previous type of that name.
+	pub fn register<T: CustomType + 'static>(&mut self, custom_type: T) -> &mut Self {
+		self.types.insert(custom_type.name().to_owned(), Box::new(custom_type));
+		self
+	}
+
+	/// Returns the custom type of the name.
+	pub fn get(&self, name: &str) -> Option<&dyn CustomType> {
+		self.types.get(name).map(|custom_type| &**custom_type)
+	}
+
+	/// Returns the custom type of the name, failing with `Error::InvalidName` if there is none.
+	pub fn custom_type(&self, name: &str) -> Result<&dyn CustomType> {
+		self.get(name).ok_or_else(|| Error::InvalidName(name.to_owned()))
+	}
+
+	/// Reads the standard type a type name is encoded as, custom types included, e.g.
+	/// `(percent,address)[]`.
+	pub fn read(&self, name: &str) -> Result<ParamType> {
+		Reader::read_with(name, |name| self.get(name).map(|custom_type| custom_type.param_type()))
+	}
+
+	/// Parses a value of the type name, the values of custom types nested in arrays and tuples
+	/// with their own `tokenize`.
+	pub fn tokenize(&self, name: &str, value: &str) -> Result<Token> {
+		if let Some(custom_type) = self.get(name) {
+			return custom_type.tokenize(value);
+		}
+
+		let param = self.read(name)?;
+		match param {
+			ParamType::Array(ref element) | ParamType::FixedArray(ref element, _) => {
+				let element_name = &name[..name.rfind('[').ok_or(Error::InvalidData)?];
+				let tokens = split_items(value, '[', ']')?
+					.into_iter()
+					.map(|item| self.tokenize(element_name, &unquote(element, item)))
+					.collect::<Result<Vec<_>>>()?;
+				match param {
+					ParamType::FixedArray(_, len) if tokens.len() != len => Err(Error::InvalidData),
+					ParamType::FixedArray(..) => Ok(Token::FixedArray(tokens)),
+					_ => Ok(Token::Array(tokens)),
+				}
+			}
+			ParamType::Tuple(ref params) if name.ends_with(')') => {
+				let names = name
+					.strip_prefix('(')
+					.and_then(|name| name.strip_suffix(')'))
+					.and_then(Reader::split_components)
+					.ok_or_else(|| Error::InvalidName(name.to_owned()))?;
+				let items = split_items(value, '(', ')')?;
+				if items.len() != params.len() {
+					return Err(Error::InvalidData);
+				}
+
+				names
+					.into_iter()
+					.zip(params)
+					.zip(items)
+					.map(|((name, param), item)| self.tokenize(name, &unquote(param, item)))
+					.collect::<Result<_>>()
+					.map(Token::Tuple)
+			}
+			param => LenientTokenizer::tokenize(&param, value),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{CustomType, TypeRegistry};
+	use crate::{encode, Error, ParamType, Result, Token};
+
+	/// Percentage with two decimals, encoded as basis points, e.g. `12.5%` as `1250`.
+	struct Percent;
+
+	impl CustomType for Percent {
+		fn name(&self) -> &str {
+			"percent"
+		}
+
+		fn param_type(&self) -> ParamType {
+			ParamType::Uint(16)
+		}
+
+		fn tokenize(&self, value: &str) -> Result<Token> {
+			let value = value.strip_suffix('%').ok_or(Error::InvalidData)?;
+			let bps = value.parse::<f64>().map_err(|_| Error::InvalidData)? * 100.0;
+			match bps.fract() == 0.0 && (0.0..=10_000.0).contains(&bps) {
+				true => Ok(Token::Uint((bps as u64).into())),
+				false => Err(Error::InvalidData),
+			}
+		}
+
+		fn stringify(&self, token: &Token) -> String {
+			let bps = token.clone().into_uint().unwrap_or_default().low_u64();
+			format!("{}%", bps as f64 / 100.0)
+		}
+	}
+
+	#[test]
+	fn test_custom_type() {
+		let mut registry = TypeRegistry::new();
+		registry.register(Percent);
+
+		let percent = registry.custom_type("percent").unwrap();
+		let token = percent.tokenize("12.5%").unwrap();
+		assert_eq!(token, Token::Uint(1250.into()));
+		assert!(percent.tokenize("12.345%").is_err());
+
+		let encoded = percent.encode(token.clone()).unwrap();
+		assert_eq!(encoded, encode(&[Token::Uint(1250.into())]));
+		assert_eq!(percent.decode(&encoded).unwrap(), token);
+		assert_eq!(percent.stringify(&token), "12.5%");
+		assert!(percent.encode(Token::Bool(true)).is_err());
+
+		assert_eq!(registry.read("percent").unwrap(), ParamType::Uint(16));
+		assert_eq!(registry.read("address[]").unwrap(), ParamType::Array(Box::new(ParamType::Address)));
+		assert_eq!(
+			registry.read("(percent,address)[2]").unwrap(),
+			ParamType::FixedArray(Box::new(ParamType::Tuple(vec![ParamType::Uint(16), ParamType::Address])), 2)
+		);
+		assert!(registry.read("fraction").is_err());
+		assert!(registry.read("fraction[]").is_err());
+
+		assert_eq!(
+			registry.tokenize("percent[]", "[12.5%, 1%]").unwrap(),
+			Token::Array(vec![Token::Uint(1250.into()), Token::Uint(100.into())])
+		);
+		assert_eq!(
+			registry.tokenize("(percent,string,bool)", r#"(0.5%,"a,b",true)"#).unwrap(),
+			Token::Tuple(vec![Token::Uint(50.into()), Token::String("a,b".into()), Token::Bool(true)])
+		);
+		assert!(registry.tokenize("percent[1]", "[1%,2%]").is_err());
+		assert!(registry.tokenize("percent[]", "[12.345%]").is_err());
+		assert!(registry.custom_type("fraction").is_err());
+	}
+}
@@ -8,7 +8,9 @@
 
 //! ABI decoder.
 
-use crate::{Error, ParamType, Token, Word};
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{Error, ParamType, Token, Word, ADDRESS_LEN, DEFAULT_MAX_DEPTH};
 
 #[derive(Debug)]
 struct DecodeResult {
@@ -16,7 +18,7 @@ struct DecodeResult {
 	new_offset: usize,
 }
 
-fn as_usize(slice: &Word) -> Result<usize, Error> {
+pub(crate) fn as_usize(slice: &Word) -> Result<usize, Error> {
 	if !slice[..28].iter().all(|x| *x == 0) {
 		return Err(Error::InvalidData);
 	}
@@ -39,6 +41,19 @@ fn as_bool(slice: &Word) -> Result<bool, Error> {
 
 /// Decodes ABI compliant vector of bytes into vector of tokens described by types param.
 pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, Error> {
+	decode_with_max_depth(types, data, DEFAULT_MAX_DEPTH)
+}
+
+/// Decodes a single token of the given type, e.g. a lone return value.
+pub fn decode_single(kind: &ParamType, data: &[u8]) -> Result<Token, Error> {
+	decode(core::slice::from_ref(kind), data)?.pop().ok_or(Error::InvalidData)
+}
+
+/// Decodes ABI compliant vector of bytes into vector of tokens described by types param.
+///
+/// Fails with `Error::NestingTooDeep` when arrays and tuples are nested more than
+/// `max_depth` levels deep, instead of risking a stack overflow.
+pub fn decode_with_max_depth(types: &[ParamType], data: &[u8], max_depth: usize) -> Result<Vec<Token>, Error> {
 	let is_empty_bytes_valid_encoding = types.iter().all(|t| t.is_empty_bytes_valid_encoding());
 	if !is_empty_bytes_valid_encoding && data.is_empty() {
 		return Err(Error::InvalidName(
@@ -53,8 +68,11 @@ pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, Error> {
 	let mut tokens = vec![];
 	let mut offset = 0;
 
-	for param in types {
-		let res = decode_param(param, data, offset)?;
+	for (index, param) in types.iter().enumerate() {
+		let res = decode_param(param, data, 0, offset, max_depth).map_err(|err| match err {
+			Error::InvalidDataAt { kind, offset, .. } => Error::InvalidDataAt { index, kind, offset },
+			err => err,
+		})?;
 		offset = res.new_offset;
 		tokens.push(res.token);
 	}
@@ -72,7 +90,7 @@ fn peek(data: &[u8], offset: usize, len: usize) -> Result<&[u8], Error> {
 
 fn peek_32_bytes(data: &[u8], offset: usize) -> Result<Word, Error> {
 	peek(data, offset, 32).map(|x| {
-		let mut out: Word = [0u8; 32];
+		let mut out = Word::default();
 		out.copy_from_slice(&x[0..32]);
 		out
 	})
@@ -86,21 +104,49 @@ fn take_bytes(data: &[u8], offset: usize, len: usize) -> Result<Vec<u8>, Error>
 	}
 }
 
-fn decode_param(param: &ParamType, data: &[u8], offset: usize) -> Result<DecodeResult, Error> {
+/// Returns the depth left for elements of an array or a tuple.
+fn nested(depth: usize) -> Result<usize, Error> {
+	depth.checked_sub(1).ok_or(Error::NestingTooDeep)
+}
+
+/// Decodes the param at `offset` in `data`, which starts at `base` in the data being decoded.
+///
+/// Fails with `Error::InvalidDataAt` of the innermost param which could not be decoded,
+/// with the index left for the caller to fill in.
+fn decode_param(
+	param: &ParamType,
+	data: &[u8],
+	base: usize,
+	offset: usize,
+	depth: usize,
+) -> Result<DecodeResult, Error> {
+	decode_param_inner(param, data, base, offset, depth).map_err(|err| match err {
+		Error::InvalidData => Error::InvalidDataAt { index: 0, kind: param.clone(), offset: base + offset },
+		err => err,
+	})
+}
+
+fn decode_param_inner(
+	param: &ParamType,
+	data: &[u8],
+	base: usize,
+	offset: usize,
+	depth: usize,
+) -> Result<DecodeResult, Error> {
 	match *param {
 		ParamType::Address => {
 			let slice = peek_32_bytes(data, offset)?;
-			let mut address = [0u8; 20];
-			address.copy_from_slice(&slice[12..]);
+			let mut address = [0u8; ADDRESS_LEN];
+			address.copy_from_slice(&slice[32 - ADDRESS_LEN..]);
 			let result = DecodeResult { token: Token::Address(address.into()), new_offset: offset + 32 };
 			Ok(result)
 		}
-		ParamType::Int(_) => {
+		ParamType::Int(_) | ParamType::Fixed(_, _) => {
 			let slice = peek_32_bytes(data, offset)?;
 			let result = DecodeResult { token: Token::Int(slice.into()), new_offset: offset + 32 };
 			Ok(result)
 		}
-		ParamType::Uint(_) => {
+		ParamType::Uint(_) | ParamType::Ufixed(_, _) => {
 			let slice = peek_32_bytes(data, offset)?;
 			let result = DecodeResult { token: Token::Uint(slice.into()), new_offset: offset + 32 };
 			Ok(result)
@@ -110,6 +156,15 @@ fn decode_param(param: &ParamType, data: &[u8], offset: usize) -> Result<DecodeR
 			let result = DecodeResult { token: Token::Bool(b), new_offset: offset + 32 };
 			Ok(result)
 		}
+		ParamType::Function => {
+			// an address and a selector, padded with trailing zeros like bytes24
+			let slice = peek_32_bytes(data, offset)?;
+			if slice[24..].iter().any(|byte| *byte != 0) {
+				return Err(Error::InvalidData);
+			}
+			let result = DecodeResult { token: Token::FixedBytes(slice[..24].to_vec()), new_offset: offset + 32 };
+			Ok(result)
+		}
 		ParamType::FixedBytes(len) => {
 			// FixedBytes is anything from bytes1 to bytes32. These values
 			// are padded with trailing zeros to fill 32 bytes.
@@ -149,7 +204,7 @@ fn decode_param(param: &ParamType, data: &[u8], offset: usize) -> Result<DecodeR
 			let mut new_offset = 0;
 
 			for _ in 0..len {
-				let res = decode_param(t, &tail, new_offset)?;
+				let res = decode_param(t, &tail, base + tail_offset, new_offset, nested(depth)?)?;
 				new_offset = res.new_offset;
 				tokens.push(res.token);
 			}
@@ -161,13 +216,20 @@ fn decode_param(param: &ParamType, data: &[u8], offset: usize) -> Result<DecodeR
 		ParamType::FixedArray(ref t, len) => {
 			let is_dynamic = param.is_dynamic();
 
-			let (tail, mut new_offset) =
-				if is_dynamic { (&data[as_usize(&peek_32_bytes(data, offset)?)?..], 0) } else { (data, offset) };
+			let (tail, tail_base, mut new_offset) = if is_dynamic {
+				let offset = as_usize(&peek_32_bytes(data, offset)?)?;
+				if offset > data.len() {
+					return Err(Error::InvalidData);
+				}
+				(&data[offset..], base + offset, 0)
+			} else {
+				(data, base, offset)
+			};
 
 			let mut tokens = vec![];
 
 			for _ in 0..len {
-				let res = decode_param(t, &tail, new_offset)?;
+				let res = decode_param(t, &tail, tail_base, new_offset, nested(depth)?)?;
 				new_offset = res.new_offset;
 				tokens.push(res.token);
 			}
@@ -184,20 +246,20 @@ fn decode_param(param: &ParamType, data: &[u8], offset: usize) -> Result<DecodeR
 
 			// The first element in a dynamic Tuple is an offset to the Tuple's data
 			// For a static Tuple the data begins right away
-			let (tail, mut new_offset) = if is_dynamic {
+			let (tail, tail_base, mut new_offset) = if is_dynamic {
 				let offset = as_usize(&peek_32_bytes(data, offset)?)?;
 				if offset > data.len() {
 					return Err(Error::InvalidData);
 				}
-				(&data[offset..], 0)
+				(&data[offset..], base + offset, 0)
 			} else {
-				(data, offset)
+				(data, base, offset)
 			};
 
 			let len = t.len();
 			let mut tokens = Vec::with_capacity(len);
 			for param in t {
-				let res = decode_param(param, &tail, new_offset)?;
+				let res = decode_param(param, &tail, tail_base, new_offset, nested(depth)?)?;
 				new_offset = res.new_offset;
 				tokens.push(res.token);
 			}
@@ -217,9 +279,34 @@ fn decode_param(param: &ParamType, data: &[u8], offset: usize) -> Result<DecodeR
 
 #[cfg(test)]
 mod tests {
-	use crate::{decode, ParamType, Token, Uint};
+	use crate::{decode, decode_single, encode, Error, ParamType, Token, Uint};
 	use hex_literal::hex;
 
+	#[test]
+	fn decode_error_context() {
+		let types = [ParamType::Uint(256), ParamType::Array(Box::new(ParamType::Bytes))];
+		let encoded = encode(&[
+			Token::Uint(1.into()),
+			Token::Array(vec![Token::Bytes(vec![0x12; 4]), Token::Bytes(vec![0x34; 4])]),
+		]);
+
+		// the contents of the second `bytes` are missing
+		let err = decode(&types, &encoded[..256]).unwrap_err();
+		assert!(matches!(err, Error::InvalidDataAt { index: 1, kind: ParamType::Bytes, offset: 128 }), "{:?}", err);
+		#[cfg(feature = "std")]
+		assert_eq!(err.to_string(), "Invalid data of param 1: expected bytes at offset 128");
+
+		let err = decode(&[ParamType::Bool, ParamType::Bool], &encoded[..40]).unwrap_err();
+		assert!(matches!(err, Error::InvalidDataAt { index: 1, kind: ParamType::Bool, offset: 32 }), "{:?}", err);
+	}
+
+	#[test]
+	fn test_decode_single() {
+		let encoded = hex!("0000000000000000000000000000000000000000000000000000000000000007");
+		assert_eq!(decode_single(&ParamType::Uint(256), &encoded).unwrap(), Token::Uint(7.into()));
+		assert!(decode_single(&ParamType::Uint(256), &encoded[..31]).is_err());
+	}
+
 	#[test]
 	fn decode_from_empty_byte_slice() {
 		// these can NOT be decoded from empty byte slice
@@ -540,6 +627,7 @@ mod tests {
 	}
 
 	#[test]
+	#[cfg(feature = "std")]
 	fn decode_corrupted_nested_array_tuple() {
 		let input = hex!(
 			"
@@ -622,4 +710,34 @@ ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff
 		};
 		assert!(func.decode_input(&input).is_err());
 	}
+
+	#[test]
+	fn decode_corrupted_fixed_array_of_strings() {
+		// tail offset points past the end of the data
+		let encoded = hex!("00000000000000000000000000000000000000000000000000000000000000ff");
+
+		assert!(decode(&[ParamType::FixedArray(Box::new(ParamType::String), 2)], &encoded).is_err());
+	}
+
+	#[test]
+	fn decode_nesting_too_deep() {
+		use crate::{decode_with_max_depth, Error};
+
+		let nested = |depth| (0..depth).fold(ParamType::Uint(256), |t, _| ParamType::FixedArray(Box::new(t), 1));
+		let encoded = hex!("0000000000000000000000000000000000000000000000000000000000000001");
+
+		assert!(decode(&[nested(64)], &encoded).is_ok());
+		assert!(matches!(decode(&[nested(65)], &encoded), Err(Error::NestingTooDeep)));
+		assert!(decode_with_max_depth(&[nested(100)], &encoded, 100).is_ok());
+		assert!(matches!(decode_with_max_depth(&[nested(2)], &encoded, 1), Err(Error::NestingTooDeep)));
+	}
+
+	#[test]
+	fn decode_function_padding() {
+		let encoded = hex!("1111111111111111111111111111111111111111222222220000000000000000");
+		assert_eq!(decode(&[ParamType::Function], &encoded).unwrap(), vec![Token::FixedBytes(encoded[..24].to_vec())]);
+
+		let encoded = hex!("1111111111111111111111111111111111111111222222220000000000000001");
+		assert!(decode(&[ParamType::Function], &encoded).is_err());
+	}
 }
@@ -0,0 +1,44 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Calldata tied to the decoder of its function output.
+
+use crate::{Bytes, FunctionOutputDecoder, Result};
+
+/// Encoded function call, which can only decode the output of the function it calls.
+///
+/// Code generated by `ethabi-derive` has a distinct decoder type per function, so
+/// passing the call of one function where another is expected is a compile error,
+/// e.g. `fn balance(call: EncodedCall<balance_of::Decoder>)`.
+#[derive(Debug, Clone)]
+pub struct EncodedCall<D> {
+	data: Bytes,
+	decoder: D,
+}
+
+impl<D: FunctionOutputDecoder> EncodedCall<D> {
+	/// Pairs calldata with the decoder of the output of the function it was encoded for.
+	pub fn new(data: Bytes, decoder: D) -> Self {
+		EncodedCall { data, decoder }
+	}
+
+	/// Returns the calldata.
+	pub fn data(&self) -> &[u8] {
+		&self.data
+	}
+
+	/// Decodes the output of the call.
+	pub fn decode_output(&self, output: &[u8]) -> Result<D::Output> {
+		self.decoder.decode(output)
+	}
+
+	/// Splits the call into its calldata and output decoder.
+	pub fn into_parts(self) -> (Bytes, D) {
+		(self.data, self.decoder)
+	}
+}
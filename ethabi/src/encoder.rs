@@ -8,176 +8,223 @@
 
 //! ABI encoder.
 
-use crate::{util::pad_u32, Bytes, Token, Word};
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{util::pad_u32, Bytes, Error, ParamType, Result, Token, Word, DEFAULT_MAX_DEPTH};
 
-fn pad_bytes(bytes: &[u8]) -> Vec<Word> {
-	let mut result = vec![pad_u32(bytes.len() as u32)];
-	result.extend(pad_fixed_bytes(bytes));
-	result
+/// Destination of an encoding, a buffer or, with `std`, a writer.
+trait Out {
+	fn put(&mut self, bytes: &[u8]);
 }
 
-fn pad_fixed_bytes(bytes: &[u8]) -> Vec<Word> {
-	let len = (bytes.len() + 31) / 32;
-	let mut result = Vec::with_capacity(len);
-	for i in 0..len {
-		let mut padded = [0u8; 32];
-
-		let to_copy = match i == len - 1 {
-			false => 32,
-			true => match bytes.len() % 32 {
-				0 => 32,
-				x => x,
-			},
-		};
-
-		let offset = 32 * i;
-		padded[..to_copy].copy_from_slice(&bytes[offset..offset + to_copy]);
-		result.push(padded);
+impl Out for Vec<u8> {
+	fn put(&mut self, bytes: &[u8]) {
+		self.extend_from_slice(bytes);
 	}
-
-	result
 }
 
-#[derive(Debug)]
-enum Mediate {
-	Raw(Vec<Word>),
-	Prefixed(Vec<Word>),
-	PrefixedArray(Vec<Mediate>),
-	PrefixedArrayWithLength(Vec<Mediate>),
-	RawTuple(Vec<Mediate>),
-	PrefixedTuple(Vec<Mediate>),
+/// Writes an encoding into an `io::Write`, keeping the first error, after which nothing more is written.
+#[cfg(feature = "std")]
+struct WriterOut<'a, W> {
+	writer: &'a mut W,
+	result: std::io::Result<()>,
+	#[cfg(feature = "audit")]
+	written: Vec<u8>,
 }
 
-impl Mediate {
-	fn head_len(&self) -> u32 {
-		match *self {
-			Mediate::Raw(ref raw) => 32 * raw.len() as u32,
-			Mediate::RawTuple(ref mediates) => mediates.iter().map(|mediate| mediate.head_len()).sum(),
-			Mediate::Prefixed(_)
-			| Mediate::PrefixedArray(_)
-			| Mediate::PrefixedArrayWithLength(_)
-			| Mediate::PrefixedTuple(_) => 32,
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Out for WriterOut<'_, W> {
+	fn put(&mut self, bytes: &[u8]) {
+		if self.result.is_ok() {
+			self.result = self.writer.write_all(bytes);
 		}
+		#[cfg(feature = "audit")]
+		self.written.extend_from_slice(bytes);
 	}
+}
 
-	fn tail_len(&self) -> u32 {
-		match *self {
-			Mediate::Raw(_) | Mediate::RawTuple(_) => 0,
-			Mediate::Prefixed(ref pre) => pre.len() as u32 * 32,
-			Mediate::PrefixedArray(ref mediates) => mediates.iter().fold(0, |acc, m| acc + m.head_len() + m.tail_len()),
-			Mediate::PrefixedArrayWithLength(ref mediates) => {
-				mediates.iter().fold(32, |acc, m| acc + m.head_len() + m.tail_len())
+fn write_word(out: &mut impl Out, word: Word) {
+	out.put(word.as_ref());
+}
+
+/// Writes the bytes right padded with zeros to a multiple of 32 bytes.
+fn write_padded(out: &mut impl Out, bytes: &[u8]) {
+	out.put(bytes);
+	out.put(&[0; 32][..(32 - bytes.len() % 32) % 32]);
+}
+
+/// Writes the heads of the tokens, followed by the tails of the dynamic ones.
+///
+/// The offsets of the tails depend only on their lengths, which are computed without encoding the tokens, so
+/// heads and tails are written in a single pass, e.g. for `string[]` and `bytes[]`.
+fn write_sequence(out: &mut impl Out, tokens: &[Token]) {
+	let mut offset: usize = tokens.iter().map(head_len).sum();
+	for token in tokens {
+		match token.is_dynamic() {
+			true => {
+				write_word(out, pad_u32(offset as u32));
+				offset += tail_len(token);
 			}
-			Mediate::PrefixedTuple(ref mediates) => mediates.iter().fold(0, |acc, m| acc + m.head_len() + m.tail_len()),
+			false => write_head(out, token),
 		}
 	}
 
-	fn head(&self, suffix_offset: u32) -> Vec<Word> {
-		match *self {
-			Mediate::Raw(ref raw) => raw.clone(),
-			Mediate::RawTuple(ref raw) => raw.iter().map(|mediate| mediate.head(0)).flatten().collect(),
-			Mediate::Prefixed(_)
-			| Mediate::PrefixedArray(_)
-			| Mediate::PrefixedArrayWithLength(_)
-			| Mediate::PrefixedTuple(_) => vec![pad_u32(suffix_offset)],
-		}
+	for token in tokens.iter().filter(|token| token.is_dynamic()) {
+		write_tail(out, token);
 	}
+}
 
-	fn tail(&self) -> Vec<Word> {
-		match *self {
-			Mediate::Raw(_) | Mediate::RawTuple(_) => vec![],
-			Mediate::PrefixedTuple(ref mediates) => encode_head_tail(mediates),
-			Mediate::Prefixed(ref raw) => raw.clone(),
-			Mediate::PrefixedArray(ref mediates) => encode_head_tail(mediates),
-			Mediate::PrefixedArrayWithLength(ref mediates) => {
-				// + 32 added to offset represents len of the array prepanded to tail
-				let mut result = vec![pad_u32(mediates.len() as u32)];
-
-				let head_tail = encode_head_tail(mediates);
+/// Writes a static token in place.
+fn write_head(out: &mut impl Out, token: &Token) {
+	match *token {
+		Token::Address(address) => write_word(out, address.into()),
+		Token::FixedBytes(ref bytes) => write_padded(out, bytes),
+		Token::Int(int) => write_word(out, int.into()),
+		Token::Uint(uint) => write_word(out, uint.into()),
+		Token::Bool(b) => write_word(out, b.into()),
+		Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) => write_sequence(out, tokens),
+		Token::Bytes(_) | Token::String(_) | Token::Array(_) => unreachable!("dynamic tokens are written in the tail"),
+	}
+}
 
-				result.extend(head_tail);
-				result
-			}
+/// Writes a dynamic token, which its head points to.
+fn write_tail(out: &mut impl Out, token: &Token) {
+	match *token {
+		Token::Bytes(ref bytes) => {
+			write_word(out, pad_u32(bytes.len() as u32));
+			write_padded(out, bytes);
+		}
+		Token::String(ref s) => {
+			write_word(out, pad_u32(s.len() as u32));
+			write_padded(out, s.as_bytes());
 		}
+		Token::Array(ref tokens) => {
+			write_word(out, pad_u32(tokens.len() as u32));
+			write_sequence(out, tokens);
+		}
+		Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) => write_sequence(out, tokens),
+		_ => unreachable!("static tokens are written in the head"),
 	}
 }
 
-fn encode_head_tail(mediates: &[Mediate]) -> Vec<Word> {
-	let heads_len = mediates.iter().fold(0, |acc, m| acc + m.head_len());
+/// Returns the number of bytes `encode` produces for the given tokens, without encoding them.
+pub fn encoded_len(tokens: &[Token]) -> usize {
+	tokens.iter().map(|token| head_len(token) + tail_len(token)).sum()
+}
 
-	let (mut result, len) =
-		mediates.iter().fold((Vec::with_capacity(heads_len as usize), heads_len), |(mut acc, offset), m| {
-			acc.extend(m.head(offset));
-			(acc, offset + m.tail_len())
-		});
+fn head_len(token: &Token) -> usize {
+	match *token {
+		_ if token.is_dynamic() => 32,
+		Token::FixedBytes(ref bytes) => 32 * bytes.len().div_ceil(32),
+		Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) => encoded_len(tokens),
+		_ => 32,
+	}
+}
 
-	let tails = mediates.iter().fold(Vec::with_capacity((len - heads_len) as usize), |mut acc, m| {
-		acc.extend(m.tail());
-		acc
-	});
+fn tail_len(token: &Token) -> usize {
+	match *token {
+		_ if !token.is_dynamic() => 0,
+		Token::Bytes(ref bytes) => 32 + 32 * bytes.len().div_ceil(32),
+		Token::String(ref s) => 32 + 32 * s.len().div_ceil(32),
+		Token::Array(ref tokens) => 32 + encoded_len(tokens),
+		Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) => encoded_len(tokens),
+		_ => 0,
+	}
+}
 
-	result.extend(tails);
+/// Encodes vector of tokens into ABI compliant vector of bytes.
+pub fn encode(tokens: &[Token]) -> Bytes {
+	let mut result = Vec::new();
+	write_tokens(&mut result, tokens);
 	result
 }
 
 /// Encodes vector of tokens into ABI compliant vector of bytes.
-pub fn encode(tokens: &[Token]) -> Bytes {
-	let mediates = &tokens.iter().map(encode_token).collect::<Vec<_>>();
+///
+/// Fails with `Error::NestingTooDeep` when arrays and tuples are nested more than
+/// `max_depth` levels deep, instead of risking a stack overflow.
+pub fn encode_with_max_depth(tokens: &[Token], max_depth: usize) -> Result<Bytes> {
+	check_depth(tokens, max_depth)?;
+	Ok(encode(tokens))
+}
 
-	encode_head_tail(mediates).iter().flat_map(|word| word.to_vec()).collect()
+/// Encodes a single token, e.g. a lone argument, checking that it is of the given type.
+pub fn encode_single(kind: &ParamType, token: Token) -> Result<Bytes> {
+	let tokens = [token];
+	Token::check_types(&tokens, core::slice::from_ref(kind))?;
+	Ok(encode(&tokens))
 }
 
-fn encode_token(token: &Token) -> Mediate {
-	match *token {
-		Token::Address(ref address) => {
-			let mut padded = [0u8; 32];
-			padded[12..].copy_from_slice(address.as_ref());
-			Mediate::Raw(vec![padded])
-		}
-		Token::Bytes(ref bytes) => Mediate::Prefixed(pad_bytes(bytes)),
-		Token::String(ref s) => Mediate::Prefixed(pad_bytes(s.as_bytes())),
-		Token::FixedBytes(ref bytes) => Mediate::Raw(pad_fixed_bytes(bytes)),
-		Token::Int(int) => Mediate::Raw(vec![int.into()]),
-		Token::Uint(uint) => Mediate::Raw(vec![uint.into()]),
-		Token::Bool(b) => {
-			let mut value = [0u8; 32];
-			if b {
-				value[31] = 1;
-			}
-			Mediate::Raw(vec![value])
-		}
-		Token::Array(ref tokens) => {
-			let mediates = tokens.iter().map(encode_token).collect();
+/// Encodes vector of tokens and appends the result to `buffer`.
+///
+/// Existing contents of `buffer` are left untouched, so the same allocation
+/// can be reused for many calls by clearing it in between. Fails like `encode_with_max_depth`
+/// with the `DEFAULT_MAX_DEPTH`.
+pub fn encode_into(buffer: &mut Vec<u8>, tokens: &[Token]) -> Result<()> {
+	check_depth(tokens, DEFAULT_MAX_DEPTH)?;
+	write_tokens(buffer, tokens);
+	Ok(())
+}
 
-			Mediate::PrefixedArrayWithLength(mediates)
-		}
-		Token::FixedArray(ref tokens) => {
-			let mediates = tokens.iter().map(encode_token).collect();
+/// Encodes vector of tokens and writes the result to `writer`.
+///
+/// The heads and tails are written straight into `writer` word by word, without buffering the encoding, so an
+/// unbuffered writer is best wrapped in a `BufWriter`. Fails with `io::ErrorKind::InvalidInput` if arrays and
+/// tuples are nested too deep, see `encode_into`.
+#[cfg(feature = "std")]
+pub fn encode_to_writer<W: std::io::Write>(writer: &mut W, tokens: &[Token]) -> std::io::Result<()> {
+	check_depth(tokens, DEFAULT_MAX_DEPTH).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+	let mut out = WriterOut {
+		writer,
+		result: Ok(()),
+		#[cfg(feature = "audit")]
+		written: Vec::new(),
+	};
+	write_sequence(&mut out, tokens);
+
+	#[cfg(feature = "audit")]
+	crate::audit::check(tokens, &out.written);
+	out.result
+}
 
-			if token.is_dynamic() {
-				Mediate::PrefixedArray(mediates)
-			} else {
-				Mediate::Raw(encode_head_tail(&mediates))
-			}
-		}
-		Token::Tuple(ref tokens) if token.is_dynamic() => {
-			let mediates = tokens.iter().map(encode_token).collect();
+/// Appends the encoding of the tokens to `out` as `encode`, however deeply they are nested.
+pub(crate) fn write_tokens(out: &mut Vec<u8>, tokens: &[Token]) {
+	out.reserve(encoded_len(tokens));
+	#[cfg(feature = "audit")]
+	let start = out.len();
+	write_sequence(out, tokens);
 
-			Mediate::PrefixedTuple(mediates)
-		}
-		Token::Tuple(ref tokens) => {
-			let mediates = tokens.iter().map(encode_token).collect();
+	#[cfg(feature = "audit")]
+	crate::audit::check(tokens, &out[start..]);
+}
 
-			Mediate::RawTuple(mediates)
+/// Fails if arrays and tuples are nested more than `max_depth` levels deep, without recursing, as encoding
+/// the tokens would.
+fn check_depth(tokens: &[Token], max_depth: usize) -> Result<()> {
+	let mut stack: Vec<_> = tokens.iter().map(|token| (token, 0)).collect();
+	while let Some((token, depth)) = stack.pop() {
+		if let Token::Array(ref tokens) | Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) = *token {
+			if depth >= max_depth {
+				return Err(Error::NestingTooDeep);
+			}
+			stack.extend(tokens.iter().map(|token| (token, depth + 1)));
 		}
 	}
+	Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-	use crate::{encode, util::pad_u32, Token};
+	use crate::{encode, encode_single, util::pad_u32, ParamType, Token};
 	use hex_literal::hex;
 
+	#[test]
+	fn test_encode_single() {
+		let token = Token::String("gavofyork".to_owned());
+		assert_eq!(encode_single(&ParamType::String, token.clone()).unwrap(), encode(std::slice::from_ref(&token)));
+		assert!(encode_single(&ParamType::Bytes, token).is_err());
+	}
+
 	#[test]
 	fn encode_address() {
 		let address = Token::Address([0x11u8; 20].into());
@@ -837,4 +884,126 @@ mod tests {
 		.to_vec();
 		assert_eq!(encoded, expected);
 	}
+
+	#[test]
+	fn encode_into_appends_to_buffer() {
+		use super::encode_into;
+
+		let tokens = [Token::Uint(0x1337.into()), Token::String("foo".into())];
+		let mut buffer = vec![0xaa, 0xbb];
+		encode_into(&mut buffer, &tokens).unwrap();
+
+		assert_eq!(buffer[..2], [0xaa, 0xbb]);
+		assert_eq!(buffer[2..], encode(&tokens)[..]);
+
+		buffer.clear();
+		encode_into(&mut buffer, &tokens).unwrap();
+		assert_eq!(buffer, encode(&tokens));
+	}
+
+	#[test]
+	#[cfg(feature = "std")]
+	fn encode_to_writer_matches_encode() {
+		use super::encode_to_writer;
+
+		let tokens = [Token::Array(vec![Token::Bytes(vec![1, 2, 3])]), Token::Bool(true)];
+		let mut writer = std::io::Cursor::new(Vec::new());
+		encode_to_writer(&mut writer, &tokens).unwrap();
+
+		assert_eq!(writer.into_inner(), encode(&tokens));
+	}
+
+	#[test]
+	#[cfg(feature = "std")]
+	fn encode_to_writer_stops_at_first_error() {
+		use super::encode_to_writer;
+
+		let tokens = [Token::String("x".repeat(100))];
+		let mut buffer = [0u8; 40];
+		let err = encode_to_writer(&mut &mut buffer[..], &tokens).unwrap_err();
+
+		assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+		assert_eq!(buffer[..], encode(&tokens)[..40]);
+	}
+
+	#[test]
+	fn encode_bytes_arrays_fast_path_matches_generic_layout() {
+		use crate::util::pad_u32;
+
+		// the heads of the items point to their tails, each a length followed by the padded bytes
+		fn generic(items: &[Vec<u8>], with_length: bool) -> Vec<u8> {
+			let tails: Vec<Vec<u8>> = items
+				.iter()
+				.map(|item| {
+					let mut tail = pad_u32(item.len() as u32).to_vec();
+					tail.extend_from_slice(item);
+					tail.resize(32 + 32 * item.len().div_ceil(32), 0);
+					tail
+				})
+				.collect();
+
+			// the array is itself dynamic, so its encoding starts with the offset of its tail
+			let mut out = pad_u32(32).to_vec();
+			if with_length {
+				out.extend_from_slice(pad_u32(items.len() as u32).as_ref());
+			}
+			let mut offset = 32 * items.len();
+			for tail in &tails {
+				out.extend_from_slice(pad_u32(offset as u32).as_ref());
+				offset += tail.len();
+			}
+			tails.iter().for_each(|tail| out.extend_from_slice(tail));
+			out
+		}
+
+		for count in 1..5 {
+			for len in 0..70 {
+				let bytes: Vec<_> = (0..count).map(|i| vec![i as u8 + 1; len + i]).collect();
+				let strings: Vec<_> = (0..count).map(|i| "x".repeat(len * i)).collect();
+				let items: Vec<_> = strings.iter().map(|s| s.as_bytes().to_vec()).collect();
+
+				let bytes_tokens: Vec<_> = bytes.iter().cloned().map(Token::Bytes).collect();
+				assert_eq!(encode(&[Token::Array(bytes_tokens.clone())]), generic(&bytes, true));
+				assert_eq!(encode(&[Token::FixedArray(bytes_tokens)]), generic(&bytes, false));
+
+				let string_tokens: Vec<_> = strings.into_iter().map(Token::String).collect();
+				assert_eq!(encode(&[Token::Array(string_tokens.clone())]), generic(&items, true));
+				assert_eq!(encode(&[Token::FixedArray(string_tokens)]), generic(&items, false));
+			}
+		}
+	}
+
+	#[test]
+	fn encoded_len_matches_encode() {
+		use super::encoded_len;
+
+		let strings = Token::Array(vec![Token::String("".into()), Token::String("x".repeat(33))]);
+		let static_tuple = Token::Tuple(vec![Token::Uint(1.into()), Token::FixedBytes(vec![0xff; 40])]);
+		let dynamic_tuple = Token::Tuple(vec![Token::Bool(true), Token::Bytes(vec![1; 64])]);
+		let cases = vec![
+			vec![],
+			vec![Token::Address([0x11u8; 20].into()), Token::Int(5.into())],
+			vec![Token::FixedBytes(vec![])],
+			vec![strings.clone(), Token::FixedArray(vec![strings.clone(), strings])],
+			vec![static_tuple.clone(), Token::Array(vec![static_tuple.clone(), static_tuple])],
+			vec![Token::FixedArray(vec![dynamic_tuple.clone(), dynamic_tuple.clone()]), dynamic_tuple],
+			vec![Token::Array(vec![Token::Array(vec![]), Token::Array(vec![Token::Uint(2.into())])])],
+		];
+
+		for tokens in cases {
+			assert_eq!(encoded_len(&tokens), encode(&tokens).len(), "{:?}", tokens);
+		}
+	}
+
+	#[test]
+	fn encode_nesting_limit() {
+		use super::{encode_into, encode_with_max_depth};
+		use crate::Error;
+
+		let nested = |depth| (0..depth).fold(Token::Bool(true), |token, _| Token::FixedArray(vec![token]));
+		assert_eq!(encode(&[nested(100)]), encode(&[Token::Bool(true)]));
+		assert_eq!(encode_with_max_depth(&[nested(100)], 100).unwrap(), encode(&[Token::Bool(true)]));
+		assert!(matches!(encode_with_max_depth(&[nested(2)], 1), Err(Error::NestingTooDeep)));
+		assert!(matches!(encode_into(&mut Vec::new(), &[nested(1_000)]), Err(Error::NestingTooDeep)));
+	}
 }
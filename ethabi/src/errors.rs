@@ -6,37 +6,129 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{ParamType, Token};
+use alloc::string;
 use anyhow::anyhow;
-use std::{num, string};
-use thiserror::Error;
+use core::{fmt, num};
 
 /// Ethabi result type
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 /// Ethabi errors
-#[derive(Debug, Error)]
+///
+/// Without the `std` feature, errors implement `Debug` only.
+#[derive(Debug)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
 pub enum Error {
 	/// Invalid entity such as a bad function name.
-	#[error("Invalid name: {0}")]
+	#[cfg_attr(feature = "std", error("Invalid name: {0}"))]
 	InvalidName(String),
 	/// Invalid data.
-	#[error("Invalid data")]
+	#[cfg_attr(feature = "std", error("Invalid data"))]
 	InvalidData,
+	/// Data which could not be decoded as a param.
+	#[cfg_attr(feature = "std", error("Invalid data of param {index}: expected {kind} at offset {offset}"))]
+	InvalidDataAt {
+		/// Index of the decoded param, among the decoded types, which contains the invalid data.
+		index: usize,
+		/// Type of the innermost param which could not be decoded, e.g. an element of an array.
+		kind: ParamType,
+		/// Byte offset of the head of the innermost param in the decoded data.
+		offset: usize,
+	},
+	/// Tokens which do not match the types of the params.
+	#[cfg_attr(feature = "std", error("Invalid tokens: {}", join(.0)))]
+	TypeMismatch(Vec<TypeMismatch>),
+	/// Types are nested deeper than allowed.
+	#[cfg_attr(feature = "std", error("Type nesting is too deep"))]
+	NestingTooDeep,
 	/// Serialization error.
+	#[cfg(feature = "std")]
 	#[error("Serialization error: {0}")]
 	SerdeJson(#[from] serde_json::Error),
 	/// Integer parsing error.
-	#[error("Integer parsing error: {0}")]
-	ParseInt(#[from] num::ParseIntError),
+	#[cfg_attr(feature = "std", error("Integer parsing error: {0}"))]
+	ParseInt(#[cfg_attr(feature = "std", from)] num::ParseIntError),
 	/// UTF-8 parsing error.
-	#[error("UTF-8 parsing error: {0}")]
-	Utf8(#[from] string::FromUtf8Error),
+	#[cfg_attr(feature = "std", error("UTF-8 parsing error: {0}"))]
+	Utf8(#[cfg_attr(feature = "std", from)] string::FromUtf8Error),
 	/// Hex string parsing error.
-	#[error("Hex parsing error: {0}")]
-	Hex(#[from] hex::FromHexError),
+	#[cfg_attr(feature = "std", error("Hex parsing error: {0}"))]
+	Hex(#[cfg_attr(feature = "std", from)] hex::FromHexError),
 	/// Other errors.
-	#[error("{0}")]
-	Other(#[from] anyhow::Error),
+	#[cfg_attr(feature = "std", error("{0}"))]
+	Other(#[cfg_attr(feature = "std", from)] anyhow::Error),
+}
+
+/// Token which does not match the type of its param, see `Token::type_mismatches`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeMismatch {
+	/// Position of the token and the param.
+	pub index: usize,
+	/// Type of the param, `None` if there are more tokens than params.
+	pub expected: Option<ParamType>,
+	/// Type of the token, `None` if there are fewer tokens than params.
+	pub found: Option<ParamType>,
+}
+
+impl TypeMismatch {
+	/// Returns the mismatches of the tokens and the types, empty if they match.
+	pub(crate) fn of_tokens(tokens: &[Token], types: &[ParamType]) -> Vec<TypeMismatch> {
+		(0..tokens.len().max(types.len()))
+			.filter_map(|index| match (types.get(index), tokens.get(index)) {
+				(Some(kind), Some(token)) if token.type_check_strict(kind) => None,
+				(kind, token) => {
+					Some(TypeMismatch { index, expected: kind.cloned(), found: token.map(Token::param_type) })
+				}
+			})
+			.collect()
+	}
+}
+
+impl fmt::Display for TypeMismatch {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match (&self.expected, &self.found) {
+			(Some(expected), Some(found)) => write!(f, "param {} expected {}, found {}", self.index, expected, found),
+			(Some(expected), None) => write!(f, "param {} expected {}, found nothing", self.index, expected),
+			(None, Some(found)) => write!(f, "param {} unexpected {}", self.index, found),
+			(None, None) => write!(f, "param {}", self.index),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+fn join(mismatches: &[TypeMismatch]) -> String {
+	mismatches.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(not(feature = "std"))]
+impl From<num::ParseIntError> for Error {
+	fn from(err: num::ParseIntError) -> Self {
+		Error::ParseInt(err)
+	}
+}
+
+#[cfg(not(feature = "std"))]
+impl From<string::FromUtf8Error> for Error {
+	fn from(err: string::FromUtf8Error) -> Self {
+		Error::Utf8(err)
+	}
+}
+
+#[cfg(not(feature = "std"))]
+impl From<hex::FromHexError> for Error {
+	fn from(err: hex::FromHexError) -> Self {
+		Error::Hex(err)
+	}
+}
+
+#[cfg(not(feature = "std"))]
+impl From<anyhow::Error> for Error {
+	fn from(err: anyhow::Error) -> Self {
+		Error::Other(err)
+	}
 }
 
 impl From<uint::FromDecStrErr> for Error {
@@ -8,13 +8,15 @@
 
 //! Contract event.
 
+use ethereum_types::{Bloom, BloomInput};
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 use std::collections::HashMap;
 
 use crate::{
-	decode, encode, signature::long_signature, Error, EventParam, Hash, Log, LogParam, ParamType, RawLog,
-	RawTopicFilter, Result, Token, Topic, TopicFilter,
+	decode, encode,
+	signature::{format_signature, long_signature},
+	Error, EventParam, Hash, Log, LogParam, ParamType, RawLog, RawTopicFilter, Result, Token, Topic, TopicFilter,
 };
 
 /// Contract event.
@@ -30,9 +32,11 @@ pub struct Event {
 }
 
 impl Event {
-	/// Returns names of all params.
-	fn params_names(&self) -> Vec<String> {
-		self.inputs.iter().map(|p| p.name.clone()).collect()
+	/// Parses a human-readable signature, e.g. `event Transfer(address indexed from, address indexed to, uint256 value)`.
+	///
+	/// The `event` keyword and param names are optional.
+	pub fn from_signature(signature: &str) -> Result<Self> {
+		crate::human_readable::parse_event(signature)
 	}
 
 	/// Returns types of all params.
@@ -50,20 +54,69 @@ impl Event {
 		long_signature(&self.name, &self.param_types())
 	}
 
+	/// Returns the canonical signature of the event, the hash of which is `signature`, e.g.
+	/// `Transfer(address,address,uint256)`.
+	pub fn canonical_signature(&self) -> String {
+		format_signature(&self.name, self.inputs.iter().map(|param| &param.kind))
+	}
+
+	/// Returns the first topic of logs of this event, the keccak hash of its
+	/// canonical signature, e.g. `Transfer(address,address,uint256)`.
+	///
+	/// The hash is computed on every call, since the fields of the event may
+	/// change. Interfaces generated by `ethabi-derive` cache it in `topic0()`.
+	pub fn topic0(&self) -> Hash {
+		self.signature()
+	}
+
+	/// Returns the topic under which the indexed param `name` with the given value is logged.
+	pub fn topic_for(&self, name: &str, value: &Token) -> Result<Hash> {
+		let param = self
+			.inputs
+			.iter()
+			.find(|p| p.indexed && p.name == name)
+			.ok_or_else(|| Error::InvalidName(name.to_owned()))?;
+
+		if !value.type_check(&param.kind) {
+			return Err(Error::InvalidData);
+		}
+
+		Ok(topic_hash(value))
+	}
+
+	/// Computes the logs bloom of the topics of a log of the event, i.e. of its signature unless
+	/// anonymous and of the values of its first indexed params.
+	///
+	/// The bloom of a log also covers the address of the contract, which may be accrued to the result.
+	pub fn bloom(&self, values: &[Token]) -> Result<Bloom> {
+		let params = self.indexed_params(true);
+		if values.len() > params.len() {
+			return Err(Error::InvalidData);
+		}
+
+		let mut bloom = Bloom::default();
+		if !self.anonymous {
+			bloom.accrue(BloomInput::Raw(self.signature().as_bytes()));
+		}
+		for (param, value) in params.iter().zip(values) {
+			if !value.type_check(&param.kind) {
+				return Err(Error::InvalidData);
+			}
+			bloom.accrue(BloomInput::Raw(topic_hash(value).as_bytes()));
+		}
+		Ok(bloom)
+	}
+
 	/// Creates topic filter
+	///
+	/// The filter of an event matches its signature as the first topic, unless the event is
+	/// anonymous, in which case the topics only match the values of the indexed params.
 	pub fn filter(&self, raw: RawTopicFilter) -> Result<TopicFilter> {
 		fn convert_token(token: Token, kind: &ParamType) -> Result<Hash> {
 			if !token.type_check(kind) {
 				return Err(Error::InvalidData);
 			}
-			let encoded = encode(&[token]);
-			if encoded.len() == 32 {
-				let mut data = [0u8; 32];
-				data.copy_from_slice(&encoded);
-				Ok(data.into())
-			} else {
-				Ok(Hash::from_slice(Keccak256::digest(&encoded).as_slice()))
-			}
+			Ok(topic_hash(&token))
 		}
 
 		fn convert_topic(topic: Topic<Token>, kind: Option<&ParamType>) -> Result<Topic<Hash>> {
@@ -90,9 +143,12 @@ impl Event {
 				topic0: convert_topic(raw.topic0, kinds.get(0))?,
 				topic1: convert_topic(raw.topic1, kinds.get(1))?,
 				topic2: convert_topic(raw.topic2, kinds.get(2))?,
-				topic3: Topic::Any,
+				topic3: convert_topic(raw.topic3, kinds.get(3))?,
 			}
 		} else {
+			if !raw.topic3.is_any() {
+				return Err(Error::InvalidData);
+			}
 			TopicFilter {
 				topic0: Topic::This(self.signature()),
 				topic1: convert_topic(raw.topic0, kinds.get(0))?,
@@ -162,25 +218,74 @@ impl Event {
 		let named_tokens = topics_named_tokens.chain(data_named_tokens).collect::<HashMap<String, Token>>();
 
 		let decoded_params = self
-			.params_names()
-			.into_iter()
-			.map(|name| LogParam { name: name.clone(), value: named_tokens[&name].clone() })
+			.inputs
+			.iter()
+			.map(|p| LogParam { name: p.name.clone(), value: named_tokens[&p.name].clone() })
 			.collect();
 
 		let result = Log { params: decoded_params };
 
 		Ok(result)
 	}
+
+	/// Parses the non-indexed params of the event from the data of a log, e.g. when its topics
+	/// are not available.
+	///
+	/// The indexed params are left out of the returned log.
+	pub fn parse_data(&self, data: &[u8]) -> Result<Log> {
+		let data_params = self.indexed_params(false);
+		let data_types = data_params.iter().map(|p| p.kind.clone()).collect::<Vec<ParamType>>();
+		let data_tokens = decode(&data_types, data)?;
+		let params =
+			data_params.into_iter().zip(data_tokens).map(|(p, value)| LogParam { name: p.name, value }).collect();
+		Ok(Log { params })
+	}
+}
+
+/// Computes the topic of an indexed value, following
+/// https://docs.soliditylang.org/en/develop/abi-spec.html#encoding-of-indexed-event-parameters
+fn topic_hash(token: &Token) -> Hash {
+	match *token {
+		Token::Bytes(ref bytes) => Hash::from_slice(&Keccak256::digest(bytes)),
+		Token::String(ref s) => Hash::from_slice(&Keccak256::digest(s.as_bytes())),
+		Token::Array(_) | Token::FixedArray(_) | Token::Tuple(_) => {
+			let mut encoded = vec![];
+			encode_in_place(token, &mut encoded);
+			Hash::from_slice(&Keccak256::digest(&encoded))
+		}
+		_ => Hash::from_slice(&encode(std::slice::from_ref(token))),
+	}
+}
+
+/// Encodes a value the way it is hashed into a topic: elements are padded to
+/// 32 bytes and concatenated, without any lengths or offsets.
+fn encode_in_place(token: &Token, out: &mut Vec<u8>) {
+	match *token {
+		Token::Bytes(ref bytes) => pad_in_place(bytes, out),
+		Token::String(ref s) => pad_in_place(s.as_bytes(), out),
+		Token::Array(ref tokens) | Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) => {
+			tokens.iter().for_each(|token| encode_in_place(token, out))
+		}
+		_ => out.extend(encode(std::slice::from_ref(token))),
+	}
+}
+
+fn pad_in_place(bytes: &[u8], out: &mut Vec<u8>) {
+	out.extend_from_slice(bytes);
+	out.resize(out.len() + (32 - bytes.len() % 32) % 32, 0);
 }
 
 #[cfg(test)]
 mod tests {
+	use super::topic_hash;
+	use crate::encode;
 	use crate::{
 		log::{Log, RawLog},
 		signature::long_signature,
 		token::Token,
-		Event, EventParam, LogParam, ParamType,
+		Event, EventParam, LogParam, ParamType, RawTopicFilter, Topic,
 	};
+	use ethereum_types::{Bloom, BloomInput};
 	use hex_literal::hex;
 
 	#[test]
@@ -271,4 +376,151 @@ mod tests {
 			}
 		);
 	}
+
+	#[test]
+	fn test_parse_data() {
+		let event =
+			Event::from_signature("event Transfer(address indexed from, address indexed to, uint256 value)").unwrap();
+		let data = hex!("0000000000000000000000000000000000000000000000000000000000000007");
+		assert_eq!(
+			event.parse_data(&data).unwrap(),
+			Log { params: vec![LogParam { name: "value".to_owned(), value: Token::Uint(7.into()) }] }
+		);
+		assert!(event.parse_data(&data[..31]).is_err());
+	}
+
+	#[test]
+	fn test_topic0() {
+		let event = Event {
+			name: "Transfer".to_owned(),
+			inputs: vec![
+				EventParam { name: "from".to_owned(), kind: ParamType::Address, indexed: true },
+				EventParam { name: "to".to_owned(), kind: ParamType::Address, indexed: true },
+				EventParam { name: "value".to_owned(), kind: ParamType::Uint(256), indexed: false },
+			],
+			anonymous: false,
+		};
+
+		assert_eq!(event.topic0(), hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").into());
+		assert_eq!(event.canonical_signature(), "Transfer(address,address,uint256)");
+
+		let mut approval = event.clone();
+		approval.name = "Approval".to_owned();
+		assert_eq!(approval.topic0(), hex!("8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925").into());
+		assert_eq!(event.topic0(), hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").into());
+	}
+
+	#[test]
+	fn test_topic_for() {
+		let event = Event {
+			name: "foo".to_owned(),
+			inputs: vec![
+				EventParam { name: "a".to_owned(), kind: ParamType::Address, indexed: true },
+				EventParam { name: "b".to_owned(), kind: ParamType::String, indexed: true },
+				EventParam {
+					name: "c".to_owned(),
+					kind: ParamType::Array(Box::new(ParamType::Uint(8))),
+					indexed: true,
+				},
+				EventParam { name: "d".to_owned(), kind: ParamType::Uint(256), indexed: false },
+			],
+			anonymous: false,
+		};
+
+		assert_eq!(
+			event.topic_for("a", &Token::Address([0x11; 20].into())).unwrap(),
+			hex!("0000000000000000000000001111111111111111111111111111111111111111").into()
+		);
+		// keccak256("hello")
+		assert_eq!(
+			event.topic_for("b", &Token::String("hello".into())).unwrap(),
+			hex!("1c8aff950685c2ed4bc3174f3472287b56d9517b9c948127319a09a7a36deac8").into()
+		);
+		// keccak256(abi.encodePacked(uint256(1), uint256(2)))
+		assert_eq!(
+			event.topic_for("c", &Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())])).unwrap(),
+			hex!("e90b7bceb6e7df5418fb78d8ee546e97c83a08bbccc01a0644d599ccd2a7c2e0").into()
+		);
+		assert!(event.topic_for("a", &Token::Bool(true)).is_err());
+		assert!(event.topic_for("d", &Token::Uint(1.into())).is_err());
+		assert!(event.topic_for("e", &Token::Uint(1.into())).is_err());
+
+		let filter = event
+			.filter(RawTopicFilter { topic1: Topic::This(Token::String("hello".into())), ..Default::default() })
+			.unwrap();
+		assert_eq!(filter.topic2, Topic::This(event.topic_for("b", &Token::String("hello".into())).unwrap()));
+	}
+
+	#[test]
+	fn test_bloom() {
+		let event =
+			Event::from_signature("event Transfer(address indexed from, address indexed to, uint256 value)").unwrap();
+		let from = Token::Address([0x11; 20].into());
+		let to = Token::Address([0x22; 20].into());
+		let other = Token::Address([0x33; 20].into());
+
+		let bloom = event.bloom(&[from.clone(), to.clone()]).unwrap();
+		let filter = |from: Topic<Token>, to: Topic<Token>| {
+			event.filter(RawTopicFilter { topic0: from, topic1: to, ..Default::default() }).unwrap()
+		};
+		assert!(filter(Topic::Any, Topic::Any).matches_bloom(&bloom));
+		assert!(filter(from.clone().into(), to.clone().into()).matches_bloom(&bloom));
+		assert!(filter(Topic::Any, vec![other.clone(), to].into()).matches_bloom(&bloom));
+		assert!(!filter(from.clone().into(), other.clone().into()).matches_bloom(&bloom));
+		assert!(!filter(Topic::Any, Topic::OneOf(vec![])).matches_bloom(&bloom));
+
+		assert_eq!(event.bloom(&[]).unwrap(), {
+			let mut bloom = Bloom::default();
+			bloom.accrue(BloomInput::Raw(event.signature().as_bytes()));
+			bloom
+		});
+		assert!(event.bloom(&[Token::Bool(true)]).is_err());
+		assert!(event.bloom(&[from, other.clone(), other]).is_err());
+	}
+
+	#[test]
+	fn test_anonymous_event() {
+		let event = Event::from_signature(
+			"event Moved(address indexed a, address indexed b, address indexed c, address indexed d, uint256 e) anonymous",
+		)
+		.unwrap();
+		let addresses: Vec<_> = (1..=4).map(|i| Token::Address([i; 20].into())).collect();
+		let topics: Vec<_> = addresses.iter().map(topic_hash).collect();
+
+		let filter = event
+			.filter(
+				RawTopicFilter::new(addresses[0].clone().into(), Topic::Any, Topic::Any)
+					.with_topic3(addresses[3].clone().into()),
+			)
+			.unwrap();
+		assert_eq!(filter.topic0, Topic::This(topics[0]));
+		assert_eq!(filter.topic1, Topic::Any);
+		assert_eq!(filter.topic3, Topic::This(topics[3]));
+
+		let log = event.parse_log(RawLog { topics: topics.clone(), data: encode(&[Token::Uint(5.into())]) }).unwrap();
+		let values: Vec<_> = log.params.into_iter().map(|param| param.value).collect();
+		assert_eq!(values, [addresses, vec![Token::Uint(5.into())]].concat());
+		assert!(event
+			.parse_log(RawLog { topics: topics[..3].to_vec(), data: encode(&[Token::Uint(5.into())]) })
+			.is_err());
+
+		let event = Event::from_signature("event Transfer(address indexed from)").unwrap();
+		let raw = RawTopicFilter::default().with_topic3(Token::Address([1; 20].into()).into());
+		assert!(event.filter(raw).is_err());
+	}
+
+	#[test]
+	fn test_filter_one_of() {
+		let event =
+			Event::from_signature("event Transfer(address indexed from, address indexed to, uint256 value)").unwrap();
+		let to = [Token::Address([0x11; 20].into()), Token::Address([0x22; 20].into())];
+
+		let filter = event.filter(RawTopicFilter { topic1: to.clone().into(), ..Default::default() }).unwrap();
+		assert_eq!(filter.topic0, Topic::This(event.signature()));
+		assert_eq!(filter.topic1, Topic::Any);
+		assert_eq!(filter.topic2, Topic::OneOf(to.iter().map(|token| event.topic_for("to", token).unwrap()).collect()));
+
+		let invalid = vec![Token::Address([0x11; 20].into()), Token::Bool(true)];
+		assert!(event.filter(RawTopicFilter { topic1: invalid.into(), ..Default::default() }).is_err());
+	}
 }
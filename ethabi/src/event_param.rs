@@ -8,7 +8,7 @@
 
 //! Event param specification.
 
-use crate::{param_type::Writer, ParamType, TupleParam};
+use crate::{param_type::Writer, Hash, ParamType, Token, TupleParam};
 use serde::{
 	de::{Error, MapAccess, Visitor},
 	ser::SerializeMap,
@@ -27,6 +27,30 @@ pub struct EventParam {
 	pub indexed: bool,
 }
 
+impl EventParam {
+	/// Whether the param is logged as the keccak hash of its value rather than the value itself, as indexed
+	/// params of type `string`, `bytes`, arrays and tuples are. `Event::parse_log` returns such values as `bytes32`.
+	pub fn is_hashed(&self) -> bool {
+		self.indexed
+			&& matches!(
+				self.kind,
+				ParamType::String
+					| ParamType::Bytes
+					| ParamType::Array(_)
+					| ParamType::FixedArray(_, _)
+					| ParamType::Tuple(_)
+			)
+	}
+
+	/// Returns the keccak hash logged for the param, given its value parsed from a log, if only the hash was logged.
+	pub fn logged_hash(&self, value: &Token) -> Option<Hash> {
+		match value {
+			Token::FixedBytes(bytes) if self.is_hashed() && bytes.len() == 32 => Some(Hash::from_slice(bytes)),
+			_ => None,
+		}
+	}
+}
+
 impl<'a> Deserialize<'a> for EventParam {
 	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
 	where
@@ -111,7 +135,23 @@ impl Serialize for EventParam {
 
 #[cfg(test)]
 mod tests {
-	use crate::{tests::assert_json_eq, EventParam, ParamType};
+	use crate::{tests::assert_json_eq, EventParam, Hash, ParamType, Token};
+
+	#[test]
+	fn event_param_logged_hash() {
+		let hash = Hash::repeat_byte(0xaa);
+		let value = Token::FixedBytes(hash.as_bytes().to_vec());
+		let param = EventParam { name: "name".to_owned(), kind: ParamType::String, indexed: true };
+		assert!(param.is_hashed());
+		assert_eq!(param.logged_hash(&value), Some(hash));
+
+		let param = EventParam { name: "name".to_owned(), kind: ParamType::FixedBytes(32), indexed: true };
+		assert!(!param.is_hashed());
+		assert_eq!(param.logged_hash(&value), None);
+
+		let param = EventParam { name: "name".to_owned(), kind: ParamType::String, indexed: false };
+		assert!(!param.is_hashed());
+	}
 
 	#[test]
 	fn event_param_deserialization() {
@@ -0,0 +1,242 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Word by word explanation of ABI encoded data, for debugging encoding mismatches.
+
+use alloc::collections::BTreeSet;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{decode, decoder::as_usize, util::head_slots, Error, ParamType, Result, Word};
+
+/// What a word of ABI encoded data holds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WordRole {
+	/// Value of the elementary type, fixed arrays and tuples of static types being encoded element by element.
+	Value(ParamType),
+	/// Offset of the tail of a dynamic value, relative to the start of the head the offset is in.
+	Offset(usize),
+	/// Number of elements of an array, or of bytes of `bytes` or a `string`.
+	Length(usize),
+	/// Contents of `bytes` or a `string`, right padded with zeros.
+	Data,
+	/// Word no param refers to.
+	Unused,
+}
+
+/// Word of ABI encoded data with the param it belongs to and what it holds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplainedWord {
+	/// Offset of the word in the data.
+	pub offset: usize,
+	/// The word.
+	pub word: Word,
+	/// Param the word belongs to, e.g. `ids[1]` or `order.0` for elements of arrays and tuples, empty if unused.
+	pub param: String,
+	/// What the word holds.
+	pub role: WordRole,
+	/// Whether the word is in the head, rather than in the tail of a dynamic param.
+	pub head: bool,
+}
+
+/// Explains ABI encoded data of the types word by word, naming params by their index, e.g. `#0`.
+///
+/// The data must decode as the types. Words are in the order of their offsets.
+pub fn explain_encoding(types: &[ParamType], data: &[u8]) -> Result<Vec<ExplainedWord>> {
+	let params: Vec<_> = types.iter().enumerate().map(|(index, kind)| (format!("#{}", index), kind.clone())).collect();
+	explain_params(&params, data, 0)
+}
+
+/// Explains encoded data of the named params, adding `start` to the offsets of the words, e.g. for a selector.
+pub(crate) fn explain_params(params: &[(String, ParamType)], data: &[u8], start: usize) -> Result<Vec<ExplainedWord>> {
+	let types: Vec<_> = params.iter().map(|(_, kind)| kind.clone()).collect();
+	decode(&types, data)?;
+	if !data.len().is_multiple_of(32) {
+		return Err(Error::InvalidData);
+	}
+
+	let mut explainer = Explainer { data, words: Vec::new() };
+	explainer.sequence(params, 0)?;
+	let mut words = core::mem::take(&mut explainer.words);
+	let used: BTreeSet<_> = words.iter().map(|(offset, _, _)| *offset).collect();
+	words.extend(
+		(0..data.len())
+			.step_by(32)
+			.filter(|offset| !used.contains(offset))
+			.map(|offset| (offset, String::new(), WordRole::Unused)),
+	);
+	// stable, so words read more than once, as only malformed data has them, keep the order they were read in
+	words.sort_by_key(|(offset, _, _)| *offset);
+
+	let head_len = 32 * types.iter().map(head_slots).sum::<usize>();
+	words
+		.into_iter()
+		.map(|(offset, param, role)| {
+			Ok(ExplainedWord {
+				offset: start + offset,
+				word: explainer.word(offset)?,
+				param,
+				role,
+				head: offset < head_len,
+			})
+		})
+		.collect()
+}
+
+/// Collects the offsets, params and roles of the words of encoded data.
+struct Explainer<'a> {
+	data: &'a [u8],
+	words: Vec<(usize, String, WordRole)>,
+}
+
+impl Explainer<'_> {
+	fn word(&self, offset: usize) -> Result<Word> {
+		let slice = offset.checked_add(32).and_then(|end| self.data.get(offset..end)).ok_or(Error::InvalidData)?;
+		let mut word = Word::default();
+		word.copy_from_slice(slice);
+		Ok(word)
+	}
+
+	/// Explains params encoded one after another from `base`, dynamic ones by their offset relative to it.
+	fn sequence(&mut self, params: &[(String, ParamType)], base: usize) -> Result<()> {
+		let mut head = base;
+		for (name, kind) in params {
+			match (kind.is_dynamic(), kind) {
+				(true, _) => {
+					let offset = as_usize(&self.word(head)?)?;
+					self.words.push((head, name.clone(), WordRole::Offset(offset)));
+					self.tail(name, kind, base.checked_add(offset).ok_or(Error::InvalidData)?)?;
+				}
+				(false, ParamType::FixedArray(inner, size)) => self.sequence(&elements(name, inner, *size), head)?,
+				(false, ParamType::Tuple(kinds)) => self.sequence(&components(name, kinds), head)?,
+				(false, _) => self.words.push((head, name.clone(), WordRole::Value(kind.clone()))),
+			}
+			head += 32 * head_slots(kind);
+		}
+		Ok(())
+	}
+
+	/// Explains the tail of a dynamic value, which starts at `offset`.
+	fn tail(&mut self, name: &str, kind: &ParamType, offset: usize) -> Result<()> {
+		match kind {
+			ParamType::Bytes | ParamType::String => {
+				let len = as_usize(&self.word(offset)?)?;
+				self.words.push((offset, name.to_owned(), WordRole::Length(len)));
+				for index in 0..len.div_ceil(32) {
+					self.words.push((offset + 32 * (index + 1), name.to_owned(), WordRole::Data));
+				}
+			}
+			ParamType::Array(inner) => {
+				let len = as_usize(&self.word(offset)?)?;
+				self.words.push((offset, name.to_owned(), WordRole::Length(len)));
+				self.sequence(&elements(name, inner, len), offset + 32)?;
+			}
+			ParamType::FixedArray(inner, size) => self.sequence(&elements(name, inner, *size), offset)?,
+			ParamType::Tuple(kinds) => self.sequence(&components(name, kinds), offset)?,
+			_ => unreachable!("only dynamic types have tails"),
+		}
+		Ok(())
+	}
+}
+
+fn elements(name: &str, kind: &ParamType, len: usize) -> Vec<(String, ParamType)> {
+	(0..len).map(|index| (format!("{}[{}]", name, index), kind.clone())).collect()
+}
+
+fn components(name: &str, kinds: &[ParamType]) -> Vec<(String, ParamType)> {
+	kinds.iter().enumerate().map(|(index, kind)| (format!("{}.{}", name, index), kind.clone())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{explain_encoding, WordRole};
+	use crate::{encode, Address, ParamType, Token, Uint};
+
+	#[test]
+	fn test_explain_encoding() {
+		let types = [
+			ParamType::Address,
+			ParamType::Bytes,
+			ParamType::Array(Box::new(ParamType::Uint(256))),
+			ParamType::Tuple(vec![ParamType::Bool, ParamType::Uint(8)]),
+		];
+		let data = encode(&[
+			Token::Address(Address::repeat_byte(0x11)),
+			Token::Bytes(vec![0xff; 33]),
+			Token::Array(vec![Token::Uint(Uint::from(1)), Token::Uint(Uint::from(2))]),
+			Token::Tuple(vec![Token::Bool(true), Token::Uint(Uint::from(8))]),
+		]);
+
+		let words: Vec<_> = explain_encoding(&types, &data)
+			.unwrap()
+			.into_iter()
+			.map(|word| (word.offset, word.param, word.role, word.head))
+			.collect();
+		assert_eq!(
+			words,
+			vec![
+				(0x00, "#0".to_owned(), WordRole::Value(ParamType::Address), true),
+				(0x20, "#1".to_owned(), WordRole::Offset(0xa0), true),
+				(0x40, "#2".to_owned(), WordRole::Offset(0x100), true),
+				(0x60, "#3.0".to_owned(), WordRole::Value(ParamType::Bool), true),
+				(0x80, "#3.1".to_owned(), WordRole::Value(ParamType::Uint(8)), true),
+				(0xa0, "#1".to_owned(), WordRole::Length(33), false),
+				(0xc0, "#1".to_owned(), WordRole::Data, false),
+				(0xe0, "#1".to_owned(), WordRole::Data, false),
+				(0x100, "#2".to_owned(), WordRole::Length(2), false),
+				(0x120, "#2[0]".to_owned(), WordRole::Value(ParamType::Uint(256)), false),
+				(0x140, "#2[1]".to_owned(), WordRole::Value(ParamType::Uint(256)), false),
+			]
+		);
+
+		let mut padded = data.clone();
+		padded.extend_from_slice(&[0; 32]);
+		let last = explain_encoding(&types, &padded).unwrap().pop().unwrap();
+		assert_eq!((last.offset, last.param.as_str(), last.role), (0x160, "", WordRole::Unused));
+		assert!(explain_encoding(&types, &data[..data.len() - 1]).is_err());
+	}
+
+	#[test]
+	#[cfg(feature = "std")]
+	fn test_explain_calldata() {
+		use crate::{Function, Param, StateMutability};
+
+		#[allow(deprecated)]
+		let function = Function {
+			name: "f".to_owned(),
+			inputs: vec![
+				Param { name: "names".to_owned(), kind: ParamType::Array(Box::new(ParamType::String)) },
+				Param { name: "".to_owned(), kind: ParamType::Bool },
+			],
+			outputs: vec![],
+			constant: false,
+			state_mutability: StateMutability::NonPayable,
+		};
+		let calldata =
+			function.encode_input(&[Token::Array(vec![Token::String("a".to_owned())]), Token::Bool(true)]).unwrap();
+
+		let words: Vec<_> = function
+			.explain_calldata(&calldata)
+			.unwrap()
+			.into_iter()
+			.map(|word| (word.offset, word.param, word.role))
+			.collect();
+		assert_eq!(
+			words,
+			vec![
+				(0x04, "names".to_owned(), WordRole::Offset(0x40)),
+				(0x24, "#1".to_owned(), WordRole::Value(ParamType::Bool)),
+				(0x44, "names".to_owned(), WordRole::Length(1)),
+				(0x64, "names[0]".to_owned(), WordRole::Offset(0x20)),
+				(0x84, "names[0]".to_owned(), WordRole::Length(1)),
+				(0xa4, "names[0]".to_owned(), WordRole::Data),
+			]
+		);
+		assert!(function.explain_calldata(&calldata[4..]).is_err());
+	}
+}
@@ -7,19 +7,38 @@
 // except according to those terms.
 
 use crate::{Hash, Token};
+use ethereum_types::{Bloom, BloomInput};
 use serde::{Serialize, Serializer};
 use serde_json::Value;
 use std::ops;
 
-/// Raw topic filter.
+/// Raw topic filter, of the values of the indexed params of an event.
+///
+/// Each topic matches any value, one value or, with `Topic::OneOf`, any of many values. Further
+/// topics may be added, so filters are built with `new` or `default` rather than literals.
 #[derive(Debug, PartialEq, Default)]
+#[non_exhaustive]
 pub struct RawTopicFilter {
-	/// Topic.
+	/// Value of the first indexed param.
 	pub topic0: Topic<Token>,
-	/// Topic.
+	/// Value of the second indexed param.
 	pub topic1: Topic<Token>,
-	/// Topic.
+	/// Value of the third indexed param.
 	pub topic2: Topic<Token>,
+	/// Value of the fourth indexed param, which only anonymous events may have.
+	pub topic3: Topic<Token>,
+}
+
+impl RawTopicFilter {
+	/// Creates a filter of the first three indexed params, matching any value of the fourth.
+	pub fn new(topic0: Topic<Token>, topic1: Topic<Token>, topic2: Topic<Token>) -> Self {
+		RawTopicFilter { topic0, topic1, topic2, topic3: Topic::Any }
+	}
+
+	/// Filters the fourth indexed param too, which only anonymous events may have.
+	pub fn with_topic3(self, topic3: Topic<Token>) -> Self {
+		RawTopicFilter { topic3, ..self }
+	}
 }
 
 /// Topic filter.
@@ -35,6 +54,43 @@ pub struct TopicFilter {
 	pub topic3: Topic<Hash>,
 }
 
+impl TopicFilter {
+	/// Returns the topics in the format of the `topics` param of `eth_getLogs`, i.e. `null` for
+	/// any topic, a string for one and an array for one of many, without trailing `null`s.
+	pub fn to_json(&self) -> Value {
+		let mut topics = vec![&self.topic0, &self.topic1, &self.topic2, &self.topic3];
+		while topics.last().is_some_and(|topic| topic.is_any()) {
+			topics.pop();
+		}
+		Value::Array(topics.into_iter().map(Topic::to_json).collect())
+	}
+
+	/// Returns the topics as taken by log filters of client libraries, e.g. `web3::types::FilterBuilder::topics`,
+	/// `None` matching any topic and a list of hashes any of them.
+	pub fn into_topics(self) -> [Option<Vec<Hash>>; 4] {
+		fn hashes(topic: Topic<Hash>) -> Option<Vec<Hash>> {
+			match topic {
+				Topic::Any => None,
+				topic => Some(topic.into()),
+			}
+		}
+
+		[hashes(self.topic0), hashes(self.topic1), hashes(self.topic2), hashes(self.topic3)]
+	}
+
+	/// Returns whether a logs bloom, e.g. of a block, may contain logs matching the filter.
+	///
+	/// False positives are possible, false negatives are not, so blocks may be skipped without
+	/// fetching their receipts if it returns `false`.
+	pub fn matches_bloom(&self, bloom: &Bloom) -> bool {
+		[&self.topic0, &self.topic1, &self.topic2, &self.topic3].iter().all(|topic| match topic {
+			Topic::Any => true,
+			Topic::This(hash) => bloom.contains_input(BloomInput::Raw(hash.as_bytes())),
+			Topic::OneOf(hashes) => hashes.iter().any(|hash| bloom.contains_input(BloomInput::Raw(hash.as_bytes()))),
+		})
+	}
+}
+
 impl Serialize for TopicFilter {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
 	where
@@ -104,6 +160,18 @@ impl<T> From<Vec<T>> for Topic<T> {
 	}
 }
 
+impl<T: Clone> From<&[T]> for Topic<T> {
+	fn from(topics: &[T]) -> Self {
+		Topic::OneOf(topics.to_vec())
+	}
+}
+
+impl<T, const N: usize> From<[T; N]> for Topic<T> {
+	fn from(topics: [T; N]) -> Self {
+		Topic::OneOf(Vec::from(topics))
+	}
+}
+
 impl<T> From<Topic<T>> for Vec<T> {
 	fn from(topic: Topic<T>) -> Self {
 		match topic {
@@ -114,20 +182,26 @@ impl<T> From<Topic<T>> for Vec<T> {
 	}
 }
 
-impl Serialize for Topic<Hash> {
-	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-	where
-		S: Serializer,
-	{
-		let value = match *self {
+impl Topic<Hash> {
+	/// Returns the topic in the format of `eth_getLogs`, see `TopicFilter::to_json`.
+	pub fn to_json(&self) -> Value {
+		match *self {
 			Topic::Any => Value::Null,
 			Topic::OneOf(ref vec) => {
 				let v = vec.iter().map(|h| format!("0x{:x}", h)).map(Value::String).collect();
 				Value::Array(v)
 			}
 			Topic::This(ref hash) => Value::String(format!("0x{:x}", hash)),
-		};
-		value.serialize(serializer)
+		}
+	}
+}
+
+impl Serialize for Topic<Hash> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		self.to_json().serialize(serializer)
 	}
 }
 
@@ -175,11 +249,46 @@ mod tests {
 		assert_eq!(expected, &topic_str);
 	}
 
+	#[test]
+	fn test_topic_filter_to_json() {
+		let topic = TopicFilter {
+			topic0: Topic::This(hash("000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b")),
+			topic1: Topic::Any,
+			topic2: Topic::OneOf(vec![hash("0000000000000000000000000aff3454fce5edbc8cca8697c15331677e6ebccc")]),
+			topic3: Topic::Any,
+		};
+		assert_eq!(
+			topic.to_json(),
+			serde_json::json!([
+				"0x000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b",
+				null,
+				["0x0000000000000000000000000aff3454fce5edbc8cca8697c15331677e6ebccc"]
+			])
+		);
+		assert_eq!(TopicFilter::default().to_json(), serde_json::json!([]));
+	}
+
+	#[test]
+	fn test_topic_filter_into_topics() {
+		let topic = TopicFilter {
+			topic0: Topic::This(Hash::repeat_byte(1)),
+			topic1: Topic::Any,
+			topic2: Topic::OneOf(vec![Hash::repeat_byte(2), Hash::repeat_byte(3)]),
+			topic3: Topic::Any,
+		};
+		assert_eq!(
+			topic.into_topics(),
+			[Some(vec![Hash::repeat_byte(1)]), None, Some(vec![Hash::repeat_byte(2), Hash::repeat_byte(3)]), None]
+		);
+	}
+
 	#[test]
 	fn test_topic_from() {
 		assert_eq!(Topic::Any as Topic<u64>, None.into());
 		assert_eq!(Topic::This(10u64), 10u64.into());
 		assert_eq!(Topic::OneOf(vec![10u64, 20]), vec![10u64, 20].into());
+		assert_eq!(Topic::OneOf(vec![10u64, 20]), [10u64, 20].into());
+		assert_eq!(Topic::OneOf(vec![10u64, 20]), (&[10u64, 20][..]).into());
 	}
 
 	#[test]
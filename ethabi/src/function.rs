@@ -11,7 +11,11 @@
 use std::string::ToString;
 
 use crate::{
-	decode, encode, signature::short_signature, Bytes, Error, Param, ParamType, Result, StateMutability, Token,
+	decode, encoded_len,
+	encoder::write_tokens,
+	signature::{format_signature, short_signature},
+	util::head_slots,
+	Bytes, Detokenize, Error, ExplainedWord, InputTokens, Param, ParamType, Result, StateMutability, Token, Tokenize,
 };
 use serde::{Deserialize, Serialize};
 
@@ -37,6 +41,13 @@ pub struct Function {
 }
 
 impl Function {
+	/// Parses a human-readable signature, e.g. `transfer(address to, uint256 amount) returns (bool)`.
+	///
+	/// The `function` keyword, param names and modifiers such as `view` are optional.
+	pub fn from_signature(signature: &str) -> Result<Self> {
+		crate::human_readable::parse_function(signature)
+	}
+
 	/// Returns all input params of given function.
 	fn input_param_types(&self) -> Vec<ParamType> {
 		self.inputs.iter().map(|p| p.kind.clone()).collect()
@@ -48,16 +59,35 @@ impl Function {
 	}
 
 	/// Prepares ABI function call with given input params.
-	pub fn encode_input(&self, tokens: &[Token]) -> Result<Bytes> {
-		let params = self.input_param_types();
+	///
+	/// The params are either tokens, which are checked against the function inputs,
+	/// or `TypedTokens`, which were checked once when created.
+	pub fn encode_input<T: InputTokens + ?Sized>(&self, tokens: &T) -> Result<Bytes> {
+		let mut result = Vec::new();
+		self.encode_input_into(&mut result, tokens)?;
+		Ok(result)
+	}
 
-		if !Token::types_check(tokens, &params) {
-			return Err(Error::InvalidData);
-		}
+	/// Prepares ABI function call with given input params and appends it to `buffer`.
+	///
+	/// Nothing is written when the tokens do not match the function inputs.
+	pub fn encode_input_into<T: InputTokens + ?Sized>(&self, buffer: &mut Vec<u8>, tokens: &T) -> Result<()> {
+		self.encode_input_with_selector(buffer, self.selector(), &self.input_param_types(), tokens)
+	}
 
-		let signed = short_signature(&self.name, &params).to_vec();
-		let encoded = encode(tokens);
-		Ok(signed.into_iter().chain(encoded.into_iter()).collect())
+	/// Same as `encode_input_into`, with the selector and input types already computed.
+	pub(crate) fn encode_input_with_selector<T: InputTokens + ?Sized>(
+		&self,
+		buffer: &mut Vec<u8>,
+		selector: [u8; 4],
+		params: &[ParamType],
+		tokens: &T,
+	) -> Result<()> {
+		let tokens = tokens.checked_tokens(params)?;
+		buffer.reserve(4 + encoded_len(tokens));
+		buffer.extend_from_slice(&selector);
+		write_tokens(buffer, tokens);
+		Ok(())
 	}
 
 	/// Parses the ABI function output to list of tokens.
@@ -65,11 +95,60 @@ impl Function {
 		decode(&self.output_param_types(), &data)
 	}
 
+	/// Prepares ABI function call with input params converted from rust types, e.g. a tuple.
+	pub fn encode_typed_input<T: Tokenize>(&self, input: T) -> Result<Bytes> {
+		self.encode_input(&input.into_tokens())
+	}
+
+	/// Parses the ABI function output into rust types, e.g. a tuple.
+	pub fn decode_typed_output<T: Detokenize>(&self, data: &[u8]) -> Result<T> {
+		T::from_tokens(self.decode_output(data)?)
+	}
+
 	/// Parses the ABI function input to a list of tokens.
 	pub fn decode_input(&self, data: &[u8]) -> Result<Vec<Token>> {
 		decode(&self.input_param_types(), &data)
 	}
 
+	/// Parses transaction calldata, i.e. the function selector followed by the
+	/// ABI encoded input, to a list of tokens.
+	///
+	/// Fails if the selector does not match this function.
+	pub fn decode_calldata(&self, data: &[u8]) -> Result<Vec<Token>> {
+		if data.len() < 4 || data[..4] != self.selector() {
+			return Err(Error::InvalidData);
+		}
+
+		self.decode_input(&data[4..])
+	}
+
+	/// Returns the function selector, the first 4 bytes of the calldata.
+	///
+	/// The selector is the start of the keccak hash of the canonical signature,
+	/// e.g. `transfer(address,uint256)`. It is computed on every call, since the
+	/// fields of the function may change; `TypedFunction` computes it only once.
+	pub fn selector(&self) -> [u8; 4] {
+		short_signature(&self.name, &self.input_param_types())
+	}
+
+	/// Returns whether the function reads or modifies blockchain state.
+	///
+	/// Unlike the `state_mutability` field this also considers the `constant` field
+	/// of ABIs generated by Solidity before 0.5.0, which have no `stateMutability`.
+	pub fn state_mutability(&self) -> StateMutability {
+		#[allow(deprecated)]
+		match (self.constant, self.state_mutability) {
+			(true, StateMutability::NonPayable) => StateMutability::View,
+			(_, state_mutability) => state_mutability,
+		}
+	}
+
+	/// Returns the canonical signature of the function, the selector being the first 4 bytes of
+	/// its hash, e.g. `transfer(address,uint256)`. Unlike `signature`, it has no outputs.
+	pub fn canonical_signature(&self) -> String {
+		format_signature(&self.name, self.inputs.iter().map(|param| &param.kind))
+	}
+
 	/// Returns a signature that uniquely identifies this function.
 	///
 	/// Examples:
@@ -87,11 +166,113 @@ impl Function {
 			(_, _) => format!("{}({}):({})", self.name, inputs, outputs),
 		}
 	}
+
+	/// Renders the calldata layout of the function input as a table, e.g.
+	///
+	/// ```text
+	/// transfer(address,bytes) selector 0x29723511
+	/// offset  slots  param  type     layout       alignment
+	/// 0x04    1      to     address  inline       right
+	/// 0x24    1      data   bytes    tail offset  left
+	/// head: 64 bytes, tail: data
+	/// ```
+	///
+	/// Offsets include the 4 byte selector. Static params are encoded inline in the
+	/// head, dynamic ones in the tail, with their offset, relative to the end of the
+	/// selector, stored in the head. Values are padded to 32 byte words, right aligned
+	/// ones are padded on the left.
+	pub fn describe_encoding(&self) -> String {
+		let mut rows = vec![["offset", "slots", "param", "type", "layout", "alignment"].map(String::from)];
+		let mut head = 0;
+		let mut tail = Vec::new();
+		for (index, param) in self.inputs.iter().enumerate() {
+			let name = match param.name.is_empty() {
+				true => format!("#{}", index),
+				false => param.name.clone(),
+			};
+			let slots = head_slots(&param.kind);
+			let layout = match (param.kind.is_dynamic(), slots) {
+				(true, _) => "tail offset".to_owned(),
+				(false, 1) => "inline".to_owned(),
+				(false, _) => format!("inline, {} words", slots),
+			};
+			rows.push([
+				format!("0x{:02x}", 4 + 32 * head),
+				slots.to_string(),
+				name.clone(),
+				param.kind.to_string(),
+				layout,
+				alignment(&param.kind).to_owned(),
+			]);
+			if param.kind.is_dynamic() {
+				tail.push(name);
+			}
+			head += slots;
+		}
+
+		let mut widths = [0; 6];
+		for row in &rows {
+			for (width, cell) in widths.iter_mut().zip(row.iter()) {
+				*width = (*width).max(cell.len());
+			}
+		}
+
+		let mut result = format!("{} selector 0x{}\n", self.signature(), hex::encode(self.selector()));
+		for row in &rows {
+			let cells: Vec<_> =
+				row.iter().zip(widths.iter()).map(|(cell, width)| format!("{:1$}", cell, width)).collect();
+			result.push_str(cells.join("  ").trim_end());
+			result.push('\n');
+		}
+		let tail = match tail.is_empty() {
+			true => "empty".to_owned(),
+			false => tail.join(", "),
+		};
+		result.push_str(&format!("head: {} bytes, tail: {}", 32 * head, tail));
+		result
+	}
+
+	/// Explains calldata of a call of the function word by word, see `explain_encoding`.
+	///
+	/// Offsets include the 4 byte selector and params are named by their name, or index if unnamed, e.g. `#0`.
+	pub fn explain_calldata(&self, data: &[u8]) -> Result<Vec<ExplainedWord>> {
+		if data.len() < 4 || data[..4] != self.selector() {
+			return Err(Error::InvalidData);
+		}
+
+		crate::explain::explain_params(&named_params(&self.inputs), &data[4..], 4)
+	}
+
+	/// Explains the encoded output of the function word by word, naming params as `explain_calldata` does.
+	pub fn explain_output(&self, data: &[u8]) -> Result<Vec<ExplainedWord>> {
+		crate::explain::explain_params(&named_params(&self.outputs), data, 0)
+	}
+}
+
+/// Names and types of params, naming unnamed ones by their index.
+fn named_params(params: &[Param]) -> Vec<(String, ParamType)> {
+	params
+		.iter()
+		.enumerate()
+		.map(|(index, param)| match param.name.is_empty() {
+			true => (format!("#{}", index), param.kind.clone()),
+			false => (param.name.clone(), param.kind.clone()),
+		})
+		.collect()
+}
+
+/// How a value is aligned within its 32 byte words.
+fn alignment(kind: &ParamType) -> &'static str {
+	match *kind {
+		ParamType::FixedBytes(_) | ParamType::Function | ParamType::Bytes | ParamType::String => "left",
+		ParamType::Array(_) | ParamType::FixedArray(_, _) | ParamType::Tuple(_) => "per element",
+		_ => "right",
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use crate::{Function, Param, ParamType, StateMutability, Token};
+	use crate::{Function, Param, ParamType, StateMutability, Token, TypedTokens};
 	use hex_literal::hex;
 
 	#[test]
@@ -114,4 +295,164 @@ mod tests {
 		let expected = hex!("cdcd77c000000000000000000000000000000000000000000000000000000000000000450000000000000000000000000000000000000000000000000000000000000001").to_vec();
 		assert_eq!(encoded, expected);
 	}
+
+	#[test]
+	fn test_function_encode_typed_tokens() {
+		#[allow(deprecated)]
+		let func = Function {
+			name: "baz".to_owned(),
+			inputs: vec![
+				Param { name: "a".to_owned(), kind: ParamType::Uint(32) },
+				Param { name: "b".to_owned(), kind: ParamType::Bool },
+			],
+			outputs: vec![],
+			constant: false,
+			state_mutability: StateMutability::Payable,
+		};
+
+		let tokens = vec![Token::Uint(69.into()), Token::Bool(true)];
+		let typed = TypedTokens::new(&[ParamType::Uint(32), ParamType::Bool], tokens.clone()).unwrap();
+		assert_eq!(func.encode_input(&typed).unwrap(), func.encode_input(&tokens).unwrap());
+
+		let other = TypedTokens::new(&[ParamType::Uint(256), ParamType::Bool], tokens).unwrap();
+		assert_eq!(
+			func.encode_input(&other).unwrap_err().to_string(),
+			"Invalid tokens: param 0 expected uint32, found uint256"
+		);
+
+		let err = func.encode_input(&[Token::Bool(true)]).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"Invalid tokens: param 0 expected uint32, found bool, param 1 expected bool, found nothing"
+		);
+	}
+
+	#[test]
+	fn test_function_encode_call_into_buffer() {
+		#[allow(deprecated)]
+		let func = Function {
+			name: "baz".to_owned(),
+			inputs: vec![
+				Param { name: "a".to_owned(), kind: ParamType::Uint(32) },
+				Param { name: "b".to_owned(), kind: ParamType::Bool },
+			],
+			outputs: vec![],
+			constant: false,
+			state_mutability: StateMutability::Payable,
+		};
+
+		let tokens = [Token::Uint(69.into()), Token::Bool(true)];
+		let mut buffer = Vec::new();
+		func.encode_input_into(&mut buffer, &tokens).unwrap();
+		func.encode_input_into(&mut buffer, &tokens).unwrap();
+		assert!(func.encode_input_into(&mut buffer, &tokens[..1]).is_err());
+
+		let expected = hex!("cdcd77c000000000000000000000000000000000000000000000000000000000000000450000000000000000000000000000000000000000000000000000000000000001");
+		assert_eq!(buffer, [expected, expected].concat());
+	}
+
+	#[test]
+	fn test_function_typed_input_output() {
+		#[allow(deprecated)]
+		let func = Function {
+			name: "baz".to_owned(),
+			inputs: vec![
+				Param { name: "a".to_owned(), kind: ParamType::Uint(32) },
+				Param { name: "b".to_owned(), kind: ParamType::Bool },
+			],
+			outputs: vec![
+				Param { name: "c".to_owned(), kind: ParamType::Uint(32) },
+				Param { name: "d".to_owned(), kind: ParamType::Bool },
+			],
+			constant: false,
+			state_mutability: StateMutability::Payable,
+		};
+
+		let encoded = func.encode_typed_input((69u32, true)).unwrap();
+		let expected = hex!("cdcd77c000000000000000000000000000000000000000000000000000000000000000450000000000000000000000000000000000000000000000000000000000000001");
+		assert_eq!(encoded, expected.to_vec());
+		assert!(func.encode_typed_input((69u32,)).is_err());
+
+		let (c, d): (u32, bool) = func.decode_typed_output(&encoded[4..]).unwrap();
+		assert_eq!((c, d), (69, true));
+		assert!(func.decode_typed_output::<(bool, bool)>(&encoded[4..]).is_err());
+	}
+
+	#[test]
+	fn test_function_decode_calldata() {
+		#[allow(deprecated)]
+		let func = Function {
+			name: "baz".to_owned(),
+			inputs: vec![
+				Param { name: "a".to_owned(), kind: ParamType::Uint(32) },
+				Param { name: "b".to_owned(), kind: ParamType::Bool },
+			],
+			outputs: vec![],
+			constant: false,
+			state_mutability: StateMutability::Payable,
+		};
+
+		let tokens = vec![Token::Uint(69.into()), Token::Bool(true)];
+		let calldata = func.encode_input(&tokens).unwrap();
+		assert_eq!(func.selector(), hex!("cdcd77c0"));
+		assert_eq!(func.decode_calldata(&calldata).unwrap(), tokens);
+		assert!(func.decode_calldata(&calldata[..3]).is_err());
+
+		let mut wrong_selector = calldata;
+		wrong_selector[0] = 0;
+		assert!(func.decode_calldata(&wrong_selector).is_err());
+
+		let mut renamed = func.clone();
+		renamed.name = "transfer".to_owned();
+		renamed.inputs = vec![
+			Param { name: "to".to_owned(), kind: ParamType::Address },
+			Param { name: "amount".to_owned(), kind: ParamType::Uint(256) },
+		];
+		assert_eq!(renamed.selector(), hex!("a9059cbb"));
+		assert_eq!(renamed.canonical_signature(), "transfer(address,uint256)");
+		assert_eq!(func.selector(), hex!("cdcd77c0"));
+	}
+
+	#[test]
+	fn test_state_mutability() {
+		let legacy: Function = serde_json::from_str(
+			r#"{ "type": "function", "name": "f", "inputs": [], "outputs": [], "constant": true }"#,
+		)
+		.unwrap();
+		assert_eq!(legacy.state_mutability(), StateMutability::View);
+
+		let pure: Function = serde_json::from_str(
+			r#"{ "type": "function", "name": "f", "inputs": [], "outputs": [], "stateMutability": "pure" }"#,
+		)
+		.unwrap();
+		assert_eq!(pure.state_mutability(), StateMutability::Pure);
+		assert!(pure.state_mutability().is_read_only());
+	}
+
+	#[test]
+	fn test_describe_encoding() {
+		#[allow(deprecated)]
+		let func = Function {
+			name: "transfer".to_owned(),
+			inputs: vec![
+				Param { name: "to".to_owned(), kind: ParamType::Address },
+				Param { name: "data".to_owned(), kind: ParamType::Bytes },
+				Param { name: "".to_owned(), kind: ParamType::Tuple(vec![ParamType::Uint(256), ParamType::Bool]) },
+				Param { name: "ids".to_owned(), kind: ParamType::FixedArray(Box::new(ParamType::FixedBytes(4)), 2) },
+			],
+			outputs: vec![],
+			constant: false,
+			state_mutability: StateMutability::NonPayable,
+		};
+
+		let expected = "\
+transfer(address,bytes,(uint256,bool),bytes4[2]) selector 0x94560bfb
+offset  slots  param  type            layout           alignment
+0x04    1      to     address         inline           right
+0x24    1      data   bytes           tail offset      left
+0x44    2      #2     (uint256,bool)  inline, 2 words  per element
+0x84    2      ids    bytes4[2]       inline, 2 words  per element
+head: 192 bytes, tail: data";
+		assert_eq!(func.describe_encoding(), expected);
+	}
 }
@@ -0,0 +1,326 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parser and formatter of human-readable signatures, e.g.
+//! `function transfer(address to, uint256 amount) returns (bool)`.
+
+use crate::{
+	operation::Operation,
+	param_type::Writer,
+	signature::{parse_params, split_call, RawParam},
+	AbiError, Constructor, Error, Event, EventParam, Function, Param, ParamType, Result, StateMutability,
+};
+
+/// Parses a function signature, with or without the `function` keyword.
+pub(crate) fn parse_function(signature: &str) -> Result<Function> {
+	match parse_operation(&with_keyword("function", signature)) {
+		Ok(Operation::Function(function)) => Ok(function),
+		_ => Err(Error::InvalidName(signature.to_owned())),
+	}
+}
+
+/// Parses an event signature, with or without the `event` keyword.
+pub(crate) fn parse_event(signature: &str) -> Result<Event> {
+	match parse_operation(&with_keyword("event", signature)) {
+		Ok(Operation::Event(event)) => Ok(event),
+		_ => Err(Error::InvalidName(signature.to_owned())),
+	}
+}
+
+fn with_keyword(keyword: &str, signature: &str) -> String {
+	let signature = signature.trim();
+	match signature.split(|c: char| c.is_whitespace() || c == '(').next() == Some(keyword) {
+		true => signature.to_owned(),
+		false => format!("{} {}", keyword, signature),
+	}
+}
+
+/// Parses a single signature starting with `function`, `event`, `error`, `constructor`, `fallback` or `receive`.
+pub(crate) fn parse_operation(signature: &str) -> Result<Operation> {
+	let invalid = || Error::InvalidName(signature.to_owned());
+	let signature = signature.trim().trim_end_matches(';').trim_end();
+	let (keyword, rest) = signature.split_once(char::is_whitespace).unwrap_or((signature, ""));
+	let (keyword, rest) = match keyword.find('(') {
+		// e.g. `constructor(uint256 a)`
+		Some(index) => (&keyword[..index], &signature[index..]),
+		None => (keyword, rest.trim_start()),
+	};
+
+	match keyword {
+		"function" => {
+			let (name, params, modifiers) = split_call(rest).ok_or_else(invalid)?;
+			let (modifiers, outputs) = match modifiers.find("returns") {
+				Some(index) => {
+					let (_, outputs, tail) = split_call(modifiers[index + "returns".len()..].trim_start())
+						.filter(|(name, _, tail)| name.is_empty() && tail.is_empty())
+						.ok_or_else(invalid)?;
+					debug_assert!(tail.is_empty());
+					(&modifiers[..index], parse_params(outputs)?)
+				}
+				None => (modifiers, vec![]),
+			};
+
+			#[allow(deprecated)]
+			let function = Function {
+				name: name.to_owned(),
+				inputs: parse_params(params)?.into_iter().map(RawParam::into_param).collect(),
+				outputs: outputs.into_iter().map(RawParam::into_param).collect(),
+				constant: false,
+				state_mutability: state_mutability(modifiers),
+			};
+			Ok(Operation::Function(function))
+		}
+		"event" => {
+			let (name, params, modifiers) = split_call(rest).ok_or_else(invalid)?;
+			let inputs = parse_params(params)?
+				.into_iter()
+				.map(|param| EventParam { name: param.name, kind: param.kind, indexed: param.indexed })
+				.collect();
+			Ok(Operation::Event(Event {
+				name: name.to_owned(),
+				inputs,
+				anonymous: modifiers.split_whitespace().any(|word| word == "anonymous"),
+			}))
+		}
+		"error" => {
+			let (name, params, _) = split_call(rest).ok_or_else(invalid)?;
+			let inputs = parse_params(params)?.into_iter().map(RawParam::into_param).collect();
+			Ok(Operation::Error(AbiError { name: name.to_owned(), inputs }))
+		}
+		"constructor" => {
+			let (_, params, _) = split_call(rest).filter(|(name, _, _)| name.is_empty()).ok_or_else(invalid)?;
+			let inputs = parse_params(params)?.into_iter().map(RawParam::into_param).collect();
+			Ok(Operation::Constructor(Constructor { inputs }))
+		}
+		"fallback" => Ok(Operation::Fallback { state_mutability: state_mutability(rest) }),
+		"receive" => Ok(Operation::Receive),
+		_ => Err(invalid()),
+	}
+}
+
+fn state_mutability(modifiers: &str) -> StateMutability {
+	modifiers
+		.split_whitespace()
+		.find_map(|word| match word {
+			"pure" => Some(StateMutability::Pure),
+			"view" | "constant" => Some(StateMutability::View),
+			"payable" => Some(StateMutability::Payable),
+			_ => None,
+		})
+		.unwrap_or(StateMutability::NonPayable)
+}
+
+impl RawParam {
+	fn into_param(self) -> Param {
+		Param { name: self.name, kind: self.kind }
+	}
+}
+
+/// Formats a function as a signature `parse_operation` parses, e.g.
+/// `function balanceOf(address owner) view returns (uint256)`.
+pub(crate) fn format_function(function: &Function) -> String {
+	let mut result = format!("function {}({})", function.name, format_params(&function.inputs));
+	match function.state_mutability() {
+		StateMutability::Pure => result.push_str(" pure"),
+		StateMutability::View => result.push_str(" view"),
+		StateMutability::Payable => result.push_str(" payable"),
+		StateMutability::NonPayable => (),
+	}
+	if !function.outputs.is_empty() {
+		result.push_str(&format!(" returns ({})", format_params(&function.outputs)));
+	}
+	result
+}
+
+/// Formats an event as a signature `parse_operation` parses, e.g.
+/// `event Transfer(address indexed from, address indexed to, uint256 value)`.
+pub(crate) fn format_event(event: &Event) -> String {
+	let params = event
+		.inputs
+		.iter()
+		.map(|param| format_param(&param.kind, param.indexed, &param.name))
+		.collect::<Vec<_>>()
+		.join(", ");
+	match event.anonymous {
+		true => format!("event {}({}) anonymous", event.name, params),
+		false => format!("event {}({})", event.name, params),
+	}
+}
+
+/// Formats an error as a signature `parse_operation` parses, e.g. `error Unauthorized(address caller)`.
+pub(crate) fn format_error(error: &AbiError) -> String {
+	format!("error {}({})", error.name, format_params(&error.inputs))
+}
+
+/// Formats a constructor as a signature `parse_operation` parses, e.g. `constructor(string name)`.
+pub(crate) fn format_constructor(constructor: &Constructor) -> String {
+	format!("constructor({})", format_params(&constructor.inputs))
+}
+
+fn format_params(params: &[Param]) -> String {
+	params.iter().map(|param| format_param(&param.kind, false, &param.name)).collect::<Vec<_>>().join(", ")
+}
+
+fn format_param(kind: &ParamType, indexed: bool, name: &str) -> String {
+	let mut result = Writer::write(kind);
+	if indexed {
+		result.push_str(" indexed");
+	}
+	if !name.is_empty() {
+		result.push(' ');
+		result.push_str(name);
+	}
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{format_event, format_function, parse_event, parse_function, parse_operation};
+	use crate::{
+		operation::Operation, AbiError, Constructor, Event, EventParam, Function, Param, ParamType, StateMutability,
+	};
+
+	#[test]
+	fn test_parse_function() {
+		#[allow(deprecated)]
+		let expected = Function {
+			name: "transfer".to_owned(),
+			inputs: vec![
+				Param { name: "to".to_owned(), kind: ParamType::Address },
+				Param { name: "amount".to_owned(), kind: ParamType::Uint(256) },
+			],
+			outputs: vec![Param { name: "".to_owned(), kind: ParamType::Bool }],
+			constant: false,
+			state_mutability: StateMutability::NonPayable,
+		};
+
+		assert_eq!(
+			parse_operation("function transfer(address to, uint256 amount) external returns (bool);").unwrap(),
+			Operation::Function(expected)
+		);
+	}
+
+	#[test]
+	fn test_parse_function_modifiers_and_tuples() {
+		let function = match parse_operation(
+			"function f(tuple(uint256 a, (address, bytes) b)[] memory items, address payable to) view returns (uint)",
+		)
+		.unwrap()
+		{
+			Operation::Function(function) => function,
+			_ => panic!("expected a function"),
+		};
+
+		assert_eq!(function.state_mutability, StateMutability::View);
+		assert_eq!(
+			function.inputs[0].kind,
+			ParamType::Array(Box::new(ParamType::Tuple(vec![
+				ParamType::Uint(256),
+				ParamType::Tuple(vec![ParamType::Address, ParamType::Bytes])
+			])))
+		);
+		assert_eq!(function.inputs[1], Param { name: "to".to_owned(), kind: ParamType::Address });
+		assert_eq!(function.outputs[0].kind, ParamType::Uint(256));
+	}
+
+	#[test]
+	fn test_parse_event() {
+		assert_eq!(
+			parse_operation("event Transfer(address indexed from, address indexed, uint256 value)").unwrap(),
+			Operation::Event(Event {
+				name: "Transfer".to_owned(),
+				inputs: vec![
+					EventParam { name: "from".to_owned(), kind: ParamType::Address, indexed: true },
+					EventParam { name: "".to_owned(), kind: ParamType::Address, indexed: true },
+					EventParam { name: "value".to_owned(), kind: ParamType::Uint(256), indexed: false },
+				],
+				anonymous: false
+			})
+		);
+
+		match parse_operation("event Foo() anonymous").unwrap() {
+			Operation::Event(event) => assert!(event.anonymous),
+			_ => panic!("expected an event"),
+		}
+	}
+
+	#[test]
+	fn test_parse_other() {
+		assert_eq!(
+			parse_operation("constructor(string name)").unwrap(),
+			Operation::Constructor(Constructor {
+				inputs: vec![Param { name: "name".to_owned(), kind: ParamType::String }]
+			})
+		);
+		assert_eq!(
+			parse_operation("fallback() external").unwrap(),
+			Operation::Fallback { state_mutability: StateMutability::NonPayable }
+		);
+		assert_eq!(
+			parse_operation("fallback() external payable").unwrap(),
+			Operation::Fallback { state_mutability: StateMutability::Payable }
+		);
+		assert_eq!(parse_operation("receive() external payable").unwrap(), Operation::Receive);
+		assert_eq!(
+			parse_operation("error Unauthorized(address caller)").unwrap(),
+			Operation::Error(AbiError {
+				name: "Unauthorized".to_owned(),
+				inputs: vec![Param { name: "caller".to_owned(), kind: ParamType::Address }]
+			})
+		);
+	}
+
+	#[test]
+	fn test_parse_function_and_event() {
+		let function = parse_function("transfer(address to, uint256 amount) returns (bool)").unwrap();
+		assert_eq!(parse_function("function transfer(address to, uint256 amount) returns (bool)").unwrap(), function);
+		assert_eq!(function.signature(), "transfer(address,uint256):(bool)");
+
+		let event = parse_event("Transfer(address indexed from, address indexed to, uint256 value)").unwrap();
+		assert_eq!(
+			parse_event("event Transfer(address indexed from, address indexed to, uint256 value)").unwrap(),
+			event
+		);
+		assert!(event.inputs[0].indexed && !event.inputs[2].indexed);
+
+		assert!(parse_function("event Transfer(address)").is_err());
+		assert!(parse_event("function transfer(address)").is_err());
+		assert!(parse_function("functionName(uint256)").is_ok());
+	}
+
+	#[test]
+	fn test_parse_invalid() {
+		assert!(parse_operation("function foo(").is_err());
+		assert!(parse_operation("function foo(uint256x)").is_err());
+		assert!(parse_operation("function foo(uint256 a b)").is_err());
+		assert!(parse_operation("function foo() returns (bool").is_err());
+		assert!(parse_operation("modifier foo()").is_err());
+		assert!(parse_operation("function foo((uint256)x)").is_err());
+		assert!(parse_operation("function foo bar()").is_err());
+	}
+
+	#[test]
+	fn test_format() {
+		let signature =
+			"function swap((address token, uint256 amount)[] legs, bytes data) payable returns (uint256 out)";
+		let function = parse_function(signature).unwrap();
+		assert_eq!(
+			format_function(&function),
+			"function swap((address,uint256)[] legs, bytes data) payable returns (uint256 out)"
+		);
+		assert_eq!(parse_function(&format_function(&function)).unwrap(), function);
+
+		let event =
+			parse_event("event Transfer(address indexed, address indexed to, uint256 value) anonymous").unwrap();
+		assert_eq!(
+			format_event(&event),
+			"event Transfer(address indexed, address indexed to, uint256 value) anonymous"
+		);
+		assert_eq!(parse_event(&format_event(&event)).unwrap(), event);
+	}
+}
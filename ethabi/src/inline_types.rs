@@ -0,0 +1,113 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Encoding and decoding with types given as strings, e.g. `address,uint256[]`.
+
+use serde_json::Value;
+
+use crate::{
+	decode, encode,
+	param_type::Reader,
+	token::{LenientTokenizer, Tokenizer},
+	Bytes, Error, ParamType, Result, Token,
+};
+
+/// Decodes ABI encoded data with the comma separated types, e.g. `(address,uint256,bytes)[]`.
+pub fn decode_str(types: &str, data: &[u8]) -> Result<Vec<Token>> {
+	decode(&read_types(types)?, data)
+}
+
+/// ABI encodes the values of a JSON array with the comma separated types, e.g.
+/// `encode_str("address,uint256[]", r#"["1111111111111111111111111111111111111111", [1, "2"]]"#)`.
+///
+/// Tuples are given as arrays. Other values may be given as strings, in the format
+/// of `LenientTokenizer`, and numbers and booleans also as JSON numbers and booleans.
+pub fn encode_str(types: &str, values: &str) -> Result<Bytes> {
+	let types = read_types(types)?;
+	let values: Vec<Value> = serde_json::from_str(values)?;
+	if types.len() != values.len() {
+		return Err(Error::InvalidData);
+	}
+
+	let tokens =
+		types.iter().zip(values.iter()).map(|(kind, value)| json_to_token(kind, value)).collect::<Result<Vec<_>>>()?;
+	Ok(encode(&tokens))
+}
+
+fn read_types(types: &str) -> Result<Vec<ParamType>> {
+	let types: String = types.chars().filter(|c| !c.is_whitespace()).collect();
+	match Reader::read(&format!("({})", types))? {
+		ParamType::Tuple(types) => Ok(types),
+		_ => Err(Error::InvalidName(types)),
+	}
+}
+
+/// Converts JSON into a token of the given type, as in `encode_str` and `encode_from_json`.
+pub fn json_to_token(kind: &ParamType, value: &Value) -> Result<Token> {
+	match (kind, value) {
+		(ParamType::Array(kind), Value::Array(values)) => {
+			values.iter().map(|value| json_to_token(kind, value)).collect::<Result<_>>().map(Token::Array)
+		}
+		(ParamType::FixedArray(kind, len), Value::Array(values)) if values.len() == *len => {
+			values.iter().map(|value| json_to_token(kind, value)).collect::<Result<_>>().map(Token::FixedArray)
+		}
+		(ParamType::Tuple(kinds), Value::Array(values)) if values.len() == kinds.len() => kinds
+			.iter()
+			.zip(values)
+			.map(|(kind, value)| json_to_token(kind, value))
+			.collect::<Result<_>>()
+			.map(Token::Tuple),
+		(ParamType::Array(_), _) | (ParamType::FixedArray(..), _) | (ParamType::Tuple(_), _) => Err(Error::InvalidData),
+		(ParamType::Bool, Value::Bool(b)) => Ok(Token::Bool(*b)),
+		(ParamType::String, Value::String(s)) => Ok(Token::String(s.clone())),
+		(_, Value::Number(number)) => LenientTokenizer::tokenize(kind, &number.to_string()),
+		(_, Value::String(s)) => LenientTokenizer::tokenize(kind, s),
+		_ => Err(Error::InvalidData),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{decode_str, encode_str};
+	use crate::{encode, Address, Token};
+
+	#[test]
+	fn test_decode_str() {
+		let tokens = vec![
+			Token::Array(vec![Token::Tuple(vec![
+				Token::Address(Address::repeat_byte(0x11)),
+				Token::Uint(5.into()),
+				Token::Bytes(vec![1, 2]),
+			])]),
+			Token::Bool(true),
+		];
+
+		assert_eq!(decode_str("(address,uint256,bytes)[], bool", &encode(&tokens)).unwrap(), tokens);
+		assert!(decode_str("(address,uint256)[],boolean", &encode(&tokens)).is_err());
+	}
+
+	#[test]
+	fn test_encode_str() {
+		let encoded = encode_str(
+			"address,uint256[],(bool,string)",
+			r#"["1111111111111111111111111111111111111111", [1, "2", "3"], [true, "hello"]]"#,
+		)
+		.unwrap();
+
+		let expected = encode(&[
+			Token::Address(Address::repeat_byte(0x11)),
+			Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into()), Token::Uint(3.into())]),
+			Token::Tuple(vec![Token::Bool(true), Token::String("hello".into())]),
+		]);
+		assert_eq!(encoded, expected);
+		assert_eq!(encode_str("", "[]").unwrap(), Vec::<u8>::new());
+		assert!(encode_str("uint256", "[1, 2]").is_err());
+		assert!(encode_str("uint256[2]", "[[1]]").is_err());
+		assert!(encode_str("bool", "[[true]]").is_err());
+	}
+}
@@ -0,0 +1,263 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Signed 256-bit integer.
+
+use core::{cmp::Ordering, convert::TryFrom, fmt, str::FromStr};
+
+use anyhow::anyhow;
+use ethereum_types::U256;
+
+use crate::{Error, Int, Token, Uint};
+
+/// Signed 256-bit integer.
+///
+/// Stored in two's complement, the same representation `Token::Int` uses, so
+/// converting between the two never changes the underlying bits.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct I256(Uint);
+
+impl I256 {
+	/// The largest value, `2^255 - 1`.
+	pub const MAX: I256 = I256(U256([u64::MAX, u64::MAX, u64::MAX, i64::MAX as u64]));
+
+	/// The smallest value, `-2^255`.
+	pub const MIN: I256 = I256(U256([0, 0, 0, 1 << 63]));
+
+	/// Reinterprets two's complement bits as a signed integer.
+	pub fn from_raw(raw: Uint) -> Self {
+		I256(raw)
+	}
+
+	/// Returns the two's complement bits of the integer.
+	pub fn into_raw(self) -> Uint {
+		self.0
+	}
+
+	/// Sign extends the lowest `bits` bits of `raw`, as used by `intN` types narrower than 256 bits.
+	pub fn from_raw_bits(raw: Uint, bits: usize) -> Self {
+		if bits == 0 || bits >= 256 {
+			return I256(raw);
+		}
+
+		let mask = (Uint::one() << bits) - 1;
+		match raw.bit(bits - 1) {
+			true => I256(raw | !mask),
+			false => I256(raw & mask),
+		}
+	}
+
+	/// Returns true if the integer is negative.
+	pub fn is_negative(&self) -> bool {
+		self.0.bit(255)
+	}
+
+	/// Returns true if the integer is zero.
+	pub fn is_zero(&self) -> bool {
+		self.0.is_zero()
+	}
+
+	/// Returns the absolute value as an unsigned integer, which cannot overflow.
+	pub fn unsigned_abs(&self) -> Uint {
+		match self.is_negative() {
+			true => (!self.0).overflowing_add(Uint::one()).0,
+			false => self.0,
+		}
+	}
+
+	/// Returns the negated value, or `None` for `I256::MIN`.
+	pub fn checked_neg(self) -> Option<Self> {
+		match self == I256::MIN {
+			true => None,
+			false => Some(I256((!self.0).overflowing_add(Uint::one()).0)),
+		}
+	}
+
+	/// Parses a decimal string with an optional leading `-`.
+	pub fn from_dec_str(value: &str) -> Result<Self, Error> {
+		let (negative, abs) = match value.strip_prefix('-') {
+			Some(abs) => (true, abs),
+			None => (false, value),
+		};
+		if abs.is_empty() {
+			return Err(anyhow!("int256 parse error: Empty").into());
+		}
+
+		let abs = Uint::from_dec_str(abs)?;
+		match negative {
+			true if abs > I256::MIN.0 => Err(anyhow!("int256 parse error: Underflow").into()),
+			true => Ok(I256((!abs).overflowing_add(Uint::one()).0)),
+			false if abs > I256::MAX.0 => Err(anyhow!("int256 parse error: Overflow").into()),
+			false => Ok(I256(abs)),
+		}
+	}
+}
+
+impl Ord for I256 {
+	fn cmp(&self, other: &Self) -> Ordering {
+		match (self.is_negative(), other.is_negative()) {
+			(true, false) => Ordering::Less,
+			(false, true) => Ordering::Greater,
+			_ => self.0.cmp(&other.0),
+		}
+	}
+}
+
+impl PartialOrd for I256 {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl fmt::Display for I256 {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.is_negative() {
+			write!(f, "-")?;
+		}
+		write!(f, "{}", self.unsigned_abs())
+	}
+}
+
+impl FromStr for I256 {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		I256::from_dec_str(s)
+	}
+}
+
+impl From<Int> for I256 {
+	fn from(raw: Int) -> Self {
+		I256(raw)
+	}
+}
+
+impl From<I256> for Int {
+	fn from(value: I256) -> Self {
+		value.0
+	}
+}
+
+impl From<I256> for Token {
+	fn from(value: I256) -> Self {
+		Token::Int(value.0)
+	}
+}
+
+impl From<i128> for I256 {
+	fn from(value: i128) -> Self {
+		let abs = Uint::from(value.unsigned_abs());
+		match value < 0 {
+			true => I256((!abs).overflowing_add(Uint::one()).0),
+			false => I256(abs),
+		}
+	}
+}
+
+impl TryFrom<I256> for i128 {
+	type Error = Error;
+
+	fn try_from(value: I256) -> Result<Self, Self::Error> {
+		let abs = value.unsigned_abs();
+		if abs.bits() > 128 {
+			return Err(Error::InvalidData);
+		}
+
+		let abs = abs.low_u128();
+		match value.is_negative() {
+			true if abs <= i128::MIN.unsigned_abs() => Ok((abs as i128).wrapping_neg()),
+			false if abs <= i128::MAX as u128 => Ok(abs as i128),
+			_ => Err(Error::InvalidData),
+		}
+	}
+}
+
+macro_rules! impl_small_int_conversions {
+	($($ty:ty),*) => {
+		$(
+			impl From<$ty> for I256 {
+				fn from(value: $ty) -> Self {
+					I256::from(value as i128)
+				}
+			}
+
+			impl TryFrom<I256> for $ty {
+				type Error = Error;
+
+				fn try_from(value: I256) -> Result<Self, Self::Error> {
+					<$ty>::try_from(i128::try_from(value)?).map_err(|_| Error::InvalidData)
+				}
+			}
+		)*
+	};
+}
+
+impl_small_int_conversions!(i8, i16, i32, i64, isize);
+
+#[cfg(test)]
+mod tests {
+	use std::convert::TryFrom;
+
+	use super::I256;
+	use crate::{Token, Uint};
+
+	#[test]
+	fn test_i256_from_primitives() {
+		assert_eq!(I256::from(-1i8).into_raw(), Uint::MAX);
+		assert_eq!(I256::from(-2i64).into_raw(), Uint::MAX - 1);
+		assert_eq!(I256::from(5i32).into_raw(), Uint::from(5));
+		assert_eq!(i128::try_from(I256::from(i128::MIN)).unwrap(), i128::MIN);
+		assert_eq!(i128::try_from(I256::from(i128::MAX)).unwrap(), i128::MAX);
+		assert_eq!(i8::try_from(I256::from(-128i32)).unwrap(), -128);
+		assert!(i8::try_from(I256::from(128i32)).is_err());
+		assert!(i128::try_from(I256::MIN).is_err());
+		assert!(i64::try_from(I256::MAX).is_err());
+	}
+
+	#[test]
+	fn test_i256_from_raw_bits() {
+		assert_eq!(I256::from_raw_bits(Uint::from(0xff), 8), I256::from(-1));
+		assert_eq!(I256::from_raw_bits(Uint::from(0x7f), 8), I256::from(127));
+		assert_eq!(I256::from_raw_bits(Uint::from(0x180), 8), I256::from(-128));
+		assert_eq!(I256::from_raw_bits(Uint::MAX, 256), I256::from(-1));
+	}
+
+	#[test]
+	fn test_i256_dec_str() {
+		assert_eq!("-123".parse::<I256>().unwrap(), I256::from(-123));
+		assert_eq!("-0".parse::<I256>().unwrap(), I256::default());
+		assert_eq!(I256::MIN.to_string().parse::<I256>().unwrap(), I256::MIN);
+		assert_eq!(I256::MAX.to_string().parse::<I256>().unwrap(), I256::MAX);
+		assert_eq!(
+			I256::MIN.to_string(),
+			"-57896044618658097711785492504343953926634992332820282019728792003956564819968"
+		);
+		assert!(format!("-{}", I256::MIN.unsigned_abs() + 1).parse::<I256>().is_err());
+		assert!(format!("{}", I256::MIN.unsigned_abs()).parse::<I256>().is_err());
+		assert!("-".parse::<I256>().is_err());
+		assert!("".parse::<I256>().is_err());
+	}
+
+	#[test]
+	fn test_i256_ordering_and_negation() {
+		assert!(I256::MIN < I256::from(-1));
+		assert!(I256::from(-1) < I256::default());
+		assert!(I256::default() < I256::MAX);
+		assert_eq!(I256::from(7).checked_neg(), Some(I256::from(-7)));
+		assert_eq!(I256::MIN.checked_neg(), None);
+		assert_eq!(I256::MIN.unsigned_abs(), Uint::one() << 255);
+	}
+
+	#[test]
+	fn test_i256_token() {
+		let token: Token = I256::from(-1).into();
+		assert_eq!(token, Token::Int(Uint::MAX));
+		assert_eq!(token.into_i256(), Some(I256::from(-1)));
+		assert_eq!(Token::Bool(true).into_i256(), None);
+	}
+}
@@ -0,0 +1,126 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Decoding into and encoding from JSON, e.g. for web frontends, databases and config-driven tools.
+
+use serde_json::Value;
+
+use crate::{decode, encode, json_to_token, Bytes, Error, ParamType, Result, Token, I256};
+
+/// Decodes ABI compliant bytes into a JSON array of the values of the given types.
+///
+/// Addresses and bytes are `0x` prefixed hex strings and integers are decimal strings,
+/// as they may not fit into JSON numbers. Arrays and tuples are JSON arrays.
+pub fn decode_to_json(types: &[ParamType], data: &[u8]) -> Result<Value> {
+	Ok(Value::Array(decode(types, data)?.into_iter().map(token_to_json).collect()))
+}
+
+/// Converts a token into JSON, as in `decode_to_json`.
+pub fn token_to_json(token: Token) -> Value {
+	match token {
+		Token::Address(address) => Value::String(format!("{:?}", address)),
+		Token::Bytes(bytes) | Token::FixedBytes(bytes) => Value::String(format!("0x{}", hex::encode(bytes))),
+		Token::Int(int) => Value::String(I256::from_raw(int).to_string()),
+		Token::Uint(uint) => Value::String(uint.to_string()),
+		Token::Bool(b) => Value::Bool(b),
+		Token::String(s) => Value::String(s),
+		Token::Array(tokens) | Token::FixedArray(tokens) | Token::Tuple(tokens) => {
+			Value::Array(tokens.into_iter().map(token_to_json).collect())
+		}
+	}
+}
+
+/// Encodes a JSON array of values of the given types, the inverse of `decode_to_json`.
+///
+/// Integers may also be JSON numbers and the `0x` prefix of hex strings is optional.
+pub fn encode_from_json(types: &[ParamType], value: &Value) -> Result<Bytes> {
+	match value {
+		Value::Array(values) if values.len() == types.len() => {
+			let tokens =
+				types.iter().zip(values).map(|(kind, value)| json_to_token(kind, value)).collect::<Result<Vec<_>>>()?;
+			Ok(encode(&tokens))
+		}
+		_ => Err(Error::InvalidData),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use super::{decode_to_json, encode_from_json};
+	use crate::{encode, ParamType, Token, I256};
+
+	#[test]
+	fn test_decode_to_json() {
+		let tokens = [
+			Token::Address([0x11u8; 20].into()),
+			Token::Uint(1_000_000.into()),
+			Token::Int(I256::from_dec_str("-2").unwrap().into_raw()),
+			Token::Bytes(vec![0xde, 0xad]),
+			Token::Tuple(vec![Token::Bool(true), Token::String("gavofyork".to_owned())]),
+			Token::Array(vec![Token::FixedBytes(vec![0xbe, 0xef])]),
+		];
+		let types = [
+			ParamType::Address,
+			ParamType::Uint(256),
+			ParamType::Int(256),
+			ParamType::Bytes,
+			ParamType::Tuple(vec![ParamType::Bool, ParamType::String]),
+			ParamType::Array(Box::new(ParamType::FixedBytes(2))),
+		];
+
+		let expected = json!([
+			"0x1111111111111111111111111111111111111111",
+			"1000000",
+			"-2",
+			"0xdead",
+			[true, "gavofyork"],
+			["0xbeef"],
+		]);
+		assert_eq!(decode_to_json(&types, &encode(&tokens)).unwrap(), expected);
+		assert!(decode_to_json(&types, &[]).is_err());
+	}
+
+	#[test]
+	fn test_encode_from_json() {
+		let types = [
+			ParamType::Address,
+			ParamType::Uint(256),
+			ParamType::Int(256),
+			ParamType::Bytes,
+			ParamType::FixedArray(Box::new(ParamType::Bool), 2),
+			ParamType::Tuple(vec![ParamType::String, ParamType::FixedBytes(2)]),
+		];
+		let value = json!([
+			"0x1111111111111111111111111111111111111111",
+			1000000,
+			"-2",
+			"dead",
+			[true, false],
+			["gavofyork", "0xbeef"],
+		]);
+
+		let encoded = encode_from_json(&types, &value).unwrap();
+		let expected = encode(&[
+			Token::Address([0x11u8; 20].into()),
+			Token::Uint(1_000_000.into()),
+			Token::Int(I256::from_dec_str("-2").unwrap().into_raw()),
+			Token::Bytes(vec![0xde, 0xad]),
+			Token::FixedArray(vec![Token::Bool(true), Token::Bool(false)]),
+			Token::Tuple(vec![Token::String("gavofyork".to_owned()), Token::FixedBytes(vec![0xbe, 0xef])]),
+		]);
+		assert_eq!(encoded, expected);
+		assert_eq!(encode_from_json(&types, &decode_to_json(&types, &encoded).unwrap()).unwrap(), encoded);
+
+		assert!(encode_from_json(&types[..1], &json!("0x1111111111111111111111111111111111111111")).is_err());
+		assert!(encode_from_json(&types[1..2], &json!([1.5])).is_err());
+		assert!(encode_from_json(&types[4..5], &json!([[true]])).is_err());
+		assert!(encode_from_json(&types[..2], &json!(["0x1111111111111111111111111111111111111111"])).is_err());
+	}
+}
@@ -8,27 +8,77 @@
 
 //! Ethereum ABI encoding decoding library.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![allow(clippy::module_inception)]
 #![warn(missing_docs)]
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod abi_error;
+#[cfg(feature = "audit")]
+mod audit;
+mod bool_output;
+#[cfg(feature = "std")]
+mod calldata;
+mod checksum;
+#[cfg(feature = "std")]
 mod constructor;
+#[cfg(feature = "std")]
 mod contract;
+#[cfg(feature = "std")]
+mod custom_type;
 mod decoder;
+#[cfg(feature = "std")]
+mod encoded_call;
 mod encoder;
 mod errors;
+#[cfg(feature = "std")]
 mod event;
+#[cfg(feature = "std")]
 mod event_param;
+mod explain;
+#[cfg(feature = "std")]
 mod filter;
+#[cfg(feature = "std")]
 mod function;
+#[cfg(feature = "std")]
+mod human_readable;
+#[cfg(feature = "std")]
+mod inline_types;
+mod int;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "std")]
 mod log;
+pub mod merkle;
+#[cfg(feature = "std")]
 mod operation;
+mod packed;
+#[cfg(feature = "std")]
 mod param;
 pub mod param_type;
+#[cfg(feature = "std")]
+pub mod reducer;
+#[cfg(feature = "online")]
+pub mod remote;
+mod revert;
 mod signature;
+#[cfg(feature = "std")]
 mod state_mutability;
+#[cfg(feature = "async")]
+mod stream;
 pub mod token;
+#[cfg(feature = "std")]
+pub mod transform;
+#[cfg(feature = "std")]
 mod tuple_param;
+#[cfg(feature = "std")]
+mod typed_function;
 mod util;
+#[cfg(feature = "web3")]
+pub mod web3_interop;
+mod word;
 
 #[cfg(test)]
 mod tests;
@@ -36,29 +86,70 @@ mod tests;
 pub use ethereum_types;
 
 pub use crate::{
+	bool_output::{decode_bool_or_empty, BoolOutput},
+	checksum::checksum_address,
+	decoder::{decode, decode_single, decode_with_max_depth},
+	encoder::{encode, encode_into, encode_single, encode_with_max_depth, encoded_len},
+	errors::{Error, Result, TypeMismatch},
+	explain::{explain_encoding, ExplainedWord, WordRole},
+	int::I256,
+	packed::decode_packed,
+	param_type::ParamType,
+	revert::{decode_revert, PanicCode, Revert, ERROR_SELECTOR, PANIC_SELECTOR},
+	signature::{canonical_signature, encode_function_call, signature_hash},
+	token::{Detokenize, InputTokens, Token, Tokenizable, TokenizableItem, Tokenize, TypedTokens},
+	word::Word,
+};
+
+#[cfg(feature = "std")]
+pub use crate::{
+	abi_error::AbiError,
+	calldata::{analyze_calldata, analyze_calldata_params, CalldataProfile},
 	constructor::Constructor,
-	contract::{Contract, Events, Functions},
-	decoder::decode,
-	encoder::encode,
-	errors::{Error, Result},
+	contract::{AbiErrors, Contract, ContractBuilder, Events, Functions, MergePolicy},
+	custom_type::{CustomType, TypeRegistry},
+	encoded_call::EncodedCall,
+	encoder::encode_to_writer,
 	event::Event,
 	event_param::EventParam,
 	filter::{RawTopicFilter, Topic, TopicFilter},
 	function::Function,
-	log::{Log, LogFilter, LogParam, ParseLog, RawLog},
+	inline_types::{decode_str, encode_str, json_to_token},
+	log::{EventLog, Log, LogFilter, LogParam, ParseLog, RawLog},
 	param::Param,
-	param_type::ParamType,
 	state_mutability::StateMutability,
-	token::Token,
+	transform::Transforms,
 	tuple_param::TupleParam,
+	typed_function::TypedFunction,
 };
 
-/// ABI word.
-pub type Word = [u8; 32];
+#[cfg(feature = "async")]
+pub use crate::stream::EventStream;
+
+#[cfg(feature = "json")]
+pub use crate::json::{decode_to_json, encode_from_json, token_to_json};
+
+#[cfg(not(feature = "std"))]
+mod no_std_prelude {
+	pub use alloc::{
+		borrow::ToOwned,
+		boxed::Box,
+		format,
+		string::{String, ToString},
+		vec,
+		vec::Vec,
+	};
+}
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
 
 /// ABI address.
 pub type Address = ethereum_types::Address;
 
+/// Length of an address in bytes, addresses are left padded to 32 bytes when encoded.
+pub const ADDRESS_LEN: usize = core::mem::size_of::<Address>();
+
 /// ABI fixed bytes.
 pub type FixedBytes = Vec<u8>;
 
@@ -74,6 +165,9 @@ pub type Uint = ethereum_types::U256;
 /// Commonly used FixedBytes of size 32
 pub type Hash = ethereum_types::H256;
 
+/// Default limit on how deeply arrays and tuples may be nested.
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
 /// Contract functions generated by ethabi-derive
 pub trait FunctionOutputDecoder {
 	/// Output types of the contract function
@@ -6,7 +6,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::{Bytes, Hash, Result, Token, TopicFilter};
+use std::{ops::Index, slice, vec};
+
+use crate::{Bytes, Event, Hash, Result, Token, TopicFilter};
 
 /// Common filtering functions that are available for any event.
 pub trait LogFilter {
@@ -25,6 +27,24 @@ pub trait ParseLog {
 	fn parse_log(&self, log: RawLog) -> Result<Self::Log>;
 }
 
+/// Typed log of a known event, e.g. generated by `ethabi-derive`.
+pub trait EventLog: Sized {
+	/// Returns the event describing the log.
+	fn event() -> Event;
+
+	/// Parses the log from a `RawLog`.
+	fn parse_log(log: RawLog) -> Result<Self>;
+
+	/// Parses the logs of the event from a stream of raw logs, e.g. of a subscription.
+	#[cfg(feature = "async")]
+	fn into_stream<S>(logs: S) -> crate::EventStream<S, Self>
+	where
+		S: futures_core::Stream<Item = RawLog>,
+	{
+		crate::EventStream::new(logs)
+	}
+}
+
 /// Ethereum log.
 #[derive(Debug, PartialEq, Clone)]
 pub struct RawLog {
@@ -55,3 +75,67 @@ pub struct Log {
 	/// Log params.
 	pub params: Vec<LogParam>,
 }
+
+impl Log {
+	/// Returns the value of the param of the name, if any.
+	pub fn param(&self, name: &str) -> Option<&Token> {
+		self.params.iter().find(|param| param.name == name).map(|param| &param.value)
+	}
+}
+
+impl Index<&str> for Log {
+	type Output = Token;
+
+	/// Returns the value of the param of the name.
+	///
+	/// Panics if the log has no param of the name, see `Log::param`.
+	fn index(&self, name: &str) -> &Token {
+		self.param(name).unwrap_or_else(|| panic!("log has no param {}", name))
+	}
+}
+
+impl IntoIterator for Log {
+	type Item = LogParam;
+	type IntoIter = vec::IntoIter<LogParam>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.params.into_iter()
+	}
+}
+
+impl<'a> IntoIterator for &'a Log {
+	type Item = &'a LogParam;
+	type IntoIter = slice::Iter<'a, LogParam>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.params.iter()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Log, LogParam};
+	use crate::Token;
+
+	#[test]
+	fn test_param() {
+		let log = Log {
+			params: vec![
+				LogParam { name: "from".to_owned(), value: Token::Uint(1.into()) },
+				LogParam { name: "value".to_owned(), value: Token::Uint(2.into()) },
+			],
+		};
+		assert_eq!(log.param("value"), Some(&Token::Uint(2.into())));
+		assert_eq!(log.param("to"), None);
+		assert_eq!(log["from"], Token::Uint(1.into()));
+		assert_eq!((&log).into_iter().map(|param| param.name.as_str()).collect::<Vec<_>>(), ["from", "value"]);
+		assert_eq!(log.into_iter().map(|param| param.value).next_back(), Some(Token::Uint(2.into())));
+	}
+
+	#[test]
+	#[should_panic(expected = "log has no param to")]
+	fn test_index_missing_param() {
+		let log = Log { params: vec![] };
+		let _ = &log["to"];
+	}
+}
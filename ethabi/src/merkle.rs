@@ -0,0 +1,175 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Merkle trees over ABI encoded values.
+//!
+//! Trees are built like OpenZeppelin's `StandardMerkleTree` builds them: leaves are sorted, laid out
+//! as a complete binary tree, and pairs of nodes are hashed in sorted order. With `LeafEncoding::Standard`
+//! leaves, roots and proofs thus match those of `StandardMerkleTree.of`, and proofs verify with
+//! OpenZeppelin's `MerkleProof` library.
+
+use sha3::{Digest, Keccak256};
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{encode, Bytes, Error, Hash, Result, Token};
+
+/// How the values of a leaf are encoded before hashing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeafEncoding {
+	/// `keccak256(abi.encodePacked(values))`.
+	///
+	/// Tokens carry no bit width, so integers are packed as `uint256` and `int256`.
+	/// Tuples and arrays of dynamic types cannot be packed.
+	Packed,
+	/// `keccak256(bytes.concat(keccak256(abi.encode(values))))`, as used by
+	/// OpenZeppelin's `StandardMerkleTree`.
+	Standard,
+}
+
+/// Computes the hash of a leaf made of the given values.
+pub fn leaf_hash(tokens: &[Token], encoding: LeafEncoding) -> Result<Hash> {
+	match encoding {
+		LeafEncoding::Packed => Ok(keccak(&encode_packed(tokens)?)),
+		LeafEncoding::Standard => Ok(keccak(keccak(&encode(tokens)).as_bytes())),
+	}
+}
+
+/// Computes the root of the tree with the given leaves, or `None` if there are none.
+pub fn build_root(leaves: &[Hash]) -> Option<Hash> {
+	build_tree(leaves).first().copied()
+}
+
+/// Computes the proof for the leaf at `index`, or `None` if it is out of bounds.
+pub fn build_proof(leaves: &[Hash], index: usize) -> Option<Vec<Hash>> {
+	let leaf = leaves.get(index)?;
+	let tree = build_tree(leaves);
+	let position = leaves.iter().filter(|other| *other < leaf).count();
+
+	let mut proof = Vec::new();
+	let mut index = tree.len() - 1 - position;
+	while index > 0 {
+		let sibling = if index % 2 == 1 { index + 1 } else { index - 1 };
+		proof.push(tree[sibling]);
+		index = (index - 1) / 2;
+	}
+	Some(proof)
+}
+
+/// Checks that `proof` proves `leaf` is part of the tree with the given `root`.
+pub fn verify(proof: &[Hash], root: Hash, leaf: Hash) -> bool {
+	proof.iter().fold(leaf, |node, sibling| hash_pair(node, *sibling)) == root
+}
+
+/// Lays out the tree of the leaves as an array, the root first and the children of node `i` at `2i + 1`
+/// and `2i + 2`, the sorted leaves filling the array backwards from its end.
+fn build_tree(leaves: &[Hash]) -> Vec<Hash> {
+	let mut sorted = leaves.to_vec();
+	sorted.sort();
+
+	let mut tree = vec![Hash::zero(); (2 * sorted.len()).saturating_sub(1)];
+	let len = tree.len();
+	for (i, leaf) in sorted.into_iter().enumerate() {
+		tree[len - 1 - i] = leaf;
+	}
+	for i in (0..len / 2).rev() {
+		tree[i] = hash_pair(tree[2 * i + 1], tree[2 * i + 2]);
+	}
+	tree
+}
+
+fn hash_pair(a: Hash, b: Hash) -> Hash {
+	let (left, right) = if a <= b { (a, b) } else { (b, a) };
+	keccak(&[left.as_bytes(), right.as_bytes()].concat())
+}
+
+fn keccak(data: &[u8]) -> Hash {
+	Hash::from_slice(&Keccak256::digest(data))
+}
+
+fn encode_packed(tokens: &[Token]) -> Result<Bytes> {
+	let mut result = Vec::new();
+	for token in tokens {
+		match *token {
+			Token::Address(ref address) => result.extend_from_slice(address.as_bytes()),
+			Token::Bool(value) => result.push(value as u8),
+			Token::FixedBytes(ref bytes) | Token::Bytes(ref bytes) => result.extend_from_slice(bytes),
+			Token::String(ref s) => result.extend_from_slice(s.as_bytes()),
+			Token::Int(_) | Token::Uint(_) => result.extend(encode(core::slice::from_ref(token))),
+			Token::Array(ref tokens) | Token::FixedArray(ref tokens) => {
+				// array elements are packed padded to 32 bytes each
+				for token in tokens {
+					if token.is_dynamic() || matches!(*token, Token::Tuple(_)) {
+						return Err(Error::InvalidData);
+					}
+					result.extend(encode(core::slice::from_ref(token)));
+				}
+			}
+			Token::Tuple(_) => return Err(Error::InvalidData),
+		}
+	}
+	Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+	use hex_literal::hex;
+	use sha3::{Digest, Keccak256};
+
+	use super::{build_proof, build_root, hash_pair, leaf_hash, verify, LeafEncoding};
+	use crate::{Address, Hash, Token};
+
+	fn leaves() -> Vec<Hash> {
+		(1u64..=5)
+			.map(|i| {
+				let tokens = [Token::Address(Address::from_low_u64_be(i)), Token::Uint(i.into())];
+				leaf_hash(&tokens, LeafEncoding::Standard).unwrap()
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_leaf_hash() {
+		let tokens = [Token::Address(Address::repeat_byte(0x11)), Token::Uint(100.into())];
+
+		let packed = hex!(
+			"
+			1111111111111111111111111111111111111111
+			0000000000000000000000000000000000000000000000000000000000000064
+		"
+		);
+		assert_eq!(leaf_hash(&tokens, LeafEncoding::Packed).unwrap(), Hash::from_slice(&Keccak256::digest(&packed)));
+		assert_ne!(
+			leaf_hash(&tokens, LeafEncoding::Standard).unwrap(),
+			leaf_hash(&tokens, LeafEncoding::Packed).unwrap()
+		);
+		assert!(leaf_hash(&[Token::Tuple(vec![])], LeafEncoding::Packed).is_err());
+		assert!(leaf_hash(&[Token::Array(vec![Token::String("a".into())])], LeafEncoding::Packed).is_err());
+	}
+
+	#[test]
+	fn test_proofs() {
+		let leaves = leaves();
+		let root = build_root(&leaves).unwrap();
+
+		for (index, leaf) in leaves.iter().enumerate() {
+			let proof = build_proof(&leaves, index).unwrap();
+			assert!(verify(&proof, root, *leaf));
+			assert!(!verify(&proof, root, Hash::zero()));
+		}
+
+		let (a, b, c) = (Hash::repeat_byte(1), Hash::repeat_byte(2), Hash::repeat_byte(3));
+		assert_eq!(build_root(&[c, a, b]), Some(hash_pair(hash_pair(b, a), c)));
+		assert_eq!(build_proof(&[c, a, b], 1), Some(vec![b, c]));
+
+		assert_eq!(build_proof(&leaves, leaves.len()), None);
+		assert_eq!(build_root(&[]), None);
+		assert_eq!(build_root(&leaves[..1]), Some(leaves[0]));
+		assert!(verify(&[], leaves[0], leaves[0]));
+	}
+}
@@ -8,7 +8,7 @@
 
 //! Operation type.
 
-use crate::{Constructor, Event, Function};
+use crate::{AbiError, Constructor, Event, Function, StateMutability};
 use serde::{Deserialize, Serialize};
 
 /// Operation type.
@@ -24,9 +24,16 @@ pub enum Operation {
 	/// Contract event.
 	#[serde(rename = "event")]
 	Event(Event),
+	/// Contract custom error.
+	#[serde(rename = "error")]
+	Error(AbiError),
 	/// Fallback function.
 	#[serde(rename = "fallback")]
-	Fallback,
+	Fallback {
+		/// Whether the fallback function accepts Ether.
+		#[serde(rename = "stateMutability", default)]
+		state_mutability: StateMutability,
+	},
 	/// Receive function.
 	#[serde(rename = "receive")]
 	Receive,
@@ -121,7 +128,7 @@ mod tests {
 						indexed: false
 					},
 				],
-				anonymous: false,
+				anonymous: false
 			})
 		);
 
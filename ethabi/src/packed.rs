@@ -0,0 +1,199 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Decoder of packed encoded data, as of Solidity's `abi.encodePacked`.
+
+use anyhow::anyhow;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{decode, Address, Error, ParamType, Result, Token, Uint, ADDRESS_LEN, I256};
+
+/// Decodes packed encoded data, as of `abi.encodePacked`, into tokens of the given types.
+///
+/// Values are packed without padding, except for the elements of arrays, which are padded
+/// to 32 bytes each. As the lengths of dynamic values are not encoded, at most one param
+/// may be of type `string`, `bytes` or an array of dynamic length, and it takes the bytes
+/// left by the other params. Tuples and arrays of arrays or of dynamic types cannot be
+/// packed.
+pub fn decode_packed(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>> {
+	let lens = types.iter().map(packed_len).collect::<Result<Vec<_>>>()?;
+	let mut dynamic = types.iter().zip(lens.iter()).filter(|(_, len)| len.is_none()).map(|(kind, _)| kind);
+	if let (Some(first), Some(second)) = (dynamic.next(), dynamic.next()) {
+		return Err(Error::Other(anyhow!(
+			"Ambiguous packed data: the lengths of both {} and {} are not encoded",
+			first,
+			second
+		)));
+	}
+
+	let static_len =
+		lens.iter().flatten().try_fold(0usize, |sum, len| sum.checked_add(*len)).ok_or(Error::InvalidData)?;
+	let dynamic_len = data.len().checked_sub(static_len).ok_or(Error::InvalidData)?;
+	if dynamic_len != 0 && lens.iter().all(Option::is_some) {
+		return Err(Error::InvalidData);
+	}
+
+	let mut offset = 0;
+	types
+		.iter()
+		.zip(lens)
+		.map(|(kind, len)| {
+			let len = len.unwrap_or(dynamic_len);
+			let token = decode_packed_param(kind, &data[offset..offset + len])?;
+			offset += len;
+			Ok(token)
+		})
+		.collect()
+}
+
+/// Returns the length of a packed param, `None` if it is dynamic.
+fn packed_len(kind: &ParamType) -> Result<Option<usize>> {
+	match kind {
+		ParamType::Address => Ok(Some(ADDRESS_LEN)),
+		ParamType::Bool => Ok(Some(1)),
+		ParamType::Int(bits) | ParamType::Uint(bits) => Ok(Some(bits / 8)),
+		ParamType::Fixed(bits, _) | ParamType::Ufixed(bits, _) => Ok(Some(bits / 8)),
+		ParamType::FixedBytes(len) => Ok(Some(*len)),
+		ParamType::Function => Ok(Some(ADDRESS_LEN + 4)),
+		ParamType::String | ParamType::Bytes => Ok(None),
+		ParamType::Array(inner) if is_packed_element(inner) => Ok(None),
+		ParamType::FixedArray(inner, len) if is_packed_element(inner) => {
+			len.checked_mul(32).map(Some).ok_or(Error::InvalidData)
+		}
+		_ => Err(Error::Other(anyhow!("{} cannot be packed", kind))),
+	}
+}
+
+/// Returns whether the type may be the element of a packed array, which is padded to 32 bytes.
+fn is_packed_element(kind: &ParamType) -> bool {
+	matches!(
+		kind,
+		ParamType::Address
+			| ParamType::Bool
+			| ParamType::Int(_)
+			| ParamType::Uint(_)
+			| ParamType::Fixed(_, _)
+			| ParamType::Ufixed(_, _)
+			| ParamType::FixedBytes(_)
+			| ParamType::Function
+	)
+}
+
+fn decode_packed_param(kind: &ParamType, data: &[u8]) -> Result<Token> {
+	match kind {
+		ParamType::Address => Ok(Token::Address(Address::from_slice(data))),
+		ParamType::Bool => match data {
+			[0] => Ok(Token::Bool(false)),
+			[1] => Ok(Token::Bool(true)),
+			_ => Err(Error::InvalidData),
+		},
+		ParamType::Uint(_) | ParamType::Ufixed(_, _) => Ok(Token::Uint(Uint::from_big_endian(data))),
+		ParamType::Int(bits) | ParamType::Fixed(bits, _) => {
+			Ok(Token::Int(I256::from_raw_bits(Uint::from_big_endian(data), *bits).into_raw()))
+		}
+		ParamType::FixedBytes(_) | ParamType::Function => Ok(Token::FixedBytes(data.to_vec())),
+		ParamType::Bytes => Ok(Token::Bytes(data.to_vec())),
+		ParamType::String => Ok(Token::String(String::from_utf8(data.to_vec())?)),
+		ParamType::Array(inner) | ParamType::FixedArray(inner, _) => {
+			if !data.len().is_multiple_of(32) {
+				return Err(Error::InvalidData);
+			}
+			let tokens = data.chunks(32).map(|word| Ok(decode(&[*inner.clone()], word)?.remove(0)));
+			let tokens = tokens.collect::<Result<Vec<_>>>()?;
+			match kind {
+				ParamType::Array(_) => Ok(Token::Array(tokens)),
+				_ => Ok(Token::FixedArray(tokens)),
+			}
+		}
+		ParamType::Tuple(_) => unreachable!("tuples cannot be packed, see packed_len"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use hex_literal::hex;
+
+	use super::decode_packed;
+	use crate::{Address, ParamType, Token, I256};
+
+	#[test]
+	fn test_decode_packed() {
+		let data = hex!(
+			"
+			4444444444444444444444444444444444444444
+			ff9c
+			01
+			68656c6c6f
+		"
+		);
+		let types = [ParamType::Address, ParamType::Int(16), ParamType::Bool, ParamType::String];
+		let expected = vec![
+			Token::Address(Address::repeat_byte(0x44)),
+			Token::Int(I256::from(-100).into()),
+			Token::Bool(true),
+			Token::String("hello".to_owned()),
+		];
+		assert_eq!(decode_packed(&types, &data).unwrap(), expected);
+
+		// the dynamic param may be anywhere, taking the bytes left by the others
+		let types = [ParamType::Bytes, ParamType::Uint(8), ParamType::FixedBytes(2)];
+		let expected =
+			vec![Token::Bytes(vec![0xaa, 0xbb]), Token::Uint(0xcc.into()), Token::FixedBytes(vec![0xdd, 0xee])];
+		assert_eq!(decode_packed(&types, &hex!("aabbccddee")).unwrap(), expected);
+		assert_eq!(decode_packed(&types, &hex!("ccddee")).unwrap()[0], Token::Bytes(vec![]));
+	}
+
+	#[test]
+	fn test_decode_packed_arrays() {
+		let data = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000002
+			07
+		"
+		);
+		let types = [ParamType::Array(Box::new(ParamType::Uint(8))), ParamType::Uint(8)];
+		let expected = vec![Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]), Token::Uint(7.into())];
+		assert_eq!(decode_packed(&types, &data).unwrap(), expected);
+
+		let types = [ParamType::FixedArray(Box::new(ParamType::Uint(8)), 2), ParamType::Uint(8)];
+		let expected =
+			vec![Token::FixedArray(vec![Token::Uint(1.into()), Token::Uint(2.into())]), Token::Uint(7.into())];
+		assert_eq!(decode_packed(&types, &data).unwrap(), expected);
+
+		// array elements must be valid when padded to 32 bytes
+		let types = [ParamType::FixedArray(Box::new(ParamType::Bool), 1)];
+		assert!(decode_packed(&types, &[0xff; 32]).is_err());
+	}
+
+	#[test]
+	#[cfg(feature = "std")]
+	fn test_decode_packed_messages() {
+		let err = decode_packed(&[ParamType::String, ParamType::Bytes], &[]).unwrap_err();
+		assert_eq!(err.to_string(), "Ambiguous packed data: the lengths of both string and bytes are not encoded");
+
+		let err = decode_packed(&[ParamType::Tuple(vec![ParamType::Bool])], &[1]).unwrap_err();
+		assert_eq!(err.to_string(), "(bool) cannot be packed");
+	}
+
+	#[test]
+	fn test_decode_packed_invalid() {
+		assert!(decode_packed(&[ParamType::String, ParamType::Bytes], &[]).is_err());
+		assert!(decode_packed(&[ParamType::Tuple(vec![ParamType::Bool])], &[1]).is_err());
+		assert!(decode_packed(&[ParamType::Array(Box::new(ParamType::String))], &[]).is_err());
+
+		assert!(decode_packed(&[ParamType::Uint(16)], &[1]).is_err());
+		assert!(decode_packed(&[ParamType::Uint(16)], &[1, 2, 3]).is_err());
+		assert!(decode_packed(&[ParamType::Bool], &[2]).is_err());
+		assert!(decode_packed(&[ParamType::Array(Box::new(ParamType::Bool))], &[1]).is_err());
+		assert!(decode_packed(&[ParamType::FixedArray(Box::new(ParamType::Bool), usize::MAX / 16)], &[]).is_err());
+		let huge = ParamType::FixedArray(Box::new(ParamType::Bool), usize::MAX / 32);
+		assert!(decode_packed(&[huge.clone(), huge], &[]).is_err());
+	}
+}
@@ -8,8 +8,9 @@
 
 //! Function and event param types.
 
-use super::Writer;
-use std::fmt;
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use core::fmt;
 
 /// Function and event param types.
 #[derive(Debug, Clone, PartialEq)]
@@ -22,6 +23,10 @@ pub enum ParamType {
 	Int(usize),
 	/// Unsigned integer.
 	Uint(usize),
+	/// Signed fixed point decimal number with the given number of bits and decimals.
+	Fixed(usize, usize),
+	/// Unsigned fixed point decimal number with the given number of bits and decimals.
+	Ufixed(usize, usize),
 	/// Boolean.
 	Bool,
 	/// String.
@@ -34,11 +39,38 @@ pub enum ParamType {
 	FixedArray(Box<ParamType>, usize),
 	/// Tuple containing different types
 	Tuple(Vec<ParamType>),
+	/// Function, an address followed by a function selector.
+	Function,
 }
 
+/// Formats the canonical type name used in signatures, e.g. `uint256`, `bytes32[4]`
+/// or `(uint256,address)[]`.
 impl fmt::Display for ParamType {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "{}", Writer::write(self))
+		match self {
+			ParamType::Address => f.write_str("address"),
+			ParamType::Bytes => f.write_str("bytes"),
+			ParamType::FixedBytes(len) => write!(f, "bytes{}", len),
+			ParamType::Int(len) => write!(f, "int{}", len),
+			ParamType::Uint(len) => write!(f, "uint{}", len),
+			ParamType::Fixed(len, decimals) => write!(f, "fixed{}x{}", len, decimals),
+			ParamType::Ufixed(len, decimals) => write!(f, "ufixed{}x{}", len, decimals),
+			ParamType::Bool => f.write_str("bool"),
+			ParamType::String => f.write_str("string"),
+			ParamType::Function => f.write_str("function"),
+			ParamType::FixedArray(param, len) => write!(f, "{}[{}]", param, len),
+			ParamType::Array(param) => write!(f, "{}[]", param),
+			ParamType::Tuple(params) => {
+				f.write_str("(")?;
+				for (index, param) in params.iter().enumerate() {
+					if index > 0 {
+						f.write_str(",")?;
+					}
+					write!(f, "{}", param)?;
+				}
+				f.write_str(")")
+			}
+		}
 	}
 }
 
@@ -76,8 +108,11 @@ mod tests {
 		assert_eq!(format!("{}", ParamType::FixedBytes(32)), "bytes32".to_owned());
 		assert_eq!(format!("{}", ParamType::Uint(256)), "uint256".to_owned());
 		assert_eq!(format!("{}", ParamType::Int(64)), "int64".to_owned());
+		assert_eq!(format!("{}", ParamType::Fixed(128, 18)), "fixed128x18".to_owned());
+		assert_eq!(format!("{}", ParamType::Ufixed(8, 1)), "ufixed8x1".to_owned());
 		assert_eq!(format!("{}", ParamType::Bool), "bool".to_owned());
 		assert_eq!(format!("{}", ParamType::String), "string".to_owned());
+		assert_eq!(format!("{}", ParamType::Function), "function".to_owned());
 		assert_eq!(format!("{}", ParamType::Array(Box::new(ParamType::Bool))), "bool[]".to_owned());
 		assert_eq!(format!("{}", ParamType::FixedArray(Box::new(ParamType::Uint(256)), 2)), "uint256[2]".to_owned());
 		assert_eq!(format!("{}", ParamType::FixedArray(Box::new(ParamType::String), 2)), "string[2]".to_owned());
@@ -85,6 +120,17 @@ mod tests {
 			format!("{}", ParamType::FixedArray(Box::new(ParamType::Array(Box::new(ParamType::Bool))), 2)),
 			"bool[][2]".to_owned()
 		);
+		assert_eq!(format!("{}", ParamType::Tuple(vec![])), "()".to_owned());
+		assert_eq!(
+			format!(
+				"{}",
+				ParamType::Array(Box::new(ParamType::Tuple(vec![
+					ParamType::Uint(256),
+					ParamType::Tuple(vec![ParamType::Address, ParamType::FixedArray(Box::new(ParamType::Bytes), 4)]),
+				])))
+			),
+			"(uint256,(address,bytes[4]))[]".to_owned()
+		);
 	}
 
 	#[test]
@@ -94,6 +140,7 @@ mod tests {
 		assert!(!ParamType::FixedBytes(32).is_dynamic());
 		assert!(!ParamType::Uint(256).is_dynamic());
 		assert!(!ParamType::Int(64).is_dynamic());
+		assert!(!ParamType::Fixed(128, 18).is_dynamic());
 		assert!(!ParamType::Bool.is_dynamic());
 		assert!(ParamType::String.is_dynamic());
 		assert!(ParamType::Array(Box::new(ParamType::Bool)).is_dynamic());
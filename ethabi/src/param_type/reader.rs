@@ -6,115 +6,45 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::{Error, ParamType};
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{Error, ParamType, DEFAULT_MAX_DEPTH};
 
 /// Used to convert param type represented as a string to rust structure.
 pub struct Reader;
 
 impl Reader {
 	/// Converts string to param type.
+	///
+	/// Fails for types nested deeper than `DEFAULT_MAX_DEPTH` levels.
 	pub fn read(name: &str) -> Result<ParamType, Error> {
-		match name.chars().last() {
-			// check if it is a struct
-			Some(')') => {
-				if !name.starts_with('(') {
-					return Err(Error::InvalidName(name.to_owned()));
-				};
-
-				let mut subtypes = Vec::new();
-				let mut subtuples = Vec::new();
-				let mut nested = 0isize;
-				let mut top_level_paren_open = 0usize;
-				let mut last_item = 1;
-				let mut chars = name.chars().enumerate();
-
-				// Iterate over name and build the nested tuple structure
-				while let Some((mut pos, c)) = chars.next() {
-					match c {
-						'(' => {
-							top_level_paren_open = pos;
-							nested += 1;
-							// If an '(' is encountered within the tuple
-							// insert an empty subtuples vector to be filled
-							if nested > 1 {
-								subtuples.push(vec![]);
-								last_item = pos + 1;
-							}
-						}
-						')' => {
-							nested -= 1;
+		Reader::read_with(name, |_| None)
+	}
 
-							// End parsing and return an error if parentheses aren't symmetrical
-							if nested < 0 {
-								return Err(Error::InvalidName(name.to_owned()));
-							}
-							// If there have not been any characters since the last item
-							// increment position without inserting any subtypes
-							else if name[last_item..pos].is_empty() {
-								last_item = pos + 1;
-							}
-							// If the item is in the top level of the tuple insert it into subtypes
-							else if nested == 0 {
-								// check for trailing brackets that indicate array of tuples
-								let sub = &name[last_item..pos];
-								let subtype = Reader::read(sub)?;
-								subtypes.push(subtype);
-								last_item = pos + 1;
-							}
-							// If the item is in a sublevel of the tuple
-							else if nested > 0 {
-								// this makes sure trailing brackets are included for the next step
-								loop {
-									match chars.clone().next() {
-										Some((_, ',')) | Some((_, ')')) | None => break,
-										_ => {
-											// consume the char and shift position
-											chars.next();
-											pos += 1;
-										}
-									}
-								}
+	/// Converts string to param type, resolving the base type names with `resolve` before the standard
+	/// ones, e.g. the `percent` in `percent[]` to a custom type.
+	pub fn read_with<F: Fn(&str) -> Option<ParamType>>(name: &str, resolve: F) -> Result<ParamType, Error> {
+		Reader::read_nested(name, 0, &resolve)
+	}
 
-								// parse the nested tuple
-								let inner_tuple = &name[top_level_paren_open..=pos];
-								let subtype = Reader::read(inner_tuple)?;
+	fn read_nested(name: &str, depth: usize, resolve: &dyn Fn(&str) -> Option<ParamType>) -> Result<ParamType, Error> {
+		if depth > DEFAULT_MAX_DEPTH {
+			return Err(Error::NestingTooDeep);
+		}
 
-								if nested > 1 {
-									let mut subtuple = std::mem::take(&mut subtuples[(nested - 2) as usize]);
-									subtuple.push(subtype);
-									subtypes.push(ParamType::Tuple(subtuple));
-								} else {
-									subtypes.push(subtype);
-								}
-								last_item = pos + 1;
-							}
-						}
-						',' => {
-							// If there have not been any characters since the last item
-							// increment position without inserting any subtypes
-							if name[last_item..pos].is_empty() {
-								last_item = pos + 1
-							}
-							// If the item is in the top level of the tuple insert it into subtypes
-							else if nested == 1 {
-								let sub = &name[last_item..pos];
-								let subtype = Reader::read(sub)?;
-								subtypes.push(subtype);
-								last_item = pos + 1;
-							}
-							// If the item is in a sublevel of the tuple
-							// insert it into the subtuple vector for the current depth level
-							else if nested > 1 {
-								let sub = &name[last_item..pos];
-								let subtype = Reader::read(sub)?;
-								subtuples[(nested - 2) as usize].push(subtype);
-								last_item = pos + 1;
-							}
-						}
-						_ => (),
-					}
-				}
-				return Ok(ParamType::Tuple(subtypes));
+		match name.chars().last() {
+			// check if it is a struct
+			Some(')') => {
+				let components = name
+					.strip_prefix('(')
+					.and_then(|name| name.strip_suffix(')'))
+					.and_then(Reader::split_components)
+					.ok_or_else(|| Error::InvalidName(name.to_owned()))?;
+				return components
+					.into_iter()
+					.map(|component| Reader::read_nested(component, depth + 1, resolve))
+					.collect::<Result<_, _>>()
+					.map(ParamType::Tuple);
 			}
 			// check if it is a fixed or dynamic array.
 			Some(']') => {
@@ -125,26 +55,33 @@ impl Reader {
 				let count = name.chars().count();
 				return if num.is_empty() {
 					// we already know it's a dynamic array!
-					let subtype = Reader::read(&name[..count - 2])?;
+					let subtype = Reader::read_nested(&name[..count - 2], depth + 1, resolve)?;
 					Ok(ParamType::Array(Box::new(subtype)))
 				} else {
 					// it's a fixed array.
 					let len = num.parse()?;
-					let subtype = Reader::read(&name[..count - num.len() - 2])?;
+					let subtype = Reader::read_nested(&name[..count - num.len() - 2], depth + 1, resolve)?;
 					Ok(ParamType::FixedArray(Box::new(subtype), len))
 				};
 			}
 			_ => (),
 		}
 
+		if let Some(param) = resolve(name) {
+			return Ok(param);
+		}
+
 		let result = match name {
 			"address" => ParamType::Address,
 			"bytes" => ParamType::Bytes,
 			"bool" => ParamType::Bool,
 			"string" => ParamType::String,
+			"function" => ParamType::Function,
 			"int" => ParamType::Int(256),
 			"tuple" => ParamType::Tuple(vec![]),
 			"uint" => ParamType::Uint(256),
+			"fixed" => ParamType::Fixed(128, 18),
+			"ufixed" => ParamType::Ufixed(128, 18),
 			s if s.starts_with("int") => {
 				let len = s[3..].parse()?;
 				ParamType::Int(len)
@@ -153,6 +90,14 @@ impl Reader {
 				let len = s[4..].parse()?;
 				ParamType::Uint(len)
 			}
+			s if s.starts_with("fixed") => {
+				let (len, decimals) = Reader::read_fixed_size(name, &s[5..])?;
+				ParamType::Fixed(len, decimals)
+			}
+			s if s.starts_with("ufixed") => {
+				let (len, decimals) = Reader::read_fixed_size(name, &s[6..])?;
+				ParamType::Ufixed(len, decimals)
+			}
 			s if s.starts_with("bytes") => {
 				let len = s[5..].parse()?;
 				ParamType::FixedBytes(len)
@@ -164,6 +109,52 @@ impl Reader {
 
 		Ok(result)
 	}
+
+	/// Splits the components of a tuple at the commas which are not nested in
+	/// another tuple, e.g. `uint256,(address,bytes)[]`.
+	///
+	/// Returns `None` if the parentheses are unbalanced or a component is empty.
+	pub(crate) fn split_components(tuple: &str) -> Option<Vec<&str>> {
+		if tuple.is_empty() {
+			return Some(vec![]);
+		}
+
+		let mut components = Vec::new();
+		let mut nested = 0usize;
+		let mut start = 0;
+		for (pos, c) in tuple.char_indices() {
+			match c {
+				'(' => nested += 1,
+				')' => nested = nested.checked_sub(1)?,
+				',' if nested == 0 => {
+					components.push(&tuple[start..pos]);
+					start = pos + 1;
+				}
+				_ => (),
+			}
+		}
+		components.push(&tuple[start..]);
+
+		match nested == 0 && components.iter().all(|component| !component.is_empty()) {
+			true => Some(components),
+			false => None,
+		}
+	}
+
+	/// Parses the `MxN` suffix of `fixedMxN` and `ufixedMxN` types, where the number of bits `M` is a multiple
+	/// of 8 from 8 to 256 and the number of decimals `N` is from 1 to 80.
+	fn read_fixed_size(name: &str, size: &str) -> Result<(usize, usize), Error> {
+		let mut parts = size.splitn(2, 'x');
+		let (len, decimals): (usize, usize) = match (parts.next(), parts.next()) {
+			(Some(len), Some(decimals)) => (len.parse()?, decimals.parse()?),
+			_ => return Err(Error::InvalidName(name.to_owned())),
+		};
+
+		match (8..=256).contains(&len) && len.is_multiple_of(8) && (1..=80).contains(&decimals) {
+			true => Ok((len, decimals)),
+			false => Err(Error::InvalidName(name.to_owned())),
+		}
+	}
 }
 
 #[cfg(test)]
@@ -178,10 +169,21 @@ mod tests {
 		assert_eq!(Reader::read("bytes32").unwrap(), ParamType::FixedBytes(32));
 		assert_eq!(Reader::read("bool").unwrap(), ParamType::Bool);
 		assert_eq!(Reader::read("string").unwrap(), ParamType::String);
+		assert_eq!(Reader::read("function").unwrap(), ParamType::Function);
 		assert_eq!(Reader::read("int").unwrap(), ParamType::Int(256));
 		assert_eq!(Reader::read("uint").unwrap(), ParamType::Uint(256));
 		assert_eq!(Reader::read("int32").unwrap(), ParamType::Int(32));
 		assert_eq!(Reader::read("uint32").unwrap(), ParamType::Uint(32));
+		assert_eq!(Reader::read("fixed").unwrap(), ParamType::Fixed(128, 18));
+		assert_eq!(Reader::read("ufixed").unwrap(), ParamType::Ufixed(128, 18));
+		assert_eq!(Reader::read("fixed128x18").unwrap(), ParamType::Fixed(128, 18));
+		assert_eq!(Reader::read("ufixed8x80").unwrap(), ParamType::Ufixed(8, 80));
+		assert!(Reader::read("fixed128").is_err());
+		assert!(Reader::read("ufixedx18").is_err());
+		assert!(Reader::read("fixed7x1").is_err());
+		assert!(Reader::read("fixed264x18").is_err());
+		assert!(Reader::read("ufixed8x0").is_err());
+		assert!(Reader::read("ufixed8x81").is_err());
 	}
 
 	#[test]
@@ -281,4 +283,45 @@ mod tests {
 
 		assert_eq!(abi, Writer::write(&param));
 	}
+
+	#[test]
+	fn test_read_tuple_with_nested_tuple_arrays() {
+		let inner = ParamType::Tuple(vec![ParamType::Address, ParamType::Bytes]);
+		assert_eq!(
+			Reader::read("(uint256,(address,bytes))[]").unwrap(),
+			ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Uint(256), inner.clone()])))
+		);
+		assert_eq!(
+			Reader::read("((address,bytes)[],(uint8,(bool)))").unwrap(),
+			ParamType::Tuple(vec![
+				ParamType::Array(Box::new(inner.clone())),
+				ParamType::Tuple(vec![ParamType::Uint(8), ParamType::Tuple(vec![ParamType::Bool])]),
+			])
+		);
+		assert_eq!(
+			Reader::read("(bool,(address,bytes)[2][])").unwrap(),
+			ParamType::Tuple(vec![
+				ParamType::Bool,
+				ParamType::Array(Box::new(ParamType::FixedArray(Box::new(inner), 2))),
+			])
+		);
+		assert_eq!(Reader::read("()").unwrap(), ParamType::Tuple(vec![]));
+	}
+
+	#[test]
+	fn test_read_invalid_tuple() {
+		assert!(Reader::read("(uint256,(bool)").is_err());
+		assert!(Reader::read("(uint256))(").is_err());
+		assert!(Reader::read("(uint256)(bool)").is_err());
+		assert!(Reader::read("(uint256,)").is_err());
+		assert!(Reader::read("(,uint256)").is_err());
+		assert!(Reader::read("uint256)").is_err());
+	}
+
+	#[test]
+	fn test_read_nesting_too_deep() {
+		assert!(Reader::read(&format!("uint256{}", "[]".repeat(64))).is_ok());
+		assert!(Reader::read(&format!("uint256{}", "[]".repeat(65))).is_err());
+		assert!(Reader::read(&format!("uint256{}", "[2]".repeat(100_000))).is_err());
+	}
 }
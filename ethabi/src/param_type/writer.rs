@@ -6,15 +6,17 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
 use crate::ParamType;
 
 /// Output formatter for param type.
 pub struct Writer;
 
 impl Writer {
-	/// Returns string which is a formatted represenation of param.
+	/// Returns the canonical name of the param type, same as its `Display` output.
 	pub fn write(param: &ParamType) -> String {
-		Writer::write_for_abi(param, true)
+		param.to_string()
 	}
 
 	/// If `serialize_tuple_contents` is `true`, tuples will be represented
@@ -22,31 +24,14 @@ impl Writer {
 	/// If it is `false`, tuples will be represented as keyword `tuple`.
 	pub fn write_for_abi(param: &ParamType, serialize_tuple_contents: bool) -> String {
 		match *param {
-			ParamType::Address => "address".to_owned(),
-			ParamType::Bytes => "bytes".to_owned(),
-			ParamType::FixedBytes(len) => format!("bytes{}", len),
-			ParamType::Int(len) => format!("int{}", len),
-			ParamType::Uint(len) => format!("uint{}", len),
-			ParamType::Bool => "bool".to_owned(),
-			ParamType::String => "string".to_owned(),
-			ParamType::FixedArray(ref param, len) => {
-				format!("{}[{}]", Writer::write_for_abi(param, serialize_tuple_contents), len)
+			ParamType::FixedArray(ref param, len) if !serialize_tuple_contents => {
+				format!("{}[{}]", Writer::write_for_abi(param, false), len)
 			}
-			ParamType::Array(ref param) => {
-				format!("{}[]", Writer::write_for_abi(param, serialize_tuple_contents))
-			}
-			ParamType::Tuple(ref params) => {
-				if serialize_tuple_contents {
-					let formatted = params
-						.iter()
-						.map(|t| Writer::write_for_abi(t, serialize_tuple_contents))
-						.collect::<Vec<String>>()
-						.join(",");
-					format!("({})", formatted)
-				} else {
-					"tuple".to_owned()
-				}
+			ParamType::Array(ref param) if !serialize_tuple_contents => {
+				format!("{}[]", Writer::write_for_abi(param, false))
 			}
+			ParamType::Tuple(_) if !serialize_tuple_contents => "tuple".to_owned(),
+			_ => param.to_string(),
 		}
 	}
 }
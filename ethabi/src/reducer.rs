@@ -0,0 +1,245 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Folding logs into application state.
+
+use std::{collections::HashMap, fmt};
+
+use crate::{Error, EventLog, Hash, RawLog, Result};
+
+type Handler<S> = Box<dyn FnMut(&mut S, RawLog) -> Result<()>>;
+
+/// Counters of logs seen by a `Reducer`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Stats {
+	/// Number of logs decoded and applied.
+	pub decoded: u64,
+	/// Number of logs without a handler.
+	pub unknown: u64,
+	/// Number of logs decoded and applied, by event name.
+	pub events: HashMap<String, u64>,
+	/// Number of logs which could not be decoded, by reason, e.g. `invalid_data`.
+	pub failures: HashMap<&'static str, u64>,
+}
+
+impl Stats {
+	/// Total number of logs which could not be decoded.
+	pub fn failed(&self) -> u64 {
+		self.failures.values().sum()
+	}
+
+	fn record(&mut self, event: &str, result: &Result<()>) {
+		match *result {
+			Ok(()) => {
+				self.decoded += 1;
+				*self.events.entry(event.to_owned()).or_default() += 1;
+			}
+			Err(ref err) => *self.failures.entry(failure_reason(err)).or_default() += 1,
+		}
+	}
+}
+
+fn failure_reason(err: &Error) -> &'static str {
+	match *err {
+		Error::InvalidName(_) => "invalid_name",
+		Error::InvalidData | Error::InvalidDataAt { .. } => "invalid_data",
+		Error::TypeMismatch(_) => "type_mismatch",
+		Error::NestingTooDeep => "nesting_too_deep",
+		Error::SerdeJson(_) => "serde_json",
+		Error::ParseInt(_) => "parse_int",
+		Error::Utf8(_) => "utf8",
+		Error::Hex(_) => "hex",
+		Error::Other(_) => "other",
+	}
+}
+
+/// What to do with logs of a registered event which cannot be parsed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+	/// Stop and return the error.
+	#[default]
+	Fail,
+	/// Ignore the log.
+	Skip,
+}
+
+/// Dispatches logs to the handlers registered for their events.
+///
+/// Logs of events without a handler are ignored.
+pub struct Reducer<S> {
+	handlers: HashMap<Hash, (String, Handler<S>)>,
+	anonymous: Vec<(String, Handler<S>)>,
+	error_policy: ErrorPolicy,
+	stats: Stats,
+}
+
+impl<S> Default for Reducer<S> {
+	fn default() -> Self {
+		Reducer {
+			handlers: HashMap::new(),
+			anonymous: Vec::new(),
+			error_policy: ErrorPolicy::default(),
+			stats: Stats::default(),
+		}
+	}
+}
+
+impl<S> fmt::Debug for Reducer<S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Reducer")
+			.field("handlers", &self.handlers.keys().collect::<Vec<_>>())
+			.field("anonymous", &self.anonymous.len())
+			.field("error_policy", &self.error_policy)
+			.field("stats", &self.stats)
+			.finish()
+	}
+}
+
+impl<S> Reducer<S> {
+	/// Creates reducer without any handlers.
+	pub fn new() -> Self {
+		Reducer::default()
+	}
+
+	/// Sets what to do with logs which cannot be parsed.
+	pub fn error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+		self.error_policy = error_policy;
+		self
+	}
+
+	/// Registers the handler for logs of event `L`, replacing the previous one.
+	///
+	/// Anonymous events have no signature topic, so their logs are given to
+	/// the first anonymous handler which parses them successfully.
+	pub fn on<L, F>(mut self, mut handler: F) -> Self
+	where
+		L: EventLog + 'static,
+		F: FnMut(&mut S, L) + 'static,
+	{
+		let event = L::event();
+		let handler: Handler<S> = Box::new(move |state, log| {
+			handler(state, L::parse_log(log)?);
+			Ok(())
+		});
+
+		match event.anonymous {
+			true => self.anonymous.push((event.name, handler)),
+			false => {
+				self.handlers.insert(event.signature(), (event.name, handler));
+			}
+		}
+		self
+	}
+
+	/// Applies a single log to the state.
+	pub fn apply(&mut self, state: &mut S, log: RawLog) -> Result<()> {
+		let handlers = &mut self.handlers;
+		let handler = log.topics.first().and_then(|topic| handlers.get_mut(topic));
+		let result = match handler {
+			Some((name, handler)) => {
+				let result = handler(state, log);
+				self.stats.record(name, &result);
+				result
+			}
+			None => {
+				// logs of unknown events cannot be told apart from malformed anonymous ones
+				let known = self.anonymous.iter_mut().find_map(|(name, handler)| match handler(state, log.clone()) {
+					Ok(()) => Some(name),
+					Err(_) => None,
+				});
+				match known {
+					Some(name) => self.stats.record(name, &Ok(())),
+					None => self.stats.unknown += 1,
+				}
+				Ok(())
+			}
+		};
+
+		match (result, self.error_policy) {
+			(Err(_), ErrorPolicy::Skip) => Ok(()),
+			(result, _) => result,
+		}
+	}
+
+	/// Returns the counters of logs seen so far.
+	pub fn stats(&self) -> &Stats {
+		&self.stats
+	}
+
+	/// Returns the counters of logs seen so far and resets them, e.g. to export them periodically.
+	pub fn take_stats(&mut self) -> Stats {
+		std::mem::take(&mut self.stats)
+	}
+
+	/// Applies the logs to the state in order.
+	///
+	/// On failure returns the index of the log which could not be parsed.
+	pub fn run<I>(&mut self, state: &mut S, logs: I) -> std::result::Result<(), (usize, Error)>
+	where
+		I: IntoIterator<Item = RawLog>,
+	{
+		for (index, log) in logs.into_iter().enumerate() {
+			self.apply(state, log).map_err(|err| (index, err))?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ErrorPolicy, Reducer, Stats};
+	use crate::{Event, EventLog, EventParam, Hash, ParamType, RawLog, Result, Token, Uint};
+
+	#[derive(Debug, PartialEq)]
+	struct Deposit(Uint);
+
+	impl EventLog for Deposit {
+		fn event() -> Event {
+			Event {
+				name: "Deposit".into(),
+				inputs: vec![EventParam { name: "amount".into(), kind: ParamType::Uint(256), indexed: false }],
+				anonymous: false,
+			}
+		}
+
+		fn parse_log(log: RawLog) -> Result<Self> {
+			let value = Deposit::event().parse_log(log)?.params.remove(0).value;
+			Ok(Deposit(value.into_uint().expect("type checked by parse_log")))
+		}
+	}
+
+	fn deposit(amount: u64) -> RawLog {
+		RawLog { topics: vec![Deposit::event().signature()], data: crate::encode(&[Token::Uint(amount.into())]) }
+	}
+
+	#[test]
+	fn test_reducer() {
+		let mut reducer = Reducer::new().on(|total: &mut Uint, deposit: Deposit| *total += deposit.0);
+		let unknown = RawLog { topics: vec![Hash::repeat_byte(1)], data: vec![] };
+
+		let mut total = Uint::zero();
+		reducer.run(&mut total, vec![deposit(1), unknown, deposit(2)]).unwrap();
+		assert_eq!(total, 3.into());
+
+		let malformed = RawLog { topics: vec![Deposit::event().signature()], data: vec![] };
+		let err = reducer.run(&mut total, vec![deposit(1), malformed.clone()]).unwrap_err();
+		assert_eq!(err.0, 1);
+		assert_eq!(total, 4.into());
+
+		let mut reducer = reducer.error_policy(ErrorPolicy::Skip);
+		reducer.run(&mut total, vec![malformed, deposit(1)]).unwrap();
+		assert_eq!(total, 5.into());
+
+		let stats = reducer.take_stats();
+		assert_eq!(stats.decoded, 4);
+		assert_eq!(stats.unknown, 1);
+		assert_eq!(stats.events["Deposit"], 4);
+		assert_eq!(stats.failed(), 2);
+		assert_eq!(*reducer.stats(), Stats::default());
+	}
+}
@@ -0,0 +1,135 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Fetching the verified ABIs of deployed contracts from Sourcify and Etherscan compatible explorers.
+
+use anyhow::anyhow;
+use serde_json::Value;
+
+use crate::{Address, Contract, Error, Result};
+
+/// Service to fetch verified ABIs from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiSource {
+	/// Sourcify server, e.g. `https://sourcify.dev/server`.
+	Sourcify {
+		/// URL of the server, without a trailing slash.
+		url: String,
+	},
+	/// Etherscan compatible API, e.g. `https://api.etherscan.io/v2/api`.
+	Etherscan {
+		/// URL of the API, queried with `module=contract&action=getabi`.
+		url: String,
+		/// API key.
+		key: String,
+	},
+}
+
+impl AbiSource {
+	/// The public Sourcify server.
+	pub fn sourcify() -> Self {
+		AbiSource::Sourcify { url: "https://sourcify.dev/server".to_owned() }
+	}
+
+	/// The multichain API of Etherscan.
+	pub fn etherscan(key: impl Into<String>) -> Self {
+		AbiSource::Etherscan { url: "https://api.etherscan.io/v2/api".to_owned(), key: key.into() }
+	}
+}
+
+/// Fetches the verified ABI of the contract at the address on the chain of the ID, e.g. 1 for mainnet, from
+/// the public Sourcify server, which requires no API key.
+pub fn fetch_abi(chain: u64, address: Address) -> Result<Contract> {
+	fetch_abi_from(&AbiSource::sourcify(), chain, address)
+}
+
+/// Fetches the verified ABI of the contract at the address on the chain of the ID from the source.
+///
+/// Etherscan compatible APIs of a single chain ignore the chain ID.
+pub fn fetch_abi_from(source: &AbiSource, chain: u64, address: Address) -> Result<Contract> {
+	let address = format!("0x{}", hex::encode(address));
+	match source {
+		AbiSource::Sourcify { url } => {
+			let url = format!("{}/v2/contract/{}/{}", url, chain, address);
+			let response = match ureq::get(&url).query("fields", "abi").call() {
+				// not found and other errors have a JSON body with their message
+				Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+				Err(ureq::Error::Transport(err)) => return Err(anyhow!("Sourcify: {}", err).into()),
+			};
+			sourcify_abi(&response.into_string().map_err(anyhow::Error::from)?)
+		}
+		AbiSource::Etherscan { url, key } => {
+			let response = ureq::get(url)
+				.query("chainid", &chain.to_string())
+				.query("module", "contract")
+				.query("action", "getabi")
+				.query("address", &address)
+				.query("apikey", key)
+				.call()
+				// the errors of ureq contain the URL, with the API key in its query
+				.map_err(|err| match err {
+					ureq::Error::Status(status, _) => anyhow!("Etherscan: HTTP status {}", status),
+					ureq::Error::Transport(err) => anyhow!("Etherscan: {}", err.kind()),
+				})?;
+			etherscan_abi(&response.into_string().map_err(anyhow::Error::from)?)
+		}
+	}
+}
+
+/// Loads the ABI of a Sourcify `contract` response, which has the JSON ABI as `abi`, or an error `message`.
+fn sourcify_abi(response: &str) -> Result<Contract> {
+	let response: Value = serde_json::from_str(response)?;
+
+	match (&response["abi"], response["message"].as_str()) {
+		(Value::Array(_), _) => Ok(serde_json::from_value(response["abi"].clone())?),
+		(_, Some(message)) => Err(anyhow!("Sourcify: {}", message).into()),
+		_ => Err(anyhow!("invalid Sourcify response").into()),
+	}
+}
+
+/// Loads the ABI of an Etherscan `getabi` response, whose result is either the JSON ABI as a string or an error
+/// message.
+fn etherscan_abi(response: &str) -> Result<Contract> {
+	let response: Value = serde_json::from_str(response)?;
+	let result = response["result"].as_str().ok_or_else(|| Error::Other(anyhow!("invalid Etherscan response")))?;
+
+	match response["status"].as_str() {
+		Some("1") => Contract::load(result.as_bytes()),
+		_ => Err(anyhow!("Etherscan: {}", result).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{etherscan_abi, sourcify_abi};
+
+	#[test]
+	fn test_sourcify_abi() {
+		let response = r#"{"abi":[{"type":"function","name":"bar","inputs":[{"name":"hello","type":"address"}],"outputs":[{"name":"","type":"bool"}],"stateMutability":"nonpayable"}],"match":"exact_match","chainId":"1","address":"0x4444444444444444444444444444444444444444"}"#;
+		let contract = sourcify_abi(response).unwrap();
+		assert_eq!(contract.function("bar").unwrap().signature(), "bar(address):(bool)");
+
+		let response = r#"{"customCode":"not_found","message":"Contract 0x4444444444444444444444444444444444444444 on chain 1 not found or not verified","errorId":"a1b2"}"#;
+		let err = sourcify_abi(response).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"Sourcify: Contract 0x4444444444444444444444444444444444444444 on chain 1 not found or not verified"
+		);
+	}
+
+	#[test]
+	fn test_etherscan_abi() {
+		let response = r#"{"status":"1","message":"OK","result":"[{\"type\":\"function\",\"name\":\"bar\",\"inputs\":[{\"name\":\"hello\",\"type\":\"address\"}],\"outputs\":[{\"name\":\"\",\"type\":\"bool\"}],\"stateMutability\":\"nonpayable\"}]"}"#;
+		let contract = etherscan_abi(response).unwrap();
+		assert_eq!(contract.function("bar").unwrap().signature(), "bar(address):(bool)");
+
+		let response = r#"{"status":"0","message":"NOTOK","result":"Contract source code not verified"}"#;
+		let err = etherscan_abi(response).unwrap_err();
+		assert_eq!(err.to_string(), "Etherscan: Contract source code not verified");
+	}
+}
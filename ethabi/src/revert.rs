@@ -0,0 +1,192 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Decoding of the standard revert payloads, `Error(string)` and `Panic(uint256)`.
+
+use core::fmt;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{decode, Error, ParamType, Result, Token, Uint};
+
+/// Selector of `Error(string)`, emitted by `require` and `revert` with a reason.
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of `Panic(uint256)`, emitted by failed assertions and checked arithmetic.
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Reason a call reverted with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Revert {
+	/// `Error(string)` with the given reason.
+	Reason(String),
+	/// `Panic(uint256)` with the given code.
+	Panic(PanicCode),
+}
+
+impl fmt::Display for Revert {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Revert::Reason(reason) => write!(f, "reverted: {}", reason),
+			Revert::Panic(code) => write!(f, "panicked: {}", code),
+		}
+	}
+}
+
+/// Panic code of Solidity 0.8, see the Solidity documentation of `Panic(uint256)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicCode {
+	/// Generic compiler inserted panic, `0x00`.
+	Generic,
+	/// Failed `assert`, `0x01`.
+	Assert,
+	/// Arithmetic overflow or underflow outside of an `unchecked` block, `0x11`.
+	ArithmeticOverflow,
+	/// Division or modulo by zero, `0x12`.
+	DivisionByZero,
+	/// Conversion of an out of range value into an enum, `0x21`.
+	InvalidEnumValue,
+	/// Access to an incorrectly encoded storage byte array, `0x22`.
+	InvalidStorageByteArray,
+	/// `pop()` on an empty array, `0x31`.
+	EmptyArrayPop,
+	/// Array, `bytesN` or slice index out of bounds, `0x32`.
+	ArrayOutOfBounds,
+	/// Allocation of too much memory or of a too large array, `0x41`.
+	OutOfMemory,
+	/// Call of a zero initialized variable of internal function type, `0x51`.
+	UninitializedFunction,
+	/// Code not known to this version of the library.
+	Other(Uint),
+}
+
+impl From<Uint> for PanicCode {
+	fn from(code: Uint) -> Self {
+		if code > Uint::from(u8::MAX) {
+			return PanicCode::Other(code);
+		}
+
+		match code.low_u32() {
+			0x00 => PanicCode::Generic,
+			0x01 => PanicCode::Assert,
+			0x11 => PanicCode::ArithmeticOverflow,
+			0x12 => PanicCode::DivisionByZero,
+			0x21 => PanicCode::InvalidEnumValue,
+			0x22 => PanicCode::InvalidStorageByteArray,
+			0x31 => PanicCode::EmptyArrayPop,
+			0x32 => PanicCode::ArrayOutOfBounds,
+			0x41 => PanicCode::OutOfMemory,
+			0x51 => PanicCode::UninitializedFunction,
+			_ => PanicCode::Other(code),
+		}
+	}
+}
+
+impl PanicCode {
+	/// Returns the numeric code.
+	pub fn code(&self) -> Uint {
+		let code = match self {
+			PanicCode::Generic => 0x00,
+			PanicCode::Assert => 0x01,
+			PanicCode::ArithmeticOverflow => 0x11,
+			PanicCode::DivisionByZero => 0x12,
+			PanicCode::InvalidEnumValue => 0x21,
+			PanicCode::InvalidStorageByteArray => 0x22,
+			PanicCode::EmptyArrayPop => 0x31,
+			PanicCode::ArrayOutOfBounds => 0x32,
+			PanicCode::OutOfMemory => 0x41,
+			PanicCode::UninitializedFunction => 0x51,
+			PanicCode::Other(code) => return *code,
+		};
+		code.into()
+	}
+}
+
+impl fmt::Display for PanicCode {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let description = match self {
+			PanicCode::Generic => "generic panic",
+			PanicCode::Assert => "assertion failed",
+			PanicCode::ArithmeticOverflow => "arithmetic overflow or underflow",
+			PanicCode::DivisionByZero => "division or modulo by zero",
+			PanicCode::InvalidEnumValue => "invalid enum value",
+			PanicCode::InvalidStorageByteArray => "invalid storage byte array",
+			PanicCode::EmptyArrayPop => "pop on empty array",
+			PanicCode::ArrayOutOfBounds => "array index out of bounds",
+			PanicCode::OutOfMemory => "out of memory",
+			PanicCode::UninitializedFunction => "call of uninitialized function",
+			PanicCode::Other(_) => "unknown panic",
+		};
+		write!(f, "{} (0x{:02x})", description, self.code())
+	}
+}
+
+/// Decodes the data a call reverted with, if it is an `Error(string)` or a `Panic(uint256)`.
+///
+/// Custom errors are decoded with `Contract::decode_error`.
+pub fn decode_revert(data: &[u8]) -> Result<Revert> {
+	if data.len() < 4 {
+		return Err(Error::InvalidData);
+	}
+
+	let (selector, data) = data.split_at(4);
+	let kind = match selector {
+		s if s == ERROR_SELECTOR => ParamType::String,
+		s if s == PANIC_SELECTOR => ParamType::Uint(256),
+		_ => return Err(Error::InvalidData),
+	};
+
+	match decode(&[kind], data)?.pop() {
+		Some(Token::String(reason)) => Ok(Revert::Reason(reason)),
+		Some(Token::Uint(code)) => Ok(Revert::Panic(code.into())),
+		_ => Err(Error::InvalidData),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{decode_revert, PanicCode, Revert, ERROR_SELECTOR, PANIC_SELECTOR};
+	use crate::{encode, signature::short_signature, ParamType, Token, Uint};
+
+	#[test]
+	fn test_selectors() {
+		assert_eq!(short_signature("Error", &[ParamType::String]), ERROR_SELECTOR);
+		assert_eq!(short_signature("Panic", &[ParamType::Uint(256)]), PANIC_SELECTOR);
+	}
+
+	#[test]
+	fn test_decode_reason() {
+		let mut data = ERROR_SELECTOR.to_vec();
+		data.extend(encode(&[Token::String("insufficient balance".to_owned())]));
+
+		let revert = decode_revert(&data).unwrap();
+		assert_eq!(revert, Revert::Reason("insufficient balance".to_owned()));
+		assert_eq!(revert.to_string(), "reverted: insufficient balance");
+	}
+
+	#[test]
+	fn test_decode_panic() {
+		let mut data = PANIC_SELECTOR.to_vec();
+		data.extend(encode(&[Token::Uint(0x11.into())]));
+
+		let revert = decode_revert(&data).unwrap();
+		assert_eq!(revert, Revert::Panic(PanicCode::ArithmeticOverflow));
+		assert_eq!(revert.to_string(), "panicked: arithmetic overflow or underflow (0x11)");
+
+		assert_eq!(PanicCode::from(Uint::from(0x99)), PanicCode::Other(0x99.into()));
+		assert_eq!(PanicCode::from(Uint::from(0x132)), PanicCode::Other(0x132.into()));
+		assert_eq!(PanicCode::DivisionByZero.code(), 0x12.into());
+	}
+
+	#[test]
+	fn test_decode_invalid() {
+		assert!(decode_revert(&[]).is_err());
+		assert!(decode_revert(&ERROR_SELECTOR).is_err());
+		assert!(decode_revert(&[0xde, 0xad, 0xbe, 0xef]).is_err());
+	}
+}
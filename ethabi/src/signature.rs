@@ -6,40 +6,289 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
 use crate::{
-	param_type::{ParamType, Writer},
-	Hash,
+	encoded_len,
+	encoder::write_tokens,
+	param_type::{ParamType, Reader, Writer},
+	Bytes, Error, Hash, Result, Token,
 };
 use sha3::{Digest, Keccak256};
 
+/// Normalizes a signature to the canonical form hashed by solc, e.g.
+/// `transfer(address to, uint amount)` to `transfer(address,uint256)`.
+///
+/// Shorthand types `uint`, `int`, `fixed` and `ufixed` are expanded and param names
+/// and whitespace are dropped. Outputs, as in `Function::signature`, are kept, e.g.
+/// `balanceOf(address):(uint)` becomes `balanceOf(address):(uint256)`.
+pub fn canonical_signature(signature: &str) -> Result<String> {
+	let ParsedSignature { name, inputs, outputs } = parse_signature(signature)?;
+	match outputs {
+		Some(outputs) => Ok(format!("{}{}:{}", name, ParamType::Tuple(inputs), ParamType::Tuple(outputs))),
+		None => Ok(format!("{}{}", name, ParamType::Tuple(inputs))),
+	}
+}
+
+/// Returns the keccak hash of the canonical form of the signature, i.e. the topic of an
+/// event, or, in its first 4 bytes, the selector of a function or custom error.
+pub fn signature_hash(signature: &str) -> Result<Hash> {
+	let ParsedSignature { name, inputs, .. } = parse_signature(signature)?;
+	// outputs are not part of the hashed signature
+	let mut result = [0u8; 32];
+	fill_signature(name, &inputs, &mut result);
+	Ok(result.into())
+}
+
+/// Encodes a call of the function with the signature, i.e. its selector followed by the
+/// tokens, e.g. `encode_function_call("transfer(address,uint256)", &[to, amount])`.
+///
+/// The signature is normalized as by `canonical_signature`, so shorthand types and param
+/// names are accepted. Fails if the tokens do not match the inputs of the signature.
+pub fn encode_function_call(signature: &str, tokens: &[Token]) -> Result<Bytes> {
+	let ParsedSignature { name, inputs, .. } = parse_signature(signature)?;
+	Token::check_types(tokens, &inputs)?;
+
+	let mut result = Vec::with_capacity(4 + encoded_len(tokens));
+	result.extend_from_slice(&short_signature(name, &inputs));
+	write_tokens(&mut result, tokens);
+	Ok(result)
+}
+
+/// Signature `name(inputs)` or `name(inputs):(outputs)`.
+struct ParsedSignature<'a> {
+	name: &'a str,
+	inputs: Vec<ParamType>,
+	outputs: Option<Vec<ParamType>>,
+}
+
+/// Parses a signature, its params like the params of human-readable signatures.
+fn parse_signature(signature: &str) -> Result<ParsedSignature<'_>> {
+	let invalid = || Error::InvalidName(signature.to_owned());
+	let types = |params| -> Result<Vec<ParamType>> {
+		Ok(parse_params(params).map_err(|_| invalid())?.into_iter().map(|param| param.kind).collect())
+	};
+
+	let (name, inputs, tail) = split_call(signature.trim()).ok_or_else(invalid)?;
+	let outputs = match tail.strip_prefix(':') {
+		Some(outputs) => {
+			let (_, outputs, _) = split_call(outputs.trim_start())
+				.filter(|(name, _, tail)| name.is_empty() && tail.is_empty())
+				.ok_or_else(invalid)?;
+			Some(types(outputs)?)
+		}
+		None if tail.is_empty() => None,
+		None => return Err(invalid()),
+	};
+	Ok(ParsedSignature { name, inputs: types(inputs)?, outputs })
+}
+
 pub fn short_signature(name: &str, params: &[ParamType]) -> [u8; 4] {
 	let mut result = [0u8; 4];
 	fill_signature(name, params, &mut result);
 	result
 }
 
+#[cfg(feature = "std")]
 pub fn long_signature(name: &str, params: &[ParamType]) -> Hash {
 	let mut result = [0u8; 32];
 	fill_signature(name, params, &mut result);
 	result.into()
 }
 
+/// Returns the canonical signature of the name and param types, e.g. `transfer(address,uint256)`.
+pub(crate) fn format_signature<'a, I>(name: &str, params: I) -> String
+where
+	I: IntoIterator<Item = &'a ParamType>,
+{
+	let types = params.into_iter().map(Writer::write).collect::<Vec<String>>().join(",");
+	format!("{}({})", name, types)
+}
+
 fn fill_signature(name: &str, params: &[ParamType], result: &mut [u8]) {
-	let types = params.iter().map(Writer::write).collect::<Vec<String>>().join(",");
+	let data = format_signature(name, params);
+
+	result.copy_from_slice(&Keccak256::digest(data.as_bytes())[..result.len()])
+}
+
+/// Parameter as written in a signature.
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+pub(crate) struct RawParam {
+	pub(crate) name: String,
+	pub(crate) kind: ParamType,
+	pub(crate) indexed: bool,
+}
+
+/// Parses the comma separated params of a signature, e.g. `address indexed from, uint256 value`.
+pub(crate) fn parse_params(params: &str) -> Result<Vec<RawParam>> {
+	split_top_level(params).into_iter().map(parse_param).collect()
+}
 
-	let data: Vec<u8> = From::from(format!("{}({})", name, types).as_str());
+fn parse_param(param: &str) -> Result<RawParam> {
+	let (kind, rest) = split_type(param.trim()).ok_or_else(|| Error::InvalidName(param.to_owned()))?;
+	let mut name = String::new();
+	let mut indexed = false;
+	for word in rest.split_whitespace() {
+		match word {
+			"indexed" => indexed = true,
+			"memory" | "calldata" | "storage" | "payable" => (),
+			word if name.is_empty() => name = word.to_owned(),
+			_ => return Err(Error::InvalidName(param.to_owned())),
+		}
+	}
 
-	result.copy_from_slice(&Keccak256::digest(&data)[..result.len()])
+	Ok(RawParam { name, kind: parse_type(kind)?, indexed })
+}
+
+/// Parses a type, which unlike in `Reader` may be a tuple with named components.
+fn parse_type(kind: &str) -> Result<ParamType> {
+	let kind = kind.strip_prefix("tuple").filter(|kind| kind.starts_with('(')).unwrap_or(kind);
+	if !kind.starts_with('(') {
+		return Reader::read(kind);
+	}
+
+	let close = matching_paren(kind).ok_or_else(|| Error::InvalidName(kind.to_owned()))?;
+	let components = parse_params(&kind[1..close])?.into_iter().map(|param| param.kind).collect();
+	let mut result = ParamType::Tuple(components);
+
+	// array suffixes apply from the innermost outwards, e.g. `(uint256)[2][]`
+	let mut suffixes = &kind[close + 1..];
+	while let Some(rest) = suffixes.strip_prefix('[') {
+		let end = rest.find(']').ok_or_else(|| Error::InvalidName(kind.to_owned()))?;
+		result = match &rest[..end] {
+			"" => ParamType::Array(Box::new(result)),
+			size => ParamType::FixedArray(Box::new(result), size.parse()?),
+		};
+		suffixes = &rest[end + 1..];
+	}
+
+	match suffixes.is_empty() {
+		true => Ok(result),
+		false => Err(Error::InvalidName(kind.to_owned())),
+	}
+}
+
+/// Splits `name(params) tail` into its parts.
+pub(crate) fn split_call(call: &str) -> Option<(&str, &str, &str)> {
+	let open = call.find('(')?;
+	let close = open + matching_paren(&call[open..])?;
+	let name = call[..open].trim();
+	if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$') {
+		return None;
+	}
+	Some((name, &call[open + 1..close], call[close + 1..].trim()))
+}
+
+/// Splits a param into its type and the rest, e.g. `(uint256 a, bool b)[] indexed foo`.
+fn split_type(param: &str) -> Option<(&str, &str)> {
+	let start = match param.strip_prefix("tuple") {
+		Some(rest) if rest.starts_with('(') => "tuple".len(),
+		_ => 0,
+	};
+
+	if param[start..].starts_with('(') {
+		let close = start + matching_paren(&param[start..])?;
+		let end = param[close..].find(char::is_whitespace).map_or(param.len(), |index| close + index);
+		return Some((&param[..end], &param[end..]));
+	}
+
+	match param.split_once(char::is_whitespace) {
+		Some((kind, rest)) => Some((kind, rest)),
+		None if !param.is_empty() => Some((param, "")),
+		None => None,
+	}
+}
+
+/// Returns the index of the paren closing the one `value` starts with.
+fn matching_paren(value: &str) -> Option<usize> {
+	let mut depth = 0usize;
+	for (index, c) in value.char_indices() {
+		match c {
+			'(' => depth += 1,
+			')' => {
+				depth = depth.checked_sub(1)?;
+				if depth == 0 {
+					return Some(index);
+				}
+			}
+			_ => (),
+		}
+	}
+	None
+}
+
+/// Splits a comma separated list, ignoring commas nested in parens.
+fn split_top_level(list: &str) -> Vec<&str> {
+	if list.trim().is_empty() {
+		return vec![];
+	}
+
+	let mut result = Vec::new();
+	let mut depth = 0isize;
+	let mut start = 0;
+	for (index, c) in list.char_indices() {
+		match c {
+			'(' => depth += 1,
+			')' => depth -= 1,
+			',' if depth == 0 => {
+				result.push(&list[start..index]);
+				start = index + 1;
+			}
+			_ => (),
+		}
+	}
+	result.push(&list[start..]);
+	result
 }
 
 #[cfg(test)]
 mod tests {
-	use super::short_signature;
-	use crate::ParamType;
+	use super::{canonical_signature, encode_function_call, short_signature, signature_hash};
+	use crate::{encode, ParamType, Token};
 	use hex_literal::hex;
 
 	#[test]
 	fn test_signature() {
 		assert_eq!(hex!("cdcd77c0"), short_signature("baz", &[ParamType::Uint(32), ParamType::Bool]));
 	}
+
+	#[test]
+	fn test_canonical_signature() {
+		assert_eq!(canonical_signature("transfer(address to, uint amount)").unwrap(), "transfer(address,uint256)");
+		assert_eq!(canonical_signature("foo(int[], fixed)").unwrap(), "foo(int256[],fixed128x18)");
+		assert_eq!(
+			canonical_signature("foo((uint a, bool b)[] c, tuple(int, bytes)[2])").unwrap(),
+			"foo((uint256,bool)[],(int256,bytes)[2])"
+		);
+		assert_eq!(canonical_signature("balanceOf(address):(uint)").unwrap(), "balanceOf(address):(uint256)");
+		assert_eq!(canonical_signature("foo()").unwrap(), "foo()");
+		assert!(canonical_signature("foo").is_err());
+		assert!(canonical_signature("foo(uint,)").is_err());
+		assert!(canonical_signature("foo((uint)").is_err());
+		assert!(canonical_signature("foo(uint x y)").is_err());
+		assert!(canonical_signature("foo(uint x):(bool) y").is_err());
+	}
+
+	#[test]
+	fn test_signature_hash() {
+		assert_eq!(signature_hash("transfer(address,uint)").unwrap()[..4], hex!("a9059cbb"));
+		assert_eq!(
+			signature_hash("Transfer(address indexed from, address indexed to, uint value)").unwrap(),
+			hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").into()
+		);
+		assert_eq!(signature_hash("balanceOf(address):(uint)").unwrap(), signature_hash("balanceOf(address)").unwrap());
+	}
+
+	#[test]
+	fn test_encode_function_call() {
+		let tokens = [Token::Address([0x11u8; 20].into()), Token::Uint(1.into())];
+		let expected = [&hex!("a9059cbb")[..], &encode(&tokens)].concat();
+
+		assert_eq!(encode_function_call("transfer(address,uint256)", &tokens).unwrap(), expected);
+		assert_eq!(encode_function_call("transfer(address to, uint amount):(bool)", &tokens).unwrap(), expected);
+		assert_eq!(encode_function_call("totalSupply()", &[]).unwrap(), hex!("18160ddd"));
+		assert!(encode_function_call("transfer(address,uint256)", &tokens[..1]).is_err());
+		assert!(encode_function_call("transfer(address,bool)", &tokens).is_err());
+		assert!(encode_function_call("transfer", &tokens).is_err());
+	}
 }
@@ -23,6 +23,19 @@ impl Default for StateMutability {
 	}
 }
 
+impl StateMutability {
+	/// Returns whether the function does not modify the blockchain state, so it can be
+	/// executed with a call instead of a transaction.
+	pub fn is_read_only(self) -> bool {
+		matches!(self, StateMutability::Pure | StateMutability::View)
+	}
+
+	/// Returns whether the function accepts Ether.
+	pub fn is_payable(self) -> bool {
+		self == StateMutability::Payable
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use crate::{tests::assert_json_eq, StateMutability};
@@ -47,4 +60,13 @@ mod test {
 
 		assert_json_eq(json, &serde_json::to_string(&deserialized).unwrap());
 	}
+
+	#[test]
+	fn read_only() {
+		assert!(StateMutability::Pure.is_read_only());
+		assert!(StateMutability::View.is_read_only());
+		assert!(!StateMutability::NonPayable.is_read_only());
+		assert!(!StateMutability::Payable.is_read_only());
+		assert!(StateMutability::Payable.is_payable());
+	}
 }
@@ -0,0 +1,170 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Streams of parsed logs, the async counterpart of parsing logs one by one.
+
+use std::{
+	marker::PhantomData,
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{EventLog, Hash, RawLog, Result};
+
+pin_project! {
+	/// Stream of the logs of the event `L`, parsed from a stream of raw logs.
+	///
+	/// Raw logs of other events, i.e. with a different first topic, are skipped without
+	/// being parsed, unless the event is anonymous. Raw logs are polled one at a time,
+	/// only when the next parsed log is polled.
+	#[must_use = "streams do nothing unless polled"]
+	pub struct EventStream<S, L> {
+		#[pin]
+		logs: S,
+		topic0: Option<Hash>,
+		log: PhantomData<fn() -> L>,
+	}
+}
+
+impl<S, L> EventStream<S, L>
+where
+	S: Stream<Item = RawLog>,
+	L: EventLog,
+{
+	/// Parses the logs of `L` from the stream of raw logs.
+	pub fn new(logs: S) -> Self {
+		let event = L::event();
+		let topic0 = match event.anonymous {
+			true => None,
+			false => Some(event.topic0()),
+		};
+		EventStream { logs, topic0, log: PhantomData }
+	}
+
+	/// Unwraps the stream of raw logs.
+	pub fn into_inner(self) -> S {
+		self.logs
+	}
+}
+
+impl<S, L> Stream for EventStream<S, L>
+where
+	S: Stream<Item = RawLog>,
+	L: EventLog,
+{
+	type Item = Result<L>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let mut this = self.project();
+		loop {
+			let log = match this.logs.as_mut().poll_next(cx) {
+				Poll::Ready(Some(log)) => log,
+				Poll::Ready(None) => return Poll::Ready(None),
+				Poll::Pending => return Poll::Pending,
+			};
+
+			match this.topic0 {
+				Some(topic0) if log.topics.first() != Some(topic0) => continue,
+				_ => return Poll::Ready(Some(L::parse_log(log))),
+			}
+		}
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		// any of the raw logs may be skipped
+		(0, self.logs.size_hint().1)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		pin::Pin,
+		task::{Context, Poll, Waker},
+		vec,
+	};
+
+	use futures_core::Stream;
+
+	use crate::{Event, EventLog, EventParam, Hash, ParamType, RawLog, Result, Uint};
+
+	#[derive(Debug, PartialEq)]
+	struct Deposit(Uint);
+
+	impl EventLog for Deposit {
+		fn event() -> Event {
+			Event {
+				name: "Deposit".to_owned(),
+				inputs: vec![EventParam { name: "amount".to_owned(), kind: ParamType::Uint(256), indexed: false }],
+				anonymous: false,
+			}
+		}
+
+		fn parse_log(log: RawLog) -> Result<Self> {
+			let log = Self::event().parse_log(log)?;
+			Ok(Deposit(log.params[0].value.clone().into_uint().unwrap()))
+		}
+	}
+
+	/// Stream of the logs, returning pending before each of them.
+	struct Logs {
+		logs: vec::IntoIter<RawLog>,
+		pending: bool,
+	}
+
+	impl Stream for Logs {
+		type Item = RawLog;
+
+		fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<RawLog>> {
+			self.pending = !self.pending;
+			match self.pending {
+				true => {
+					cx.waker().wake_by_ref();
+					Poll::Pending
+				}
+				false => Poll::Ready(self.logs.next()),
+			}
+		}
+	}
+
+	fn deposit(amount: u64) -> RawLog {
+		let mut data = [0u8; 32];
+		data[24..].copy_from_slice(&amount.to_be_bytes());
+		RawLog { topics: vec![Deposit::event().topic0()], data: data.to_vec() }
+	}
+
+	#[test]
+	fn test_event_stream() {
+		let logs = vec![
+			deposit(1),
+			RawLog { topics: vec![Hash::repeat_byte(1)], data: vec![] },
+			RawLog { topics: vec![], data: vec![] },
+			deposit(2),
+			RawLog { topics: vec![Deposit::event().topic0()], data: vec![] },
+		];
+		let mut stream = Box::pin(Deposit::into_stream(Logs { logs: logs.into_iter(), pending: false }));
+		let mut cx = Context::from_waker(Waker::noop());
+
+		let mut parsed = Vec::new();
+		loop {
+			match stream.as_mut().poll_next(&mut cx) {
+				Poll::Ready(Some(log)) => parsed.push(log),
+				Poll::Ready(None) => break,
+				Poll::Pending => continue,
+			}
+		}
+
+		assert_eq!(parsed.len(), 3);
+		assert_eq!(parsed[0].as_ref().unwrap(), &Deposit(1.into()));
+		assert_eq!(parsed[1].as_ref().unwrap(), &Deposit(2.into()));
+		assert!(parsed[2].is_err());
+	}
+}
@@ -6,19 +6,21 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::{decode, encode, ParamType, Token};
+use crate::{decode, encode, ParamType, Token, Uint};
 use hex_literal::hex;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fmt::Debug;
 
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) fn assert_json_eq(left: &str, right: &str) {
 	let left: Value = serde_json::from_str(left).unwrap();
 	let right: Value = serde_json::from_str(right).unwrap();
 	assert_eq!(left, right);
 }
 
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) fn assert_ser_de<T>(canon: &T)
 where
 	T: Serialize + for<'a> Deserialize<'a> + PartialEq + Debug,
@@ -146,6 +148,21 @@ test_encode_decode! {
 	tokens: [Token::Uint([0x11u8; 32].into())],
 	data: "1111111111111111111111111111111111111111111111111111111111111111"
 }
+test_encode_decode! {
+	name: function,
+	types: [ParamType::Function],
+	tokens: [Token::FixedBytes(hex!("11111111111111111111111111111111111111118c5be1e5").to_vec())],
+	data: "11111111111111111111111111111111111111118c5be1e50000000000000000"
+}
+test_encode_decode! {
+	name: fixed,
+	types: [ParamType::Fixed(128, 18), ParamType::Ufixed(8, 1)],
+	// -1.5 and 1.5
+	tokens: [Token::Int(Uint::MAX - 1_500_000_000_000_000_000u64 + 1), Token::Uint(15.into())],
+	data: "
+		ffffffffffffffffffffffffffffffffffffffffffffffffeb2eedf284ea0000
+		000000000000000000000000000000000000000000000000000000000000000f"
+}
 test_encode_decode! {
 	name: uint2,
 	types: [ParamType::Uint(32)],
@@ -413,6 +430,89 @@ test_encode_decode! {
 		6666666666666666666666666666666666666666666666666666666666666666
 		6666666666660000000000000000000000000000000000000000000000000000"
 }
+test_encode_decode! {
+	name: dynamic_array_of_strings,
+	types: [ParamType::Array(Box::new(ParamType::String))],
+	tokens: [Token::Array(vec![Token::String("foo".into()), Token::String("".into())])],
+	// line 1 at 0x00 =   0: tail offset of array
+	// line 2 at 0x20 =  32: length of array
+	// line 3 at 0x40 =  64: offset of array[0] (string)
+	// line 4 at 0x60 =  96: offset of array[1] (string)
+	// line 5 at 0x80 = 128: length of array[0]
+	// line 6 at 0xa0 = 160: value of array[0]
+	// line 7 at 0xc0 = 192: length of array[1]
+	data: "
+		0000000000000000000000000000000000000000000000000000000000000020
+		0000000000000000000000000000000000000000000000000000000000000002
+		0000000000000000000000000000000000000000000000000000000000000040
+		0000000000000000000000000000000000000000000000000000000000000080
+		0000000000000000000000000000000000000000000000000000000000000003
+		666f6f0000000000000000000000000000000000000000000000000000000000
+		0000000000000000000000000000000000000000000000000000000000000000"
+}
+test_encode_decode! {
+	name: dynamic_array_of_dynamic_arrays_of_strings,
+	types: [ParamType::Array(Box::new(ParamType::Array(Box::new(ParamType::String))))],
+	tokens: [
+		Token::Array(vec![
+			Token::Array(vec![Token::String("a".into())]),
+			Token::Array(vec![]),
+		])
+	],
+	data: "
+		0000000000000000000000000000000000000000000000000000000000000020
+		0000000000000000000000000000000000000000000000000000000000000002
+		0000000000000000000000000000000000000000000000000000000000000040
+		00000000000000000000000000000000000000000000000000000000000000c0
+		0000000000000000000000000000000000000000000000000000000000000001
+		0000000000000000000000000000000000000000000000000000000000000020
+		0000000000000000000000000000000000000000000000000000000000000001
+		6100000000000000000000000000000000000000000000000000000000000000
+		0000000000000000000000000000000000000000000000000000000000000000"
+}
+test_encode_decode! {
+	name: dynamic_array_of_dynamic_tuples,
+	types: [ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Uint(256), ParamType::String])))],
+	tokens: [
+		Token::Array(vec![
+			Token::Tuple(vec![Token::Uint(1.into()), Token::String("a".into())]),
+			Token::Tuple(vec![Token::Uint(2.into()), Token::String("b".into())]),
+		])
+	],
+	data: "
+		0000000000000000000000000000000000000000000000000000000000000020
+		0000000000000000000000000000000000000000000000000000000000000002
+		0000000000000000000000000000000000000000000000000000000000000040
+		00000000000000000000000000000000000000000000000000000000000000c0
+		0000000000000000000000000000000000000000000000000000000000000001
+		0000000000000000000000000000000000000000000000000000000000000040
+		0000000000000000000000000000000000000000000000000000000000000001
+		6100000000000000000000000000000000000000000000000000000000000000
+		0000000000000000000000000000000000000000000000000000000000000002
+		0000000000000000000000000000000000000000000000000000000000000040
+		0000000000000000000000000000000000000000000000000000000000000001
+		6200000000000000000000000000000000000000000000000000000000000000"
+}
+test_encode_decode! {
+	name: tuple_of_dynamic_array_of_strings_and_bytes,
+	types: [ParamType::Tuple(vec![ParamType::Array(Box::new(ParamType::String)), ParamType::Bytes])],
+	tokens: [
+		Token::Tuple(vec![
+			Token::Array(vec![Token::String("x".into())]),
+			Token::Bytes(vec![0xab]),
+		])
+	],
+	data: "
+		0000000000000000000000000000000000000000000000000000000000000020
+		0000000000000000000000000000000000000000000000000000000000000040
+		00000000000000000000000000000000000000000000000000000000000000c0
+		0000000000000000000000000000000000000000000000000000000000000001
+		0000000000000000000000000000000000000000000000000000000000000020
+		0000000000000000000000000000000000000000000000000000000000000001
+		7800000000000000000000000000000000000000000000000000000000000000
+		0000000000000000000000000000000000000000000000000000000000000001
+		ab00000000000000000000000000000000000000000000000000000000000000"
+}
 test_encode_decode! {
 	name: empty_dynamic_array,
 	types: [
@@ -6,18 +6,19 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
 use crate::{
 	errors::Error,
-	token::{StrictTokenizer, Tokenizer},
-	Uint,
+	token::{hex_digits, StrictTokenizer, Tokenizer},
+	Uint, ADDRESS_LEN, I256,
 };
-use anyhow::anyhow;
 
 /// Tries to parse string as a token. Does not require string to clearly represent the value.
 pub struct LenientTokenizer;
 
 impl Tokenizer for LenientTokenizer {
-	fn tokenize_address(value: &str) -> Result<[u8; 20], Error> {
+	fn tokenize_address(value: &str) -> Result<[u8; ADDRESS_LEN], Error> {
 		StrictTokenizer::tokenize_address(value)
 	}
 
@@ -43,34 +44,144 @@ impl Tokenizer for LenientTokenizer {
 			return result;
 		}
 
+		if let Some(hex) = hex_digits(value) {
+			return Uint::from_str_radix(hex, 16).map(Into::into).map_err(|_| Error::InvalidData);
+		}
+
+		if let Some(uint) = parse_units(value) {
+			return uint.map(Into::into);
+		}
+
+		if is_literal(value) {
+			return parse_decimal(value, 0).map(Into::into);
+		}
+
 		let uint = Uint::from_dec_str(value)?;
 		Ok(uint.into())
 	}
 
-	// We don't have a proper signed int 256-bit long type, so here we're cheating. We build a U256
-	// out of it and check that it's within the lower/upper bound of a hypothetical I256 type: half
-	// the `U256::max_value().
 	fn tokenize_int(value: &str) -> Result<[u8; 32], Error> {
 		let result = StrictTokenizer::tokenize_int(value);
 		if result.is_ok() {
 			return result;
 		}
 
-		let abs = Uint::from_dec_str(value.trim_start_matches('-'))?;
-		let max = Uint::max_value() / 2;
-		let int = if value.starts_with('-') {
-			if abs.is_zero() {
-				return Ok(abs.into());
-			} else if abs > max + 1 {
-				return Err(anyhow!("int256 parse error: Underflow").into());
-			}
-			!abs + 1 // two's complement
-		} else {
-			if abs > max {
-				return Err(anyhow!("int256 parse error: Overflow").into());
-			}
-			abs
-		};
-		Ok(int.into())
+		if hex_digits(value).is_some() {
+			return Self::tokenize_uint(value);
+		}
+
+		if is_literal(value) {
+			let (sign, abs) = value.strip_prefix('-').map_or(("", value), |abs| ("-", abs));
+			let int = I256::from_dec_str(&format!("{}{}", sign, parse_decimal(abs, 0)?))?;
+			return Ok(int.into_raw().into());
+		}
+
+		let int = I256::from_dec_str(value)?;
+		Ok(int.into_raw().into())
+	}
+}
+
+/// Decimals of the units of ether accepted as suffixes of unsigned integers, `wei` last as it
+/// is a suffix of `gwei`.
+const UNITS: [(&str, usize); 3] = [("gwei", 9), ("ether", 18), ("wei", 0)];
+
+/// Parses a decimal number with a unit of ether, e.g. `1.5 ether` or `20 gwei`, as wei.
+///
+/// Returns `None` if the value has no unit.
+fn parse_units(value: &str) -> Option<Result<Uint, Error>> {
+	let (number, decimals) = UNITS.iter().find_map(|(unit, decimals)| {
+		let number = value.strip_suffix(unit)?.trim_end();
+		Some((number, *decimals))
+	})?;
+	Some(parse_decimal(number, decimals))
+}
+
+/// Returns whether the value is a decimal literal with underscores or an exponent, e.g.
+/// `1_000_000` or `1e18`.
+fn is_literal(value: &str) -> bool {
+	value.contains(['_', 'e', 'E'])
+}
+
+/// Parses a decimal literal, e.g. `1_000`, `1.5` or `1.5e18`, scaled by `10^decimals`.
+///
+/// Fails if the scaled number is not an integer.
+fn parse_decimal(number: &str, decimals: usize) -> Result<Uint, Error> {
+	if number.starts_with('_') || number.ends_with('_') {
+		return Err(Error::InvalidData);
+	}
+	let number = number.replace('_', "");
+	let (mantissa, exponent) = match number.split_once(['e', 'E']) {
+		Some((mantissa, exponent)) => (mantissa, exponent.parse::<u8>().map_err(|_| Error::InvalidData)?),
+		None => (number.as_str(), 0),
+	};
+	let decimals = decimals + exponent as usize;
+
+	let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+	let digits = |s: &str| s.bytes().all(|byte| byte.is_ascii_digit());
+	if integer.is_empty() || !digits(integer) || !digits(fraction) || fraction.len() > decimals {
+		return Err(Error::InvalidData);
+	}
+
+	Ok(Uint::from_dec_str(&format!("{}{:0<width$}", integer, fraction, width = decimals))?)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		token::{LenientTokenizer, Tokenizer},
+		ParamType, Token, Uint, I256,
+	};
+
+	#[test]
+	fn tokenize_units() {
+		let tokenize = |value| LenientTokenizer::tokenize(&ParamType::Uint(256), value);
+		let ether = Uint::exp10(18);
+		assert_eq!(tokenize("1.5 ether").unwrap(), Token::Uint(ether * 3 / 2));
+		assert_eq!(tokenize("2ether").unwrap(), Token::Uint(ether * 2));
+		assert_eq!(tokenize("20 gwei").unwrap(), Token::Uint(Uint::exp10(10) * 2));
+		assert_eq!(tokenize("0.000000001 gwei").unwrap(), Token::Uint(1.into()));
+		assert_eq!(tokenize("7 wei").unwrap(), Token::Uint(7.into()));
+		assert!(tokenize("0.1 wei").is_err());
+		assert!(tokenize("1.5.0 ether").is_err());
+		assert!(tokenize(".5 ether").is_err());
+		assert!(tokenize("-1 ether").is_err());
+		assert!(tokenize("ether").is_err());
+	}
+
+	#[test]
+	fn tokenize_hex_prefix() {
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Uint(256), "0x1e").unwrap(), Token::Uint(30.into()));
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Int(256), "0xff").unwrap(), Token::Int(255.into()));
+		assert_eq!(
+			LenientTokenizer::tokenize(&ParamType::Uint(256), &format!("{:#x}", Uint::MAX)).unwrap(),
+			Token::Uint(Uint::MAX)
+		);
+		assert!(LenientTokenizer::tokenize(&ParamType::Uint(256), &format!("{:#x}0", Uint::MAX)).is_err());
+		assert!(LenientTokenizer::tokenize(&ParamType::Uint(8), "0x100").is_err());
+		assert!(LenientTokenizer::tokenize(&ParamType::Uint(256), "0xg").is_err());
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Uint(256), "0X1E").unwrap(), Token::Uint(30.into()));
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Int(256), "0Xff").unwrap(), Token::Int(255.into()));
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::String, "0x12").unwrap(), Token::String("0x12".into()));
+	}
+
+	#[test]
+	fn tokenize_literals() {
+		let uint = |value| LenientTokenizer::tokenize(&ParamType::Uint(256), value);
+		assert_eq!(uint("1_000_000").unwrap(), Token::Uint(1_000_000.into()));
+		assert_eq!(uint("1e18").unwrap(), Token::Uint(Uint::exp10(18)));
+		assert_eq!(uint("2.5E3").unwrap(), Token::Uint(2500.into()));
+		assert_eq!(uint("1_000e3").unwrap(), Token::Uint(1_000_000.into()));
+		assert_eq!(uint("1.5e9 gwei").unwrap(), Token::Uint(Uint::exp10(18) * 3 / 2));
+		assert!(uint("1.5e0").is_err());
+		assert!(uint("_1").is_err());
+		assert!(uint("1e").is_err());
+		assert!(uint("1e-3").is_err());
+		assert!(uint("1e78").is_err());
+
+		let int = |value| LenientTokenizer::tokenize(&ParamType::Int(256), value);
+		assert_eq!(int("-1_000").unwrap(), Token::Int(I256::from(-1000).into()));
+		assert_eq!(int("-2e3").unwrap(), Token::Int(I256::from(-2000).into()));
+		assert_eq!(int("3e2").unwrap(), Token::Int(300.into()));
+		assert!(int("--1e3").is_err());
 	}
 }
@@ -9,29 +9,54 @@
 //! ABI param and parsing for it.
 
 mod lenient;
+mod padded;
 mod strict;
 mod token;
+mod tokenizable;
+mod typed_tokens;
 
-use std::cmp::Ordering::{Equal, Less};
+use alloc::borrow::Cow;
+use anyhow::anyhow;
 
-pub use self::{lenient::LenientTokenizer, strict::StrictTokenizer, token::Token};
-use crate::{Error, ParamType};
+pub use self::{
+	lenient::LenientTokenizer,
+	padded::{LeftPadded, PaddedTokenizer, RightPadded},
+	strict::StrictTokenizer,
+	token::Token,
+	tokenizable::{Detokenize, Tokenizable, TokenizableItem, Tokenize},
+	typed_tokens::{InputTokens, TypedTokens},
+};
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{Error, ParamType, ADDRESS_LEN};
 
 /// This trait should be used to parse string values as tokens.
 pub trait Tokenizer {
 	/// Tries to parse a string as a token of given type.
+	///
+	/// Fails if an integer does not fit the bits of its type.
 	fn tokenize(param: &ParamType, value: &str) -> Result<Token, Error> {
-		match *param {
+		let token = match *param {
 			ParamType::Address => Self::tokenize_address(value).map(|a| Token::Address(a.into())),
 			ParamType::String => Self::tokenize_string(value).map(Token::String),
 			ParamType::Bool => Self::tokenize_bool(value).map(Token::Bool),
 			ParamType::Bytes => Self::tokenize_bytes(value).map(Token::Bytes),
 			ParamType::FixedBytes(len) => Self::tokenize_fixed_bytes(value, len).map(Token::FixedBytes),
+			ParamType::Function => Self::tokenize_function(value).map(Token::FixedBytes),
 			ParamType::Uint(_) => Self::tokenize_uint(value).map(Into::into).map(Token::Uint),
 			ParamType::Int(_) => Self::tokenize_int(value).map(Into::into).map(Token::Int),
+			ParamType::Ufixed(_, _) => Self::tokenize_uint(value).map(Into::into).map(Token::Uint),
+			ParamType::Fixed(_, _) => Self::tokenize_int(value).map(Into::into).map(Token::Int),
 			ParamType::Array(ref p) => Self::tokenize_array(value, p).map(Token::Array),
 			ParamType::FixedArray(ref p, len) => Self::tokenize_fixed_array(value, p, len).map(Token::FixedArray),
 			ParamType::Tuple(ref p) => Self::tokenize_struct(value, p).map(Token::Tuple),
+		}?;
+
+		match token {
+			Token::Int(_) | Token::Uint(_) if !token.type_check_strict(param) => {
+				Err(anyhow!("{} parse error: Out of range", param).into())
+			}
+			token => Ok(token),
 		}
 	}
 
@@ -46,116 +71,36 @@ pub trait Tokenizer {
 
 	/// Tried to parse a struct as a vector of tokens
 	fn tokenize_struct(value: &str, param: &[ParamType]) -> Result<Vec<Token>, Error> {
-		if !value.starts_with('(') || !value.ends_with(')') {
-			return Err(Error::InvalidData);
-		}
-
-		if value.chars().count() == 2 {
-			return Ok(vec![]);
-		}
-
-		let mut result = vec![];
-		let mut nested = 0isize;
-		let mut ignore = false;
-		let mut last_item = 1;
-		let mut params = param.iter();
-		for (pos, ch) in value.chars().enumerate() {
-			match ch {
-				'(' if !ignore => {
-					nested += 1;
-				}
-				')' if !ignore => {
-					nested -= 1;
-
-					match nested.cmp(&0) {
-						Less => {
-							return Err(Error::InvalidData);
-						}
-						Equal => {
-							let sub = &value[last_item..pos];
-							let token = Self::tokenize(params.next().ok_or(Error::InvalidData)?, sub)?;
-							result.push(token);
-							last_item = pos + 1;
-						}
-						_ => {}
-					}
-				}
-				'"' => {
-					ignore = !ignore;
-				}
-				',' if nested == 1 && !ignore => {
-					let sub = &value[last_item..pos];
-					let token = Self::tokenize(params.next().ok_or(Error::InvalidData)?, sub)?;
-					result.push(token);
-					last_item = pos + 1;
-				}
-				_ => (),
-			}
-		}
-
-		if ignore {
+		let items = Self::struct_items(value)?;
+		if items.len() != param.len() {
 			return Err(Error::InvalidData);
 		}
 
-		Ok(result)
+		param.iter().zip(items).map(|(param, item)| Self::tokenize(param, &unquote(param, item))).collect()
 	}
 
 	/// Tries to parse a value as a vector of tokens.
 	fn tokenize_array(value: &str, param: &ParamType) -> Result<Vec<Token>, Error> {
-		if !value.starts_with('[') || !value.ends_with(']') {
-			return Err(Error::InvalidData);
-		}
-
-		if value.chars().count() == 2 {
-			return Ok(vec![]);
-		}
+		Self::array_items(value)?.into_iter().map(|item| Self::tokenize(param, &unquote(param, item))).collect()
+	}
 
-		let mut result = vec![];
-		let mut nested = 0isize;
-		let mut ignore = false;
-		let mut last_item = 1;
-		for (i, ch) in value.chars().enumerate() {
-			match ch {
-				'[' if !ignore => {
-					nested += 1;
-				}
-				']' if !ignore => {
-					nested -= 1;
-					match nested.cmp(&0) {
-						Less => {
-							return Err(Error::InvalidData);
-						}
-						Equal => {
-							let sub = &value[last_item..i];
-							let token = Self::tokenize(param, sub)?;
-							result.push(token);
-							last_item = i + 1;
-						}
-						_ => {}
-					}
-				}
-				'"' => {
-					ignore = !ignore;
-				}
-				',' if nested == 1 && !ignore => {
-					let sub = &value[last_item..i];
-					let token = Self::tokenize(param, sub)?;
-					result.push(token);
-					last_item = i + 1;
-				}
-				_ => (),
-			}
-		}
+	/// Splits a struct, e.g. `(1,ab)`, into its items.
+	fn struct_items(value: &str) -> Result<Vec<&str>, Error> {
+		split_items(value, '(', ')')
+	}
 
-		if ignore {
-			return Err(Error::InvalidData);
-		}
+	/// Splits an array, e.g. `[1,2]`, into its items.
+	fn array_items(value: &str) -> Result<Vec<&str>, Error> {
+		split_items(value, '[', ']')
+	}
 
-		Ok(result)
+	/// Tries to parse a value as a function, the 20 bytes of an address followed by a 4 byte selector.
+	fn tokenize_function(value: &str) -> Result<Vec<u8>, Error> {
+		Self::tokenize_fixed_bytes(value, 24)
 	}
 
 	/// Tries to parse a value as an address.
-	fn tokenize_address(value: &str) -> Result<[u8; 20], Error>;
+	fn tokenize_address(value: &str) -> Result<[u8; ADDRESS_LEN], Error>;
 
 	/// Tries to parse a value as a string.
 	fn tokenize_string(value: &str) -> Result<String, Error>;
@@ -176,9 +121,94 @@ pub trait Tokenizer {
 	fn tokenize_int(value: &str) -> Result<[u8; 32], Error>;
 }
 
+/// Strips the `0x` or `0X` prefix of hex, if any.
+fn strip_hex_prefix(value: &str) -> &str {
+	hex_digits(value).unwrap_or(value)
+}
+
+/// Returns the digits of hex with a `0x` or `0X` prefix, `None` if there is no prefix.
+fn hex_digits(value: &str) -> Option<&str> {
+	value.strip_prefix("0x").or_else(|| value.strip_prefix("0X"))
+}
+
+/// Splits an array or tuple literal, e.g. `[a, "b,c", [d]]`, into its trimmed items.
+///
+/// Commas only separate the items outside of nested literals and quoted strings, in which `\"`
+/// and `\\` escape a quote and a backslash. A trailing comma is an error.
+pub(crate) fn split_items(value: &str, open: char, close: char) -> Result<Vec<&str>, Error> {
+	let inner = value.strip_prefix(open).and_then(|value| value.strip_suffix(close)).ok_or(Error::InvalidData)?;
+	if inner.trim().is_empty() {
+		return Ok(vec![]);
+	}
+
+	let mut items = vec![];
+	let mut nested = vec![];
+	let mut quoted = false;
+	let mut escaped = false;
+	let mut start = 0;
+	for (pos, ch) in inner.char_indices() {
+		match ch {
+			_ if escaped => escaped = false,
+			'\\' if quoted => escaped = true,
+			'"' => quoted = !quoted,
+			_ if quoted => {}
+			'[' | '(' => nested.push(ch),
+			']' if nested.pop() != Some('[') => return Err(Error::InvalidData),
+			')' if nested.pop() != Some('(') => return Err(Error::InvalidData),
+			',' if nested.is_empty() => {
+				items.push(inner[start..pos].trim());
+				start = pos + 1;
+			}
+			_ => {}
+		}
+	}
+
+	if quoted || !nested.is_empty() {
+		return Err(Error::InvalidData);
+	}
+
+	match inner[start..].trim() {
+		"" if !items.is_empty() => Err(Error::InvalidData),
+		last => {
+			items.push(last);
+			Ok(items)
+		}
+	}
+}
+
+/// Strips the quotes around string elements of arrays and structs, e.g. `["a,b","c"]`, and
+/// unescapes the quotes and backslashes inside them.
+pub(crate) fn unquote<'a>(param: &ParamType, value: &'a str) -> Cow<'a, str> {
+	let inner = match *param {
+		ParamType::String if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') => {
+			&value[1..value.len() - 1]
+		}
+		_ => return Cow::Borrowed(value),
+	};
+	if !inner.contains('\\') {
+		return Cow::Borrowed(inner);
+	}
+
+	let mut unescaped = String::with_capacity(inner.len());
+	let mut chars = inner.chars();
+	while let Some(ch) = chars.next() {
+		match ch {
+			'\\' => match chars.next() {
+				Some(escaped @ ('"' | '\\')) => unescaped.push(escaped),
+				next => {
+					unescaped.push(ch);
+					unescaped.extend(next);
+				}
+			},
+			ch => unescaped.push(ch),
+		}
+	}
+	Cow::Owned(unescaped)
+}
+
 #[cfg(test)]
 mod test {
-	use super::{LenientTokenizer, ParamType, Tokenizer};
+	use super::{LenientTokenizer, ParamType, Token, Tokenizer};
 	#[test]
 	fn single_quoted_in_array_must_error() {
 		assert!(LenientTokenizer::tokenize_array("[1,\"0,false]", &ParamType::Bool).is_err());
@@ -187,4 +217,91 @@ mod test {
 		assert!(LenientTokenizer::tokenize_array("[1,\"0\",false]", &ParamType::Bool).is_err());
 		assert!(LenientTokenizer::tokenize_array("[1,0]", &ParamType::Bool).is_ok());
 	}
+
+	#[test]
+	fn tokenize_quoted_strings_in_array() {
+		assert_eq!(
+			LenientTokenizer::tokenize(&ParamType::Array(Box::new(ParamType::String)), r#"["a,b","[c]",d]"#).unwrap(),
+			Token::Array(vec![Token::String("a,b".into()), Token::String("[c]".into()), Token::String("d".into())])
+		);
+		assert_eq!(
+			LenientTokenizer::tokenize(&ParamType::FixedArray(Box::new(ParamType::String), 2), r#"["",x]"#).unwrap(),
+			Token::FixedArray(vec![Token::String("".into()), Token::String("x".into())])
+		);
+		assert!(LenientTokenizer::tokenize(&ParamType::Array(Box::new(ParamType::Bytes)), "[1234,]").is_err());
+		assert!(LenientTokenizer::tokenize(&ParamType::Array(Box::new(ParamType::String)), r#"["a",]"#).is_err());
+		assert!(LenientTokenizer::tokenize(&ParamType::Tuple(vec![ParamType::Bool]), "(true,)").is_err());
+	}
+
+	#[test]
+	fn tokenize_tuples() {
+		let kind = ParamType::Tuple(vec![
+			ParamType::Uint(8),
+			ParamType::String,
+			ParamType::Tuple(vec![ParamType::Bool, ParamType::Array(Box::new(ParamType::Uint(8)))]),
+		]);
+		assert_eq!(
+			LenientTokenizer::tokenize(&kind, r#"( 1, "a, (b)", (true, [2, 3]) )"#).unwrap(),
+			Token::Tuple(vec![
+				Token::Uint(1.into()),
+				Token::String("a, (b)".into()),
+				Token::Tuple(vec![Token::Bool(true), Token::Array(vec![Token::Uint(2.into()), Token::Uint(3.into())])]),
+			])
+		);
+		assert!(LenientTokenizer::tokenize(&kind, r#"(1, "a")"#).is_err());
+		assert!(LenientTokenizer::tokenize(&kind, r#"(1, "a", (true, []), 2)"#).is_err());
+		assert!(LenientTokenizer::tokenize(&kind, "(").is_err());
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Tuple(vec![]), "( )").unwrap(), Token::Tuple(vec![]));
+
+		let kind = ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Uint(8), ParamType::String])));
+		assert_eq!(
+			LenientTokenizer::tokenize(&kind, "[(1, é), (2, ü)]").unwrap(),
+			Token::Array(vec![
+				Token::Tuple(vec![Token::Uint(1.into()), Token::String("é".into())]),
+				Token::Tuple(vec![Token::Uint(2.into()), Token::String("ü".into())]),
+			])
+		);
+	}
+
+	#[test]
+	fn tokenize_nested_arrays() {
+		let kind = ParamType::Array(Box::new(ParamType::Array(Box::new(ParamType::String))));
+		assert_eq!(
+			LenientTokenizer::tokenize(&kind, r#"[ ["a", "b,]" ] , [ ] , ["x\"y\\z", "(" ] ]"#).unwrap(),
+			Token::Array(vec![
+				Token::Array(vec![Token::String("a".into()), Token::String("b,]".into())]),
+				Token::Array(vec![]),
+				Token::Array(vec![Token::String(r#"x"y\z"#.into()), Token::String("(".into())]),
+			])
+		);
+		assert!(LenientTokenizer::tokenize(&kind, "[[a],[b]").is_err());
+		assert!(LenientTokenizer::tokenize(&kind, "[[a)]").is_err());
+		assert!(LenientTokenizer::tokenize(&kind, "[[a]][b]").is_err());
+		assert!(LenientTokenizer::tokenize(&kind, r#"[["a\"]]"#).is_err());
+	}
+
+	#[test]
+	fn tokenize_out_of_range() {
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Uint(8), "255").unwrap(), Token::Uint(255.into()));
+		#[cfg(feature = "std")]
+		assert_eq!(
+			LenientTokenizer::tokenize(&ParamType::Uint(8), "300").unwrap_err().to_string(),
+			"uint8 parse error: Out of range"
+		);
+		assert!(LenientTokenizer::tokenize(&ParamType::Int(8), "-128").is_ok());
+		assert!(LenientTokenizer::tokenize(&ParamType::Int(8), "-129").is_err());
+		assert!(LenientTokenizer::tokenize(&ParamType::Array(Box::new(ParamType::Int(8))), "[1,128]").is_err());
+		assert!(LenientTokenizer::tokenize(&ParamType::Uint(256), &format!("{}0", crate::Uint::MAX)).is_err());
+	}
+
+	#[test]
+	fn tokenize_negative_int() {
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Int(256), "-1").unwrap(), Token::Int([0xff; 32].into()));
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Int(8), "-1").unwrap(), Token::Int([0xff; 32].into()));
+		assert_eq!(
+			LenientTokenizer::tokenize(&ParamType::Array(Box::new(ParamType::Int(64))), "[-2,3]").unwrap(),
+			Token::Array(vec![Token::Int(crate::Int::MAX - 1), Token::Int(3.into())])
+		);
+		assert!(LenientTokenizer::tokenize(&ParamType::Int(256), "-").is_err());
+	}
 }
@@ -0,0 +1,144 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{errors::Error, token::Tokenizer, ADDRESS_LEN};
+use core::marker::PhantomData;
+
+/// Tokenizer which pads fixed bytes shorter than their type with zeros, on the left if `LEFT`
+/// and on the right otherwise, and otherwise parses values like `T`, splitting arrays and tuples
+/// into items like it too. Functions, an address followed by a selector, are never padded.
+///
+/// ```
+/// use ethabi::{token::{LeftPadded, LenientTokenizer, Token, Tokenizer}, ParamType};
+///
+/// let token = LeftPadded::<LenientTokenizer>::tokenize(&ParamType::FixedBytes(4), "0x1234").unwrap();
+/// assert_eq!(token, Token::FixedBytes(vec![0, 0, 0x12, 0x34]));
+/// ```
+pub struct PaddedTokenizer<T, const LEFT: bool>(PhantomData<T>);
+
+/// Tokenizer padding fixed bytes on the left, e.g. `0x1234` as `bytes4` to `0x00001234`.
+pub type LeftPadded<T> = PaddedTokenizer<T, true>;
+
+/// Tokenizer padding fixed bytes on the right, e.g. `0x1234` as `bytes4` to `0x12340000`.
+pub type RightPadded<T> = PaddedTokenizer<T, false>;
+
+impl<T: Tokenizer, const LEFT: bool> Tokenizer for PaddedTokenizer<T, LEFT> {
+	fn struct_items(value: &str) -> Result<Vec<&str>, Error> {
+		T::struct_items(value)
+	}
+
+	fn array_items(value: &str) -> Result<Vec<&str>, Error> {
+		T::array_items(value)
+	}
+
+	fn tokenize_function(value: &str) -> Result<Vec<u8>, Error> {
+		T::tokenize_function(value)
+	}
+
+	fn tokenize_address(value: &str) -> Result<[u8; ADDRESS_LEN], Error> {
+		T::tokenize_address(value)
+	}
+
+	fn tokenize_string(value: &str) -> Result<String, Error> {
+		T::tokenize_string(value)
+	}
+
+	fn tokenize_bool(value: &str) -> Result<bool, Error> {
+		T::tokenize_bool(value)
+	}
+
+	fn tokenize_bytes(value: &str) -> Result<Vec<u8>, Error> {
+		T::tokenize_bytes(value)
+	}
+
+	fn tokenize_fixed_bytes(value: &str, len: usize) -> Result<Vec<u8>, Error> {
+		let bytes = T::tokenize_bytes(value)?;
+		if bytes.len() > len {
+			return Err(Error::InvalidData);
+		}
+
+		let padding = vec![0; len - bytes.len()];
+		match LEFT {
+			true => Ok([padding, bytes].concat()),
+			false => Ok([bytes, padding].concat()),
+		}
+	}
+
+	fn tokenize_uint(value: &str) -> Result<[u8; 32], Error> {
+		T::tokenize_uint(value)
+	}
+
+	fn tokenize_int(value: &str) -> Result<[u8; 32], Error> {
+		T::tokenize_int(value)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{LeftPadded, RightPadded};
+	use crate::{
+		token::{LenientTokenizer, StrictTokenizer, Token, Tokenizer},
+		ParamType,
+	};
+
+	#[test]
+	fn tokenize_padded_fixed_bytes() {
+		let kind = ParamType::FixedBytes(4);
+		assert_eq!(
+			RightPadded::<StrictTokenizer>::tokenize(&kind, "1234").unwrap(),
+			Token::FixedBytes(vec![0x12, 0x34, 0, 0])
+		);
+		assert_eq!(
+			LeftPadded::<StrictTokenizer>::tokenize(&kind, "12345678").unwrap(),
+			Token::FixedBytes(vec![0x12, 0x34, 0x56, 0x78])
+		);
+		assert!(LeftPadded::<StrictTokenizer>::tokenize(&kind, "1234567890").is_err());
+		assert!(StrictTokenizer::tokenize(&kind, "1234").is_err());
+
+		let kind = ParamType::Array(Box::new(ParamType::FixedBytes(2)));
+		assert_eq!(
+			LeftPadded::<StrictTokenizer>::tokenize(&kind, "[12,3456]").unwrap(),
+			Token::Array(vec![Token::FixedBytes(vec![0, 0x12]), Token::FixedBytes(vec![0x34, 0x56])])
+		);
+	}
+
+	#[test]
+	fn tokenize_padded_like_inner_tokenizer() {
+		let kind = ParamType::Array(Box::new(ParamType::FixedBytes(2)));
+		assert!(LeftPadded::<StrictTokenizer>::tokenize(&kind, "[12,,34]").is_err());
+		assert!(RightPadded::<StrictTokenizer>::tokenize(&kind, "[12,,34]").is_err());
+		assert_eq!(
+			LeftPadded::<LenientTokenizer>::tokenize(&kind, "[12,,34]").unwrap(),
+			Token::Array(vec![
+				Token::FixedBytes(vec![0, 0x12]),
+				Token::FixedBytes(vec![0, 0]),
+				Token::FixedBytes(vec![0, 0x34])
+			])
+		);
+
+		let kind = ParamType::Tuple(vec![ParamType::FixedBytes(2), ParamType::Bool]);
+		assert!(LeftPadded::<StrictTokenizer>::tokenize(&kind, "(12,)").is_err());
+		assert_eq!(
+			LeftPadded::<StrictTokenizer>::tokenize(&kind, "(12,true)").unwrap(),
+			Token::Tuple(vec![Token::FixedBytes(vec![0, 0x12]), Token::Bool(true)])
+		);
+	}
+
+	#[test]
+	fn tokenize_unpadded_function() {
+		let function = "2222222222222222222222222222222222222222aabbccdd";
+		assert_eq!(
+			LeftPadded::<StrictTokenizer>::tokenize(&ParamType::Function, function).unwrap(),
+			Token::FixedBytes(hex::decode(function).unwrap())
+		);
+		assert!(LeftPadded::<StrictTokenizer>::tokenize(&ParamType::Function, "aabbccdd").is_err());
+		assert!(RightPadded::<StrictTokenizer>::tokenize(&ParamType::Function, &function[..46]).is_err());
+	}
+}
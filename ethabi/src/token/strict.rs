@@ -6,21 +6,43 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::{errors::Error, token::Tokenizer};
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{
+	checksum::{checksum_address, has_valid_checksum},
+	errors::Error,
+	token::{split_items, strip_hex_prefix, Tokenizer},
+	ADDRESS_LEN,
+};
+use anyhow::anyhow;
 
 /// Tries to parse string as a token. Require string to clearly represent the value.
 pub struct StrictTokenizer;
 
 impl Tokenizer for StrictTokenizer {
-	fn tokenize_address(value: &str) -> Result<[u8; 20], Error> {
+	/// Rejects empty items, e.g. the second one of `(1234,,ab)`, an empty string being `""`.
+	fn struct_items(value: &str) -> Result<Vec<&str>, Error> {
+		non_empty_items(value, '(', ')')
+	}
+
+	/// Rejects empty items, e.g. the second one of `[1234,,ab]`, an empty string being `""`.
+	fn array_items(value: &str) -> Result<Vec<&str>, Error> {
+		non_empty_items(value, '[', ']')
+	}
+
+	/// Verifies the EIP-55 checksum of mixed-case addresses.
+	fn tokenize_address(value: &str) -> Result<[u8; ADDRESS_LEN], Error> {
+		let value = strip_hex_prefix(value);
 		let hex: Vec<u8> = hex::decode(value)?;
-		match hex.len() == 20 {
-			false => Err(Error::InvalidData),
-			true => {
-				let mut address = [0u8; 20];
-				address.copy_from_slice(&hex);
-				Ok(address)
-			}
+		if hex.len() != ADDRESS_LEN {
+			return Err(Error::InvalidData);
+		}
+
+		let mut address = [0u8; ADDRESS_LEN];
+		address.copy_from_slice(&hex);
+		match has_valid_checksum(value, &address.into()) {
+			true => Ok(address),
+			false => Err(anyhow!("Address checksum error: expected {}", checksum_address(&address.into())).into()),
 		}
 	}
 
@@ -37,11 +59,11 @@ impl Tokenizer for StrictTokenizer {
 	}
 
 	fn tokenize_bytes(value: &str) -> Result<Vec<u8>, Error> {
-		hex::decode(value).map_err(Into::into)
+		hex::decode(strip_hex_prefix(value)).map_err(Into::into)
 	}
 
 	fn tokenize_fixed_bytes(value: &str, len: usize) -> Result<Vec<u8>, Error> {
-		let hex: Vec<u8> = hex::decode(value)?;
+		let hex: Vec<u8> = hex::decode(strip_hex_prefix(value))?;
 		match hex.len() == len {
 			true => Ok(hex),
 			false => Err(Error::InvalidData),
@@ -49,7 +71,7 @@ impl Tokenizer for StrictTokenizer {
 	}
 
 	fn tokenize_uint(value: &str) -> Result<[u8; 32], Error> {
-		let hex: Vec<u8> = hex::decode(value)?;
+		let hex: Vec<u8> = hex::decode(strip_hex_prefix(value))?;
 		match hex.len() == 32 {
 			true => {
 				let mut uint = [0u8; 32];
@@ -65,6 +87,15 @@ impl Tokenizer for StrictTokenizer {
 	}
 }
 
+/// Same as `split_items`, failing if an item is empty.
+fn non_empty_items(value: &str, open: char, close: char) -> Result<Vec<&str>, Error> {
+	let items = split_items(value, open, close)?;
+	match items.iter().any(|item| item.is_empty()) {
+		true => Err(Error::InvalidData),
+		false => Ok(items),
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::{
@@ -84,6 +115,59 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn tokenize_hex_prefix() {
+		assert_eq!(
+			StrictTokenizer::tokenize(&ParamType::Address, "0x1111111111111111111111111111111111111111").unwrap(),
+			Token::Address([0x11u8; 20].into())
+		);
+		assert_eq!(StrictTokenizer::tokenize(&ParamType::Bytes, "0x1234").unwrap(), Token::Bytes(vec![0x12, 0x34]));
+		assert_eq!(StrictTokenizer::tokenize(&ParamType::Bytes, "0x").unwrap(), Token::Bytes(vec![]));
+		assert_eq!(StrictTokenizer::tokenize(&ParamType::Bytes, "0X12ab").unwrap(), Token::Bytes(vec![0x12, 0xab]));
+		assert_eq!(
+			StrictTokenizer::tokenize(&ParamType::Address, "0X1111111111111111111111111111111111111111").unwrap(),
+			Token::Address([0x11u8; 20].into())
+		);
+		assert_eq!(
+			StrictTokenizer::tokenize(&ParamType::FixedBytes(2), "0x1234").unwrap(),
+			Token::FixedBytes(vec![0x12, 0x34])
+		);
+		assert_eq!(
+			StrictTokenizer::tokenize(
+				&ParamType::Uint(256),
+				"0x1111111111111111111111111111111111111111111111111111111111111111"
+			)
+			.unwrap(),
+			Token::Uint([0x11u8; 32].into())
+		);
+		assert_eq!(
+			StrictTokenizer::tokenize(&ParamType::Array(Box::new(ParamType::Bytes)), "[0x12, 34]").unwrap(),
+			Token::Array(vec![Token::Bytes(vec![0x12]), Token::Bytes(vec![0x34])])
+		);
+		assert!(StrictTokenizer::tokenize(&ParamType::Bytes, "0x0x12").is_err());
+	}
+
+	#[test]
+	fn tokenize_checksum_address() {
+		let address = Token::Address("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse().unwrap());
+		assert_eq!(
+			StrictTokenizer::tokenize(&ParamType::Address, "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").unwrap(),
+			address
+		);
+		assert_eq!(
+			StrictTokenizer::tokenize(&ParamType::Address, "5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED").unwrap(),
+			address
+		);
+		#[cfg(feature = "std")]
+		assert_eq!(
+			StrictTokenizer::tokenize(&ParamType::Address, "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD")
+				.unwrap_err()
+				.to_string(),
+			"Address checksum error: expected 5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+		);
+		assert_eq!(format!("{:#}", Token::Array(vec![address])), "[5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed]");
+	}
+
 	#[test]
 	fn tokenize_string() {
 		assert_eq!(
@@ -194,4 +278,49 @@ mod tests {
 			])
 		);
 	}
+
+	#[test]
+	fn tokenize_hex_arrays() {
+		let uint = "1111111111111111111111111111111111111111111111111111111111111111";
+		assert_eq!(
+			StrictTokenizer::tokenize(
+				&ParamType::Array(Box::new(ParamType::Uint(256))),
+				&format!("[{}, {}]", uint, uint)
+			)
+			.unwrap(),
+			Token::Array(vec![Token::Uint([0x11u8; 32].into()), Token::Uint([0x11u8; 32].into())])
+		);
+		assert!(StrictTokenizer::tokenize(&ParamType::Array(Box::new(ParamType::Bytes)), "[1234,,ab]").is_err());
+		assert!(
+			StrictTokenizer::tokenize(&ParamType::Tuple(vec![ParamType::Bytes, ParamType::Bool]), "(,true)").is_err()
+		);
+		assert_eq!(
+			StrictTokenizer::tokenize(&ParamType::Array(Box::new(ParamType::String)), r#"["",a]"#).unwrap(),
+			Token::Array(vec![Token::String("".into()), Token::String("a".into())])
+		);
+		assert!(StrictTokenizer::tokenize(&ParamType::Array(Box::new(ParamType::Uint(256))), "[1]").is_err());
+		assert!(StrictTokenizer::tokenize(&ParamType::Array(Box::new(ParamType::Bytes)), "[0x1]").is_err());
+	}
+
+	#[test]
+	fn tokenize_fixed_arrays() {
+		let address = "2222222222222222222222222222222222222222";
+		let kind = ParamType::FixedArray(Box::new(ParamType::Address), 2);
+		assert_eq!(
+			StrictTokenizer::tokenize(&kind, &format!("[{},{}]", address, address)).unwrap(),
+			Token::FixedArray(vec![Token::Address([0x22u8; 20].into()), Token::Address([0x22u8; 20].into())])
+		);
+		assert!(StrictTokenizer::tokenize(&kind, &format!("[{}]", address)).is_err());
+		assert!(StrictTokenizer::tokenize(&kind, &format!("[{},{},{}]", address, address, address)).is_err());
+
+		let kind = ParamType::FixedArray(Box::new(ParamType::FixedArray(Box::new(ParamType::Bool), 2)), 2);
+		assert_eq!(
+			StrictTokenizer::tokenize(&kind, "[[1,0],[0,1]]").unwrap(),
+			Token::FixedArray(vec![
+				Token::FixedArray(vec![Token::Bool(true), Token::Bool(false)]),
+				Token::FixedArray(vec![Token::Bool(false), Token::Bool(true)]),
+			])
+		);
+		assert!(StrictTokenizer::tokenize(&kind, "[[1,0],[0]]").is_err());
+	}
 }
@@ -7,11 +7,18 @@
 // except according to those terms.
 
 //! Ethereum ABI params.
-use crate::{Address, Bytes, FixedBytes, Int, ParamType, Uint};
-use std::fmt;
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{
+	checksum::checksum_address, errors::TypeMismatch, Address, Bytes, Error, FixedBytes, Int, ParamType, Uint, I256,
+};
+use core::{cmp::Ordering, convert::TryFrom, fmt};
 
 /// Ethereum ABI params.
-#[derive(Debug, PartialEq, Clone)]
+///
+/// Tokens of the same kind are ordered by value, with `Int` compared as a signed
+/// integer. Tokens of different kinds are ordered by their position in this enum.
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Token {
 	/// Address.
 	///
@@ -63,21 +70,23 @@ pub enum Token {
 	Tuple(Vec<Token>),
 }
 
+/// Displays the token, with EIP-55 checksums of addresses in the alternate format, `{:#}`.
 impl fmt::Display for Token {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match *self {
 			Token::Bool(b) => write!(f, "{}", b),
 			Token::String(ref s) => write!(f, "{}", s),
+			Token::Address(ref a) if f.alternate() => write!(f, "{}", checksum_address(a)),
 			Token::Address(ref a) => write!(f, "{:x}", a),
 			Token::Bytes(ref bytes) | Token::FixedBytes(ref bytes) => write!(f, "{}", hex::encode(&bytes)),
 			Token::Uint(ref i) | Token::Int(ref i) => write!(f, "{:x}", i),
 			Token::Array(ref arr) | Token::FixedArray(ref arr) => {
-				let s = arr.iter().map(|ref t| format!("{}", t)).collect::<Vec<String>>().join(",");
+				let s = arr.iter().map(|t| display(t, f.alternate())).collect::<Vec<String>>().join(",");
 
 				write!(f, "[{}]", s)
 			}
 			Token::Tuple(ref s) => {
-				let s = s.iter().map(|ref t| format!("{}", t)).collect::<Vec<String>>().join(",");
+				let s = s.iter().map(|t| display(t, f.alternate())).collect::<Vec<String>>().join(",");
 
 				write!(f, "({})", s)
 			}
@@ -85,47 +94,105 @@ impl fmt::Display for Token {
 	}
 }
 
+fn display(token: &Token, alternate: bool) -> String {
+	match alternate {
+		true => format!("{:#}", token),
+		false => format!("{}", token),
+	}
+}
+
+impl Ord for Token {
+	fn cmp(&self, other: &Self) -> Ordering {
+		match (self, other) {
+			(Token::Address(a), Token::Address(b)) => a.cmp(b),
+			(Token::FixedBytes(a), Token::FixedBytes(b)) | (Token::Bytes(a), Token::Bytes(b)) => a.cmp(b),
+			(Token::Int(a), Token::Int(b)) => I256::from_raw(*a).cmp(&I256::from_raw(*b)),
+			(Token::Uint(a), Token::Uint(b)) => a.cmp(b),
+			(Token::Bool(a), Token::Bool(b)) => a.cmp(b),
+			(Token::String(a), Token::String(b)) => a.cmp(b),
+			(Token::FixedArray(a), Token::FixedArray(b))
+			| (Token::Array(a), Token::Array(b))
+			| (Token::Tuple(a), Token::Tuple(b)) => a.cmp(b),
+			_ => self.rank().cmp(&other.rank()),
+		}
+	}
+}
+
+impl PartialOrd for Token {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
 impl Token {
+	/// Position of the token kind, used for ordering tokens of different kinds.
+	fn rank(&self) -> u8 {
+		match *self {
+			Token::Address(_) => 0,
+			Token::FixedBytes(_) => 1,
+			Token::Bytes(_) => 2,
+			Token::Int(_) => 3,
+			Token::Uint(_) => 4,
+			Token::Bool(_) => 5,
+			Token::String(_) => 6,
+			Token::FixedArray(_) => 7,
+			Token::Array(_) => 8,
+			Token::Tuple(_) => 9,
+		}
+	}
+
 	/// Check whether the type of the token matches the given parameter type.
 	///
 	/// Numeric types (`Int` and `Uint`) type check if the size of the token
 	/// type is of greater or equal size than the provided parameter type.
 	pub fn type_check(&self, param_type: &ParamType) -> bool {
+		self.type_check_with(param_type, false)
+	}
+
+	/// Same as `type_check`, but numeric types (`Int` and `Uint`) only type check if their
+	/// value fits the bits of the parameter type, e.g. `Uint(300)` does not type check as `uint8`.
+	pub fn type_check_strict(&self, param_type: &ParamType) -> bool {
+		self.type_check_with(param_type, true)
+	}
+
+	fn type_check_with(&self, param_type: &ParamType, strict: bool) -> bool {
 		match *self {
 			Token::Address(_) => *param_type == ParamType::Address,
 			Token::Bytes(_) => *param_type == ParamType::Bytes,
-			Token::Int(_) => {
-				matches!(*param_type, ParamType::Int(_))
-			}
-			Token::Uint(_) => {
-				matches!(*param_type, ParamType::Uint(_))
-			}
+			Token::Int(int) => match *param_type {
+				ParamType::Int(bits) | ParamType::Fixed(bits, _) => {
+					!strict || I256::from_raw_bits(int, bits) == I256::from_raw(int)
+				}
+				_ => false,
+			},
+			Token::Uint(uint) => match *param_type {
+				ParamType::Uint(bits) | ParamType::Ufixed(bits, _) => !strict || uint.bits() <= bits,
+				_ => false,
+			},
 			Token::Bool(_) => *param_type == ParamType::Bool,
 			Token::String(_) => *param_type == ParamType::String,
-			Token::FixedBytes(ref bytes) => {
-				if let ParamType::FixedBytes(size) = *param_type {
-					size >= bytes.len()
-				} else {
-					false
-				}
-			}
+			Token::FixedBytes(ref bytes) => match *param_type {
+				ParamType::FixedBytes(size) => size >= bytes.len(),
+				ParamType::Function => bytes.len() == 24,
+				_ => false,
+			},
 			Token::Array(ref tokens) => {
 				if let ParamType::Array(ref param_type) = *param_type {
-					tokens.iter().all(|t| t.type_check(param_type))
+					tokens.iter().all(|t| t.type_check_with(param_type, strict))
 				} else {
 					false
 				}
 			}
 			Token::FixedArray(ref tokens) => {
 				if let ParamType::FixedArray(ref param_type, size) = *param_type {
-					size == tokens.len() && tokens.iter().all(|t| t.type_check(param_type))
+					size == tokens.len() && tokens.iter().all(|t| t.type_check_with(param_type, strict))
 				} else {
 					false
 				}
 			}
 			Token::Tuple(ref tokens) => {
 				if let ParamType::Tuple(ref param_type) = *param_type {
-					tokens.iter().enumerate().all(|(i, t)| t.type_check(&param_type[i]))
+					tokens.iter().enumerate().all(|(i, t)| t.type_check_with(&param_type[i], strict))
 				} else {
 					false
 				}
@@ -165,6 +232,11 @@ impl Token {
 		}
 	}
 
+	/// Converts token to...
+	pub fn into_i256(self) -> Option<I256> {
+		self.into_int().map(I256::from_raw)
+	}
+
 	/// Converts token to...
 	pub fn into_uint(self) -> Option<Uint> {
 		match self {
@@ -197,6 +269,14 @@ impl Token {
 		}
 	}
 
+	/// Converts token to...
+	pub fn into_tuple(self) -> Option<Vec<Token>> {
+		match self {
+			Token::Tuple(tokens) => Some(tokens),
+			_ => None,
+		}
+	}
+
 	/// Converts token to...
 	pub fn into_array(self) -> Option<Vec<Token>> {
 		match self {
@@ -212,6 +292,58 @@ impl Token {
 		}
 	}
 
+	/// Returns the positions where the tokens do not match the given parameter types, with
+	/// the types expected and found, empty if every token passes `type_check_strict`.
+	pub fn type_mismatches(tokens: &[Token], param_types: &[ParamType]) -> Vec<TypeMismatch> {
+		TypeMismatch::of_tokens(tokens, param_types)
+	}
+
+	/// Same as `types_check`, failing with `Error::TypeMismatch` on mismatches.
+	pub(crate) fn check_types(tokens: &[Token], param_types: &[ParamType]) -> crate::Result<()> {
+		match Token::type_mismatches(tokens, param_types) {
+			mismatches if mismatches.is_empty() => Ok(()),
+			mismatches => Err(Error::TypeMismatch(mismatches)),
+		}
+	}
+
+	/// Returns the smallest type the token type checks against, e.g. `uint256` for any
+	/// `Uint`, with `()` as the element type of empty arrays.
+	pub fn param_type(&self) -> ParamType {
+		match self {
+			Token::Address(_) => ParamType::Address,
+			Token::FixedBytes(bytes) => ParamType::FixedBytes(bytes.len()),
+			Token::Bytes(_) => ParamType::Bytes,
+			Token::Int(_) => ParamType::Int(256),
+			Token::Uint(_) => ParamType::Uint(256),
+			Token::Bool(_) => ParamType::Bool,
+			Token::String(_) => ParamType::String,
+			Token::FixedArray(tokens) => ParamType::FixedArray(Box::new(element_type(tokens)), tokens.len()),
+			Token::Array(tokens) => ParamType::Array(Box::new(element_type(tokens))),
+			Token::Tuple(tokens) => ParamType::Tuple(tokens.iter().map(Token::param_type).collect()),
+		}
+	}
+
+	/// Sorts the tokens in ascending order, keeping equal tokens in their original order.
+	pub fn sort(tokens: &mut [Token]) {
+		tokens.sort();
+	}
+
+	/// Sorts the tokens in ascending order and removes duplicates.
+	pub fn sort_dedup(tokens: &mut Vec<Token>) {
+		tokens.sort();
+		tokens.dedup();
+	}
+
+	/// Check if the tokens are in ascending order.
+	pub fn is_sorted(tokens: &[Token]) -> bool {
+		tokens.windows(2).all(|pair| pair[0] <= pair[1])
+	}
+
+	/// Check if the tokens are in ascending order and contain no duplicates.
+	pub fn is_strictly_sorted(tokens: &[Token]) -> bool {
+		tokens.windows(2).all(|pair| pair[0] < pair[1])
+	}
+
 	/// Check if the token is a dynamic type resulting in prefixed encoding
 	pub fn is_dynamic(&self) -> bool {
 		match self {
@@ -223,9 +355,170 @@ impl Token {
 	}
 }
 
+fn element_type(tokens: &[Token]) -> ParamType {
+	tokens.first().map_or(ParamType::Tuple(vec![]), Token::param_type)
+}
+
+impl From<bool> for Token {
+	fn from(value: bool) -> Self {
+		Token::Bool(value)
+	}
+}
+
+impl From<Address> for Token {
+	fn from(value: Address) -> Self {
+		Token::Address(value)
+	}
+}
+
+impl From<&str> for Token {
+	fn from(value: &str) -> Self {
+		Token::String(value.to_owned())
+	}
+}
+
+impl From<String> for Token {
+	fn from(value: String) -> Self {
+		Token::String(value)
+	}
+}
+
+impl From<Vec<u8>> for Token {
+	fn from(value: Vec<u8>) -> Self {
+		Token::Bytes(value)
+	}
+}
+
+/// Converts to `Token::FixedBytes`, failing to compile unless `N` is from 1 to 32.
+impl<const N: usize> From<[u8; N]> for Token {
+	fn from(value: [u8; N]) -> Self {
+		const { assert!(N >= 1 && N <= 32, "fixed bytes have 1 to 32 bytes") };
+		Token::FixedBytes(value.to_vec())
+	}
+}
+
+impl TryFrom<Token> for bool {
+	type Error = Error;
+
+	fn try_from(token: Token) -> Result<Self, Self::Error> {
+		token.into_bool().ok_or(Error::InvalidData)
+	}
+}
+
+impl TryFrom<Token> for Address {
+	type Error = Error;
+
+	fn try_from(token: Token) -> Result<Self, Self::Error> {
+		token.into_address().ok_or(Error::InvalidData)
+	}
+}
+
+impl TryFrom<Token> for String {
+	type Error = Error;
+
+	fn try_from(token: Token) -> Result<Self, Self::Error> {
+		token.into_string().ok_or(Error::InvalidData)
+	}
+}
+
+impl TryFrom<Token> for Vec<u8> {
+	type Error = Error;
+
+	fn try_from(token: Token) -> Result<Self, Self::Error> {
+		token.into_bytes().ok_or(Error::InvalidData)
+	}
+}
+
+impl<const N: usize> TryFrom<Token> for [u8; N] {
+	type Error = Error;
+
+	fn try_from(token: Token) -> Result<Self, Self::Error> {
+		let bytes = token.into_fixed_bytes().ok_or(Error::InvalidData)?;
+		<[u8; N]>::try_from(bytes.as_slice()).map_err(|_| Error::InvalidData)
+	}
+}
+
+macro_rules! impl_uint_conversions {
+	($($ty:ty),*) => {
+		$(
+			impl From<$ty> for Token {
+				fn from(value: $ty) -> Self {
+					Token::Uint(value.into())
+				}
+			}
+
+			impl TryFrom<Token> for $ty {
+				type Error = Error;
+
+				fn try_from(token: Token) -> Result<Self, Self::Error> {
+					let value = token.into_uint().ok_or(Error::InvalidData)?;
+					if value.bits() > 128 {
+						return Err(Error::InvalidData);
+					}
+					<$ty>::try_from(value.low_u128()).map_err(|_| Error::InvalidData)
+				}
+			}
+		)*
+	};
+}
+
+impl_uint_conversions!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_int_conversions {
+	($($ty:ty),*) => {
+		$(
+			impl From<$ty> for Token {
+				fn from(value: $ty) -> Self {
+					I256::from(value).into()
+				}
+			}
+
+			impl TryFrom<Token> for $ty {
+				type Error = Error;
+
+				fn try_from(token: Token) -> Result<Self, Self::Error> {
+					<$ty>::try_from(token.into_i256().ok_or(Error::InvalidData)?)
+				}
+			}
+		)*
+	};
+}
+
+impl_int_conversions!(i8, i16, i32, i64, i128, isize);
+
 #[cfg(test)]
 mod tests {
-	use crate::{ParamType, Token};
+	use std::convert::TryFrom;
+
+	use crate::{Address, ParamType, Token, TypeMismatch, Uint, I256};
+
+	#[test]
+	fn test_type_mismatches() {
+		let tokens = [Token::Bool(false), Token::Uint(0.into()), Token::Array(vec![])];
+		let types = [ParamType::Uint(32), ParamType::Uint(8)];
+		assert_eq!(
+			Token::type_mismatches(&tokens, &types),
+			[
+				TypeMismatch { index: 0, expected: Some(ParamType::Uint(32)), found: Some(ParamType::Bool) },
+				TypeMismatch {
+					index: 2,
+					expected: None,
+					found: Some(ParamType::Array(Box::new(ParamType::Tuple(vec![])))),
+				},
+			]
+		);
+		assert_eq!(
+			Token::type_mismatches(&tokens[..1], &[ParamType::Bool, ParamType::Address]),
+			[TypeMismatch { index: 1, expected: Some(ParamType::Address), found: None }]
+		);
+		assert!(Token::type_mismatches(&tokens[1..2], &types[1..]).is_empty());
+
+		assert_eq!(
+			Token::Tuple(vec![Token::FixedBytes(vec![0; 4]), Token::FixedArray(vec![Token::String("a".to_owned())])])
+				.param_type(),
+			ParamType::Tuple(vec![ParamType::FixedBytes(4), ParamType::FixedArray(Box::new(ParamType::String), 1)])
+		);
+	}
 
 	#[test]
 	fn test_type_check() {
@@ -241,6 +534,8 @@ mod tests {
 		assert_type_check(vec![Token::Uint(0.into()), Token::Bool(false)], vec![ParamType::Uint(32), ParamType::Bool]);
 
 		assert_not_type_check(vec![Token::Uint(0.into())], vec![ParamType::Uint(32), ParamType::Bool]);
+		assert_type_check(vec![Token::Uint(256.into())], vec![ParamType::Uint(8)]);
+		assert_type_check(vec![Token::Int(I256::from(-129).into())], vec![ParamType::Int(8)]);
 		assert_not_type_check(vec![Token::Uint(0.into()), Token::Bool(false)], vec![ParamType::Uint(32)]);
 		assert_not_type_check(
 			vec![Token::Bool(false), Token::Uint(0.into())],
@@ -250,6 +545,8 @@ mod tests {
 		assert_type_check(vec![Token::FixedBytes(vec![0, 0, 0, 0])], vec![ParamType::FixedBytes(4)]);
 		assert_type_check(vec![Token::FixedBytes(vec![0, 0, 0])], vec![ParamType::FixedBytes(4)]);
 		assert_not_type_check(vec![Token::FixedBytes(vec![0, 0, 0, 0])], vec![ParamType::FixedBytes(3)]);
+		assert_type_check(vec![Token::FixedBytes(vec![0; 24])], vec![ParamType::Function]);
+		assert_not_type_check(vec![Token::FixedBytes(vec![0; 20])], vec![ParamType::Function]);
 
 		assert_type_check(
 			vec![Token::Array(vec![Token::Bool(false), Token::Bool(true)])],
@@ -282,6 +579,19 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_type_check_strict() {
+		assert!(Token::Uint(255.into()).type_check_strict(&ParamType::Uint(8)));
+		assert!(!Token::Uint(256.into()).type_check_strict(&ParamType::Uint(8)));
+		assert!(Token::Int(I256::from(-128).into()).type_check_strict(&ParamType::Int(8)));
+		assert!(Token::Int(127.into()).type_check_strict(&ParamType::Int(8)));
+		assert!(!Token::Int(I256::from(-129).into()).type_check_strict(&ParamType::Int(8)));
+		assert!(!Token::Int(128.into()).type_check_strict(&ParamType::Int(8)));
+		assert!(!Token::Array(vec![Token::Uint(256.into())])
+			.type_check_strict(&ParamType::Array(Box::new(ParamType::Uint(8)))));
+		assert!(!Token::Bool(true).type_check_strict(&ParamType::Uint(8)));
+	}
+
 	#[test]
 	fn test_is_dynamic() {
 		assert!(!Token::Address("0000000000000000000000000000000000000000".parse().unwrap()).is_dynamic());
@@ -296,4 +606,57 @@ mod tests {
 		assert!(Token::FixedArray(vec![Token::String("".into())]).is_dynamic());
 		assert!(Token::FixedArray(vec![Token::Array(vec![Token::Bool(false)])]).is_dynamic());
 	}
+
+	#[test]
+	fn test_sort() {
+		let mut tokens =
+			vec![Token::Uint(3.into()), Token::Uint(1.into()), Token::Uint(3.into()), Token::Uint(2.into())];
+		assert!(!Token::is_sorted(&tokens));
+
+		Token::sort(&mut tokens);
+		assert_eq!(
+			tokens,
+			vec![Token::Uint(1.into()), Token::Uint(2.into()), Token::Uint(3.into()), Token::Uint(3.into())]
+		);
+		assert!(Token::is_sorted(&tokens));
+		assert!(!Token::is_strictly_sorted(&tokens));
+
+		Token::sort_dedup(&mut tokens);
+		assert_eq!(tokens, vec![Token::Uint(1.into()), Token::Uint(2.into()), Token::Uint(3.into())]);
+		assert!(Token::is_strictly_sorted(&tokens));
+	}
+
+	#[test]
+	fn test_ordering() {
+		assert!(Token::Int(Uint::MAX) < Token::Int(0.into()));
+		assert!(Token::Uint(0.into()) < Token::Uint(Uint::MAX));
+		assert!(Token::Address(Address::zero()) < Token::Address(Address::repeat_byte(1)));
+		assert!(Token::Bytes(vec![1]) < Token::Bytes(vec![1, 0]));
+		assert!(Token::Array(vec![Token::Bool(false)]) < Token::Array(vec![Token::Bool(true)]));
+		assert!(Token::Address(Address::repeat_byte(0xff)) < Token::Uint(0.into()));
+	}
+
+	#[test]
+	fn test_native_conversions() {
+		assert_eq!(Token::from(5u8), Token::Uint(5.into()));
+		assert_eq!(Token::from(u128::MAX), Token::Uint(u128::MAX.into()));
+		assert_eq!(Token::from(-1i32), Token::Int(Uint::MAX));
+		assert_eq!(Token::from("foo"), Token::String("foo".into()));
+		assert_eq!(Token::from([1u8, 2]), Token::FixedBytes(vec![1, 2]));
+		assert_eq!(Token::from(vec![1u8, 2]), Token::Bytes(vec![1, 2]));
+		assert_eq!(Token::from(Address::zero()), Token::Address(Address::zero()));
+		assert_eq!(Token::from(true), Token::Bool(true));
+
+		assert_eq!(u8::try_from(Token::Uint(255.into())).unwrap(), 255);
+		assert!(u8::try_from(Token::Uint(256.into())).is_err());
+		assert!(u128::try_from(Token::Uint(Uint::MAX)).is_err());
+		assert!(u8::try_from(Token::Int(1.into())).is_err());
+		assert_eq!(i8::try_from(Token::from(-128i64)).unwrap(), -128);
+		assert!(i8::try_from(Token::from(128i64)).is_err());
+		assert_eq!(String::try_from(Token::from("foo")).unwrap(), "foo");
+		assert_eq!(<[u8; 2]>::try_from(Token::from([1u8, 2])).unwrap(), [1, 2]);
+		assert!(<[u8; 3]>::try_from(Token::from([1u8, 2])).is_err());
+		assert_eq!(Vec::<u8>::try_from(Token::from(vec![1u8])).unwrap(), vec![1]);
+		assert!(bool::try_from(Token::from(1u8)).is_err());
+	}
 }
@@ -0,0 +1,317 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions between rust types and tokens.
+
+use core::convert::TryFrom;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{Address, Error, Hash, Token, Uint, I256};
+
+/// Rust type which can be converted to and from a single token.
+pub trait Tokenizable: Sized {
+	/// Converts the token into the rust type.
+	fn from_token(token: Token) -> Result<Self, Error>;
+
+	/// Converts the rust type into a token.
+	fn into_token(self) -> Token;
+}
+
+/// Tokenizable type which can be an element of an array.
+///
+/// Implemented by every `Tokenizable` except `u8`, so that `Vec<u8>` and `[u8; N]`
+/// can map to `bytes` and `bytesN` instead of arrays of `uint8`.
+pub trait TokenizableItem: Tokenizable {}
+
+/// Rust type which can be converted into a list of tokens, e.g. function inputs.
+pub trait Tokenize {
+	/// Converts the rust type into a list of tokens.
+	fn into_tokens(self) -> Vec<Token>;
+}
+
+/// Rust type which can be converted from a list of tokens, e.g. function outputs.
+pub trait Detokenize: Sized {
+	/// Converts the list of tokens into the rust type.
+	fn from_tokens(tokens: Vec<Token>) -> Result<Self, Error>;
+}
+
+impl Tokenizable for Token {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		Ok(token)
+	}
+
+	fn into_token(self) -> Token {
+		self
+	}
+}
+
+impl TokenizableItem for Token {}
+
+macro_rules! impl_tokenizable_via_conversions {
+	($($ty:ty),*) => {
+		$(
+			impl Tokenizable for $ty {
+				fn from_token(token: Token) -> Result<Self, Error> {
+					<$ty>::try_from(token)
+				}
+
+				fn into_token(self) -> Token {
+					self.into()
+				}
+			}
+		)*
+	};
+}
+
+impl_tokenizable_via_conversions!(
+	bool,
+	Address,
+	String,
+	Vec<u8>,
+	u8,
+	u16,
+	u32,
+	u64,
+	u128,
+	usize,
+	i8,
+	i16,
+	i32,
+	i64,
+	i128,
+	isize
+);
+
+impl<const N: usize> Tokenizable for [u8; N] {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		<[u8; N]>::try_from(token)
+	}
+
+	fn into_token(self) -> Token {
+		self.into()
+	}
+}
+
+impl Tokenizable for Uint {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		token.into_uint().ok_or(Error::InvalidData)
+	}
+
+	fn into_token(self) -> Token {
+		Token::Uint(self)
+	}
+}
+
+impl Tokenizable for I256 {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		token.into_i256().ok_or(Error::InvalidData)
+	}
+
+	fn into_token(self) -> Token {
+		self.into()
+	}
+}
+
+impl Tokenizable for Hash {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		match token {
+			Token::FixedBytes(ref bytes) if bytes.len() == 32 => Ok(Hash::from_slice(bytes)),
+			_ => Err(Error::InvalidData),
+		}
+	}
+
+	fn into_token(self) -> Token {
+		Token::FixedBytes(self.as_bytes().to_vec())
+	}
+}
+
+macro_rules! impl_tokenizable_item {
+	($($ty:ty),*) => {
+		$(impl TokenizableItem for $ty {})*
+	};
+}
+
+impl_tokenizable_item!(
+	bool,
+	Address,
+	String,
+	Vec<u8>,
+	u16,
+	u32,
+	u64,
+	u128,
+	usize,
+	i8,
+	i16,
+	i32,
+	i64,
+	i128,
+	isize,
+	Uint,
+	I256,
+	Hash
+);
+
+impl<const N: usize> TokenizableItem for [u8; N] {}
+
+impl<T: TokenizableItem> Tokenizable for Vec<T> {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		token.into_array().ok_or(Error::InvalidData)?.into_iter().map(T::from_token).collect()
+	}
+
+	fn into_token(self) -> Token {
+		Token::Array(self.into_iter().map(T::into_token).collect())
+	}
+}
+
+impl<T: TokenizableItem> TokenizableItem for Vec<T> {}
+
+impl<T: TokenizableItem, const N: usize> Tokenizable for [T; N] {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		let items = token.into_fixed_array().ok_or(Error::InvalidData)?;
+		let items = items.into_iter().map(T::from_token).collect::<Result<Vec<_>, _>>()?;
+		<[T; N]>::try_from(items).map_err(|_| Error::InvalidData)
+	}
+
+	fn into_token(self) -> Token {
+		Token::FixedArray(IntoIterator::into_iter(self).map(T::into_token).collect())
+	}
+}
+
+impl<T: TokenizableItem, const N: usize> TokenizableItem for [T; N] {}
+
+impl Tokenize for Vec<Token> {
+	fn into_tokens(self) -> Vec<Token> {
+		self
+	}
+}
+
+impl Detokenize for Vec<Token> {
+	fn from_tokens(tokens: Vec<Token>) -> Result<Self, Error> {
+		Ok(tokens)
+	}
+}
+
+impl Tokenize for () {
+	fn into_tokens(self) -> Vec<Token> {
+		vec![]
+	}
+}
+
+impl Detokenize for () {
+	fn from_tokens(tokens: Vec<Token>) -> Result<Self, Error> {
+		match tokens.is_empty() {
+			true => Ok(()),
+			false => Err(Error::InvalidData),
+		}
+	}
+}
+
+macro_rules! impl_tuple {
+	($count:expr, $($ty:ident),+) => {
+		impl<$($ty: Tokenizable),+> Tokenizable for ($($ty,)+) {
+			fn from_token(token: Token) -> Result<Self, Error> {
+				match token {
+					Token::Tuple(tokens) => Self::from_tokens(tokens),
+					_ => Err(Error::InvalidData),
+				}
+			}
+
+			fn into_token(self) -> Token {
+				Token::Tuple(self.into_tokens())
+			}
+		}
+
+		impl<$($ty: TokenizableItem),+> TokenizableItem for ($($ty,)+) {}
+
+		impl<$($ty: Tokenizable),+> Tokenize for ($($ty,)+) {
+			#[allow(non_snake_case)]
+			fn into_tokens(self) -> Vec<Token> {
+				let ($($ty,)+) = self;
+				vec![$($ty.into_token()),+]
+			}
+		}
+
+		impl<$($ty: Tokenizable),+> Detokenize for ($($ty,)+) {
+			fn from_tokens(tokens: Vec<Token>) -> Result<Self, Error> {
+				if tokens.len() != $count {
+					return Err(Error::InvalidData);
+				}
+
+				let mut tokens = tokens.into_iter();
+				Ok(($($ty::from_token(tokens.next().ok_or(Error::InvalidData)?)?,)+))
+			}
+		}
+	};
+}
+
+impl_tuple!(1, A);
+impl_tuple!(2, A, B);
+impl_tuple!(3, A, B, C);
+impl_tuple!(4, A, B, C, D);
+impl_tuple!(5, A, B, C, D, E);
+impl_tuple!(6, A, B, C, D, E, F);
+impl_tuple!(7, A, B, C, D, E, F, G);
+impl_tuple!(8, A, B, C, D, E, F, G, H);
+impl_tuple!(9, A, B, C, D, E, F, G, H, I);
+impl_tuple!(10, A, B, C, D, E, F, G, H, I, J);
+impl_tuple!(11, A, B, C, D, E, F, G, H, I, J, K);
+impl_tuple!(12, A, B, C, D, E, F, G, H, I, J, K, L);
+
+#[cfg(test)]
+mod tests {
+	use super::{Detokenize, Tokenizable, Tokenize};
+	use crate::{Address, Token, Uint, I256};
+
+	#[test]
+	fn tokenize_tuple() {
+		let tokens = (Address::zero(), Uint::from(7), vec![1u8, 2], [3u8; 2], vec![true]).into_tokens();
+		assert_eq!(
+			tokens,
+			vec![
+				Token::Address(Address::zero()),
+				Token::Uint(7.into()),
+				Token::Bytes(vec![1, 2]),
+				Token::FixedBytes(vec![3, 3]),
+				Token::Array(vec![Token::Bool(true)]),
+			]
+		);
+
+		let (address, amount, bytes, fixed, flags) =
+			<(Address, Uint, Vec<u8>, [u8; 2], Vec<bool>)>::from_tokens(tokens).unwrap();
+		assert_eq!(address, Address::zero());
+		assert_eq!(amount, 7.into());
+		assert_eq!(bytes, vec![1, 2]);
+		assert_eq!(fixed, [3, 3]);
+		assert_eq!(flags, vec![true]);
+	}
+
+	#[test]
+	fn tokenize_nested() {
+		let value = ([(1u64, I256::from(-1)); 2], vec![vec![String::from("a")]]);
+		let token = value.clone().into_token();
+		assert_eq!(
+			token,
+			Token::Tuple(vec![
+				Token::FixedArray(vec![Token::Tuple(vec![Token::Uint(1.into()), Token::Int(Uint::MAX)]); 2]),
+				Token::Array(vec![Token::Array(vec![Token::String("a".into())])]),
+			])
+		);
+		assert_eq!(<([(u64, I256); 2], Vec<Vec<String>>)>::from_token(token).unwrap(), value);
+	}
+
+	#[test]
+	fn detokenize_mismatch() {
+		assert!(<(bool,)>::from_tokens(vec![]).is_err());
+		assert!(<(bool,)>::from_tokens(vec![Token::Bool(true), Token::Bool(false)]).is_err());
+		assert!(<(bool, u8)>::from_tokens(vec![Token::Bool(true), Token::Bool(false)]).is_err());
+		assert!(<()>::from_tokens(vec![Token::Bool(true)]).is_err());
+		assert!(<[bool; 2]>::from_token(Token::FixedArray(vec![Token::Bool(true)])).is_err());
+	}
+}
@@ -0,0 +1,106 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{errors::TypeMismatch, Error, ParamType, Result, Token};
+
+/// Tokens validated against a list of types once, so that they can be encoded
+/// repeatedly without checking every token again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedTokens {
+	types: Vec<ParamType>,
+	tokens: Vec<Token>,
+}
+
+impl TypedTokens {
+	/// Binds the tokens to the types, failing if they do not match.
+	pub fn new(types: &[ParamType], tokens: Vec<Token>) -> Result<Self> {
+		Token::check_types(&tokens, types)?;
+		Ok(TypedTokens { types: types.to_vec(), tokens })
+	}
+
+	/// Returns the types the tokens were validated against.
+	pub fn types(&self) -> &[ParamType] {
+		&self.types
+	}
+
+	/// Returns the tokens.
+	pub fn tokens(&self) -> &[Token] {
+		&self.tokens
+	}
+
+	/// Returns the tokens, dropping the types.
+	pub fn into_tokens(self) -> Vec<Token> {
+		self.tokens
+	}
+}
+
+/// Tokens which can be encoded as the input of a function.
+pub trait InputTokens {
+	/// Returns the tokens if they match the given types, failing with `Error::TypeMismatch` otherwise.
+	fn checked_tokens(&self, types: &[ParamType]) -> Result<&[Token]>;
+}
+
+impl InputTokens for [Token] {
+	fn checked_tokens(&self, types: &[ParamType]) -> Result<&[Token]> {
+		Token::check_types(self, types)?;
+		Ok(self)
+	}
+}
+
+impl<const N: usize> InputTokens for [Token; N] {
+	fn checked_tokens(&self, types: &[ParamType]) -> Result<&[Token]> {
+		self[..].checked_tokens(types)
+	}
+}
+
+impl InputTokens for Vec<Token> {
+	fn checked_tokens(&self, types: &[ParamType]) -> Result<&[Token]> {
+		self[..].checked_tokens(types)
+	}
+}
+
+impl InputTokens for TypedTokens {
+	/// Compares the types instead of checking the tokens again.
+	fn checked_tokens(&self, types: &[ParamType]) -> Result<&[Token]> {
+		if self.types == types {
+			return Ok(&self.tokens);
+		}
+
+		let mismatches = (0..self.types.len().max(types.len()))
+			.filter(|&index| self.types.get(index) != types.get(index))
+			.map(|index| TypeMismatch {
+				index,
+				expected: types.get(index).cloned(),
+				found: self.types.get(index).cloned(),
+			})
+			.collect();
+		Err(Error::TypeMismatch(mismatches))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{InputTokens, TypedTokens};
+	use crate::{ParamType, Token};
+
+	#[test]
+	fn test_typed_tokens() {
+		let types = [ParamType::Bool, ParamType::Array(Box::new(ParamType::Uint(256)))];
+		let tokens = vec![Token::Bool(true), Token::Array(vec![Token::Uint(1.into())])];
+
+		let typed = TypedTokens::new(&types, tokens.clone()).unwrap();
+		assert_eq!(typed.types(), types);
+		assert_eq!(typed.checked_tokens(&types).unwrap(), &tokens[..]);
+		assert!(typed.checked_tokens(&types[..1]).is_err());
+		assert_eq!(typed.into_tokens(), tokens);
+
+		assert!(TypedTokens::new(&types[..1], tokens).is_err());
+	}
+}
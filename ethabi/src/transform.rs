@@ -0,0 +1,162 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Post-processing of decoded params, e.g. to normalize values centrally in a data pipeline.
+
+use std::{collections::HashMap, fmt};
+
+use crate::{Function, Log, Token};
+
+type Hook = Box<dyn Fn(Token) -> Token + Send + Sync>;
+
+/// Hooks transforming decoded tokens, keyed by event or function name and param name.
+///
+/// ```
+/// use ethabi::{transform, Log, LogParam, Token, Transforms};
+///
+/// let mut transforms = Transforms::new();
+/// transforms.register("Transfer", "value", transform::decimals(6));
+///
+/// let log = Log { params: vec![LogParam { name: "value".to_owned(), value: Token::Uint(1_500_000.into()) }] };
+/// let log = transforms.apply_log("Transfer", log);
+/// assert_eq!(log.params[0].value, Token::String("1.5".to_owned()));
+/// ```
+#[derive(Default)]
+pub struct Transforms {
+	hooks: HashMap<String, HashMap<String, Hook>>,
+}
+
+impl fmt::Debug for Transforms {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let params: Vec<_> =
+			self.hooks.iter().flat_map(|(item, hooks)| hooks.keys().map(move |param| (item, param))).collect();
+		f.debug_struct("Transforms").field("params", &params).finish()
+	}
+}
+
+impl Transforms {
+	/// Creates a set of transformations without hooks.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Registers the hook of the param of the event or function, replacing any previous one.
+	pub fn register<F>(&mut self, item: &str, param: &str, hook: F) -> &mut Self
+	where
+		F: Fn(Token) -> Token + Send + Sync + 'static,
+	{
+		self.hooks.entry(item.to_owned()).or_default().insert(param.to_owned(), Box::new(hook));
+		self
+	}
+
+	/// Applies the hook of the param of the event or function to the token, if any.
+	pub fn apply(&self, item: &str, param: &str, token: Token) -> Token {
+		match self.hooks.get(item).and_then(|hooks| hooks.get(param)) {
+			Some(hook) => hook(token),
+			None => token,
+		}
+	}
+
+	/// Applies the hooks of the event to the params of its decoded log.
+	pub fn apply_log(&self, event: &str, mut log: Log) -> Log {
+		for param in &mut log.params {
+			let value = std::mem::replace(&mut param.value, Token::Bool(false));
+			param.value = self.apply(event, &param.name, value);
+		}
+		log
+	}
+
+	/// Applies the hooks of the function to its decoded outputs, e.g. of `Function::decode_output`.
+	pub fn apply_outputs(&self, function: &Function, tokens: Vec<Token>) -> Vec<Token> {
+		tokens
+			.into_iter()
+			.enumerate()
+			.map(|(index, token)| match function.outputs.get(index) {
+				Some(output) => self.apply(&function.name, &output.name, token),
+				None => token,
+			})
+			.collect()
+	}
+}
+
+/// Hook formatting unsigned integers as decimal strings with the given number of decimals,
+/// e.g. `1500000` with 6 decimals as `"1.5"`.
+///
+/// Other tokens are left as they are.
+pub fn decimals(decimals: usize) -> impl Fn(Token) -> Token + Send + Sync + 'static {
+	move |token| match token {
+		Token::Uint(value) => {
+			let digits = format!("{:0>width$}", value.to_string(), width = decimals + 1);
+			let (integer, fraction) = digits.split_at(digits.len() - decimals);
+			let fraction = fraction.trim_end_matches('0');
+			match fraction.is_empty() {
+				true => Token::String(integer.to_owned()),
+				false => Token::String(format!("{}.{}", integer, fraction)),
+			}
+		}
+		token => token,
+	}
+}
+
+/// Hook mapping unsigned integers, e.g. of Solidity enums, to the labels at their index.
+///
+/// Other tokens and integers without a label are left as they are.
+pub fn labels(labels: &[&str]) -> impl Fn(Token) -> Token + Send + Sync + 'static {
+	let labels: Vec<String> = labels.iter().map(|label| (*label).to_owned()).collect();
+	move |token| match token {
+		Token::Uint(value) if value < labels.len().into() => Token::String(labels[value.low_u64() as usize].clone()),
+		token => token,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{decimals, labels, Transforms};
+	use crate::{Function, Log, LogParam, Token};
+
+	#[test]
+	fn test_decimals() {
+		let hook = decimals(6);
+		assert_eq!(hook(Token::Uint(1_500_000.into())), Token::String("1.5".to_owned()));
+		assert_eq!(hook(Token::Uint(2_000_000.into())), Token::String("2".to_owned()));
+		assert_eq!(hook(Token::Uint(42.into())), Token::String("0.000042".to_owned()));
+		assert_eq!(hook(Token::Uint(0.into())), Token::String("0".to_owned()));
+		assert_eq!(hook(Token::Bool(true)), Token::Bool(true));
+		assert_eq!(decimals(0)(Token::Uint(7.into())), Token::String("7".to_owned()));
+	}
+
+	#[test]
+	fn test_labels() {
+		let hook = labels(&["Pending", "Active", "Closed"]);
+		assert_eq!(hook(Token::Uint(1.into())), Token::String("Active".to_owned()));
+		assert_eq!(hook(Token::Uint(3.into())), Token::Uint(3.into()));
+	}
+
+	#[test]
+	fn test_apply() {
+		let mut transforms = Transforms::new();
+		transforms.register("Transfer", "value", decimals(2)).register("state", "", labels(&["Open", "Closed"]));
+
+		let log = Log {
+			params: vec![
+				LogParam { name: "to".to_owned(), value: Token::Uint(1.into()) },
+				LogParam { name: "value".to_owned(), value: Token::Uint(150.into()) },
+			],
+		};
+		let log = transforms.apply_log("Transfer", log);
+		assert_eq!(log.params[0].value, Token::Uint(1.into()));
+		assert_eq!(log.params[1].value, Token::String("1.5".to_owned()));
+		assert_eq!(transforms.apply("Approval", "value", Token::Uint(150.into())), Token::Uint(150.into()));
+
+		let function = Function::from_signature("function state() view returns (uint8)").unwrap();
+		assert_eq!(
+			transforms.apply_outputs(&function, vec![Token::Uint(1.into())]),
+			vec![Token::String("Closed".to_owned())]
+		);
+	}
+}
@@ -0,0 +1,144 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Statically typed contract function call builder.
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{Bytes, Detokenize, Function, ParamType, Result, Tokenize};
+
+/// Contract function with statically known input and output types.
+///
+/// `Args` and `Out` are usually tuples, e.g. `TypedFunction<(Address, Uint), (bool,)>`.
+pub struct TypedFunction<Args, Out> {
+	function: Function,
+	selector: [u8; 4],
+	inputs: Vec<ParamType>,
+	_types: PhantomData<fn(Args) -> Out>,
+}
+
+impl<Args: Tokenize, Out: Detokenize> TypedFunction<Args, Out> {
+	/// Wraps the function.
+	///
+	/// The types are checked against the function params when encoding and decoding.
+	pub fn new(function: Function) -> Self {
+		let selector = function.selector();
+		let inputs = function.inputs.iter().map(|param| param.kind.clone()).collect();
+		TypedFunction { function, selector, inputs, _types: PhantomData }
+	}
+
+	/// Returns the underlying function.
+	pub fn function(&self) -> &Function {
+		&self.function
+	}
+
+	/// Returns the function selector, computed once when wrapping the function.
+	pub fn selector(&self) -> [u8; 4] {
+		self.selector
+	}
+
+	/// Unwraps the underlying function.
+	pub fn into_inner(self) -> Function {
+		self.function
+	}
+
+	/// Prepares ABI function call with given input params.
+	pub fn encode_input(&self, args: Args) -> Result<Bytes> {
+		let mut result = Vec::new();
+		self.function.encode_input_with_selector(&mut result, self.selector, &self.inputs, &args.into_tokens())?;
+		Ok(result)
+	}
+
+	/// Parses the ABI function output.
+	pub fn decode_output(&self, data: &[u8]) -> Result<Out> {
+		self.function.decode_typed_output(data)
+	}
+
+	/// Parses the ABI function input.
+	pub fn decode_input(&self, data: &[u8]) -> Result<Args>
+	where
+		Args: Detokenize,
+	{
+		Args::from_tokens(self.function.decode_input(data)?)
+	}
+}
+
+impl<Args, Out> Clone for TypedFunction<Args, Out> {
+	fn clone(&self) -> Self {
+		TypedFunction {
+			function: self.function.clone(),
+			selector: self.selector,
+			inputs: self.inputs.clone(),
+			_types: PhantomData,
+		}
+	}
+}
+
+impl<Args, Out> fmt::Debug for TypedFunction<Args, Out> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_tuple("TypedFunction").field(&self.function).finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use hex_literal::hex;
+
+	use super::TypedFunction;
+	use crate::{Address, Function, Param, ParamType, StateMutability, Uint};
+
+	#[test]
+	fn test_typed_function() {
+		#[allow(deprecated)]
+		let function = Function {
+			name: "transfer".to_owned(),
+			inputs: vec![
+				Param { name: "to".to_owned(), kind: ParamType::Address },
+				Param { name: "value".to_owned(), kind: ParamType::Uint(256) },
+			],
+			outputs: vec![Param { name: "".to_owned(), kind: ParamType::Bool }],
+			constant: false,
+			state_mutability: StateMutability::NonPayable,
+		};
+
+		let transfer = TypedFunction::<(Address, Uint), (bool,)>::new(function);
+		let to = Address::repeat_byte(0x11);
+		assert_eq!(transfer.selector(), hex!("a9059cbb"));
+		let encoded = transfer.encode_input((to, 5.into())).unwrap();
+		assert_eq!(
+			encoded,
+			hex!(
+				"
+				a9059cbb
+				0000000000000000000000001111111111111111111111111111111111111111
+				0000000000000000000000000000000000000000000000000000000000000005
+			"
+			)
+			.to_vec()
+		);
+		assert_eq!(transfer.decode_input(&encoded[4..]).unwrap(), (to, 5.into()));
+
+		let output = hex!("0000000000000000000000000000000000000000000000000000000000000001");
+		assert_eq!(transfer.decode_output(&output).unwrap(), (true,));
+	}
+
+	#[test]
+	fn test_typed_function_mismatch() {
+		#[allow(deprecated)]
+		let function = Function {
+			name: "f".to_owned(),
+			inputs: vec![Param { name: "a".to_owned(), kind: ParamType::Bool }],
+			outputs: vec![],
+			constant: false,
+			state_mutability: StateMutability::NonPayable,
+		};
+
+		let f = TypedFunction::<(Uint,), ()>::new(function);
+		assert!(f.encode_input((1.into(),)).is_err());
+	}
+}
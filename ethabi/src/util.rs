@@ -8,17 +8,28 @@
 
 //! Utils used by different modules.
 
-use crate::Word;
+use crate::{ParamType, Word};
 
 /// Converts a u32 to a right aligned array of 32 bytes.
 pub fn pad_u32(value: u32) -> Word {
-	let mut padded = [0u8; 32];
+	let mut padded = Word::default();
 	padded[28..32].copy_from_slice(&value.to_be_bytes());
 	padded
 }
 
+/// Number of 32 byte words a param occupies in the head.
+pub(crate) fn head_slots(kind: &ParamType) -> usize {
+	match *kind {
+		_ if kind.is_dynamic() => 1,
+		ParamType::FixedArray(ref kind, size) => size * head_slots(kind),
+		ParamType::Tuple(ref kinds) => kinds.iter().map(head_slots).sum(),
+		_ => 1,
+	}
+}
+
 // This is a workaround to support non-spec compliant function and event names,
 // see: https://github.com/paritytech/parity/issues/4122
+#[cfg(feature = "std")]
 pub(crate) mod sanitize_name {
 	use serde::{Deserialize, Deserializer};
 
@@ -0,0 +1,140 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions between logs, filters and addresses of this crate and of the `web3` crate.
+//!
+//! `web3` uses another version of `ethereum-types`, so its `H160` and `H256` are other types than `Address`
+//! and `Hash`. Both are foreign to this crate, which thus converts them with functions rather than `From`.
+
+use ::web3::types::{Bytes, Filter, FilterBuilder, Log, H160, H256};
+
+use crate::{Address, Hash, RawLog, TopicFilter};
+
+/// Converts an address into a `web3` address.
+pub fn address_to_web3(address: Address) -> H160 {
+	H160(address.0)
+}
+
+/// Converts a `web3` address into an address.
+pub fn address_from_web3(address: H160) -> Address {
+	Address::from(address.0)
+}
+
+/// Converts a hash, e.g. a topic, into a `web3` hash.
+pub fn hash_to_web3(hash: Hash) -> H256 {
+	H256(hash.0)
+}
+
+/// Converts a `web3` hash, e.g. a topic, into a hash.
+pub fn hash_from_web3(hash: H256) -> Hash {
+	Hash::from(hash.0)
+}
+
+impl From<Log> for RawLog {
+	fn from(log: Log) -> Self {
+		RawLog { topics: log.topics.into_iter().map(hash_from_web3).collect(), data: log.data.0 }
+	}
+}
+
+impl From<&Log> for RawLog {
+	fn from(log: &Log) -> Self {
+		RawLog { topics: log.topics.iter().copied().map(hash_from_web3).collect(), data: log.data.0.clone() }
+	}
+}
+
+impl From<RawLog> for Log {
+	/// Converts the topics and data of a raw log, leaving the address zero and the block and transaction unknown.
+	fn from(log: RawLog) -> Self {
+		Log {
+			address: H160::zero(),
+			topics: log.topics.into_iter().map(hash_to_web3).collect(),
+			data: Bytes(log.data),
+			block_hash: None,
+			block_number: None,
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: None,
+			transaction_log_index: None,
+			log_type: None,
+			removed: None,
+		}
+	}
+}
+
+impl From<TopicFilter> for FilterBuilder {
+	/// Starts a filter of the logs matching the topics, to be restricted further e.g. by address.
+	fn from(filter: TopicFilter) -> Self {
+		let [topic0, topic1, topic2, topic3] =
+			filter.into_topics().map(|hashes| hashes.map(|hashes| hashes.into_iter().map(hash_to_web3).collect()));
+		FilterBuilder::default().topics(topic0, topic1, topic2, topic3)
+	}
+}
+
+impl From<TopicFilter> for Filter {
+	fn from(filter: TopicFilter) -> Self {
+		FilterBuilder::from(filter).build()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ::web3::types::{Bytes, Filter, FilterBuilder, Log, H160, H256};
+
+	use super::{address_from_web3, address_to_web3, hash_from_web3, hash_to_web3};
+	use crate::{Address, Hash, RawLog, Topic, TopicFilter};
+
+	#[test]
+	fn test_address_and_hash() {
+		assert_eq!(address_to_web3(Address::repeat_byte(1)), H160::repeat_byte(1));
+		assert_eq!(address_from_web3(H160::repeat_byte(1)), Address::repeat_byte(1));
+		assert_eq!(hash_to_web3(Hash::repeat_byte(2)), H256::repeat_byte(2));
+		assert_eq!(hash_from_web3(H256::repeat_byte(2)), Hash::repeat_byte(2));
+	}
+
+	#[test]
+	fn test_log_into_raw_log() {
+		let log = Log {
+			address: H160::repeat_byte(1),
+			topics: vec![H256::repeat_byte(2)],
+			data: Bytes(vec![3, 4]),
+			block_hash: None,
+			block_number: None,
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: None,
+			transaction_log_index: None,
+			log_type: None,
+			removed: None,
+		};
+
+		let expected = RawLog { topics: vec![Hash::repeat_byte(2)], data: vec![3, 4] };
+		assert_eq!(RawLog::from(&log), expected);
+		assert_eq!(RawLog::from(log.clone()), expected);
+		assert_eq!(Log::from(expected), Log { address: H160::zero(), ..log });
+	}
+
+	#[test]
+	fn test_topic_filter_into_filter() {
+		let filter = TopicFilter {
+			topic0: Topic::This(Hash::repeat_byte(1)),
+			topic1: Topic::Any,
+			topic2: Topic::OneOf(vec![Hash::repeat_byte(2), Hash::repeat_byte(3)]),
+			topic3: Topic::Any,
+		};
+
+		let expected = FilterBuilder::default()
+			.topics(
+				Some(vec![H256::repeat_byte(1)]),
+				None,
+				Some(vec![H256::repeat_byte(2), H256::repeat_byte(3)]),
+				None,
+			)
+			.build();
+		assert_eq!(Filter::from(filter), expected);
+	}
+}
@@ -0,0 +1,157 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! ABI word.
+
+use core::{
+	convert::TryFrom,
+	ops::{Deref, DerefMut},
+};
+
+use crate::{Address, Error, Hash, Uint, ADDRESS_LEN};
+
+/// ABI word, the 32 byte unit every value is encoded into.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Word(pub [u8; 32]);
+
+impl Deref for Word {
+	type Target = [u8; 32];
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl DerefMut for Word {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl AsRef<[u8]> for Word {
+	fn as_ref(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+impl From<[u8; 32]> for Word {
+	fn from(bytes: [u8; 32]) -> Self {
+		Word(bytes)
+	}
+}
+
+impl From<Word> for [u8; 32] {
+	fn from(word: Word) -> Self {
+		word.0
+	}
+}
+
+impl From<Uint> for Word {
+	fn from(value: Uint) -> Self {
+		Word(value.into())
+	}
+}
+
+impl From<Word> for Uint {
+	fn from(word: Word) -> Self {
+		word.0.into()
+	}
+}
+
+impl From<Hash> for Word {
+	fn from(hash: Hash) -> Self {
+		Word(hash.0)
+	}
+}
+
+impl From<Word> for Hash {
+	fn from(word: Word) -> Self {
+		Hash::from(word.0)
+	}
+}
+
+impl From<Address> for Word {
+	fn from(address: Address) -> Self {
+		let mut word = Word::default();
+		word[32 - ADDRESS_LEN..].copy_from_slice(address.as_ref());
+		word
+	}
+}
+
+impl TryFrom<Word> for Address {
+	type Error = Error;
+
+	/// Fails if any of the leading padding bytes is non-zero.
+	fn try_from(word: Word) -> Result<Self, Self::Error> {
+		if word[..32 - ADDRESS_LEN].iter().any(|x| *x != 0) {
+			return Err(Error::InvalidData);
+		}
+
+		Ok(Address::from_slice(&word[32 - ADDRESS_LEN..]))
+	}
+}
+
+impl From<bool> for Word {
+	fn from(value: bool) -> Self {
+		let mut word = Word::default();
+		word[31] = value as u8;
+		word
+	}
+}
+
+impl TryFrom<Word> for bool {
+	type Error = Error;
+
+	/// Fails for anything other than `0` or `1`.
+	fn try_from(word: Word) -> Result<Self, Self::Error> {
+		if word[..31].iter().any(|x| *x != 0) {
+			return Err(Error::InvalidData);
+		}
+
+		match word[31] {
+			0 => Ok(false),
+			1 => Ok(true),
+			_ => Err(Error::InvalidData),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::convert::TryFrom;
+
+	use super::Word;
+	use crate::{Address, Hash, Uint};
+	use hex_literal::hex;
+
+	#[test]
+	fn word_conversions() {
+		let address = Address::from(hex!("1111111111111111111111111111111111111111"));
+		let word = Word::from(address);
+		assert_eq!(word.0, hex!("0000000000000000000000001111111111111111111111111111111111111111"));
+		assert_eq!(Address::try_from(word).unwrap(), address);
+
+		let uint = Uint::from(0x1337);
+		assert_eq!(Word::from(uint).0, hex!("0000000000000000000000000000000000000000000000000000000000001337"));
+		assert_eq!(Uint::from(Word::from(uint)), uint);
+
+		let hash = Hash::from([0xab; 32]);
+		assert_eq!(Hash::from(Word::from(hash)), hash);
+
+		assert!(bool::try_from(Word::from(true)).unwrap());
+		assert!(!bool::try_from(Word::from(false)).unwrap());
+	}
+
+	#[test]
+	fn word_conversions_reject_dirty_padding() {
+		let word = Word([0xff; 32]);
+		assert!(Address::try_from(word).is_err());
+		assert!(bool::try_from(word).is_err());
+		assert!(bool::try_from(Word::from(Uint::from(2))).is_err());
+	}
+}
@@ -12,10 +12,31 @@ use_contract!(validators, "../res/Validators.abi");
 use_contract!(operations, "../res/Operations.abi");
 use_contract!(urlhint, "../res/urlhint.abi");
 use_contract!(test_rust_keywords, "../res/test_rust_keywords.abi");
+use_contract!(dynamic_arrays, "../res/dynamic_arrays.abi");
+use_contract!(eip20_embedded, "../res/eip20.abi", embed = true);
+use_contract!(callbacks, "../res/callbacks.abi");
+use_contract!(eip20_tested, "../res/eip20.abi", generate_tests = true);
+use_contract!(validators_tested, "../res/Validators.abi", generate_tests = true);
+use_contract!(eip20_text, "../res/eip20.txt.abi", embed = true);
+use_contract!(swap, "../res/swap.txt.abi", defaults = "../res/swap.defaults.json");
+use_contract!(common_events, "../res/common_events.txt.abi");
+use_contract!(
+	eip20_shared,
+	"../res/eip20.abi",
+	common_events = "crate::common_events",
+	common_events_path = "../res/common_events.txt.abi"
+);
+use_contract!(eip20_v2, "../res/eip20_v2.abi");
+use_contract!(eip20_docs, "../res/eip20.abi", docs = true);
+use_contract!(eip20_lenient, "../res/eip20.abi", lenient_bool = "transfer, transferFrom");
+use_contract!(tuples, "../res/tuples.abi", generate_tests = true);
 
 #[cfg(test)]
 mod tests {
-	use crate::{eip20, validators};
+	use crate::{
+		callbacks, common_events, dynamic_arrays, eip20, eip20_docs, eip20_embedded, eip20_lenient, eip20_shared,
+		eip20_text, eip20_v2, swap, tuples, validators,
+	};
 	use ethabi::{Address, Uint};
 	use hex_literal::hex;
 
@@ -97,6 +118,169 @@ mod tests {
 		assert_eq!(expected_string, hex::encode(&encoded_from_string))
 	}
 
+	#[test]
+	fn test_encoding_dynamic_arrays_of_dynamic_types() {
+		use dynamic_arrays::functions;
+
+		let encoded = functions::concat::encode_input(vec!["foo"], vec![vec![0xabu8]]);
+		let expected = concat!(
+			"41b94c2e",
+			"0000000000000000000000000000000000000000000000000000000000000040",
+			"00000000000000000000000000000000000000000000000000000000000000c0",
+			"0000000000000000000000000000000000000000000000000000000000000001",
+			"0000000000000000000000000000000000000000000000000000000000000020",
+			"0000000000000000000000000000000000000000000000000000000000000003",
+			"666f6f0000000000000000000000000000000000000000000000000000000000",
+			"0000000000000000000000000000000000000000000000000000000000000001",
+			"0000000000000000000000000000000000000000000000000000000000000020",
+			"0000000000000000000000000000000000000000000000000000000000000001",
+			"ab00000000000000000000000000000000000000000000000000000000000000",
+		);
+		assert_eq!(hex::encode(&encoded), expected);
+	}
+
+	#[test]
+	fn test_decoding_fixed_array_of_strings_output() {
+		let output = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000080
+			0000000000000000000000000000000000000000000000000000000000000003
+			666f6f0000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000000
+		"
+		);
+
+		let decoded = dynamic_arrays::functions::concat::decode_output(&output).unwrap();
+
+		assert_eq!(decoded, ["foo".to_owned(), "".to_owned()]);
+	}
+
+	#[test]
+	fn test_embedded_abi() {
+		let contract = eip20_embedded::contract();
+
+		assert_eq!(contract, &ethabi::Contract::load(eip20_embedded::ABI_JSON.as_bytes()).unwrap());
+		assert!(std::ptr::eq(contract, eip20_embedded::contract()));
+
+		let tokens = [ethabi::Token::Address([0x11u8; 20].into()), ethabi::Token::Uint(1.into())];
+		let expected = contract.function("transfer").unwrap().encode_input(&tokens).unwrap();
+		assert_eq!(eip20_embedded::functions::transfer::encode_input([0x11u8; 20], 1), expected);
+	}
+
+	#[test]
+	fn test_text_abi() {
+		let json = eip20::abi();
+		let text = eip20_text::contract();
+
+		assert_eq!(text, &ethabi::Contract::load_text(eip20_text::ABI_TEXT.as_bytes()).unwrap());
+		let signatures = |contract: &ethabi::Contract| {
+			let mut signatures: Vec<_> = contract.functions().map(|f| f.signature()).collect();
+			signatures.extend(contract.events().map(|e| format!("{:?}", e.signature())));
+			signatures.sort();
+			signatures
+		};
+		assert_eq!(signatures(text), signatures(json));
+		assert_eq!(
+			eip20_text::functions::transfer::encode_input([0x11u8; 20], 1),
+			eip20::functions::transfer::encode_input([0x11u8; 20], 1)
+		);
+	}
+
+	#[test]
+	fn test_common_events() {
+		fn total(transfers: &[common_events::logs::Transfer]) -> Uint {
+			transfers.iter().map(|transfer| transfer.value).fold(Uint::zero(), |a, b| a + b)
+		}
+
+		let log = ethabi::RawLog {
+			topics: vec![
+				eip20_shared::events::transfer::topic0(),
+				ethabi::Hash::repeat_byte(0),
+				ethabi::Hash::repeat_byte(0),
+			],
+			data: ethabi::encode(&[ethabi::Token::Uint(3.into())]),
+		};
+		let transfer: eip20_shared::logs::Transfer = eip20_shared::events::transfer::parse_log(log).unwrap();
+		assert_eq!(total(&[transfer.clone(), transfer]), 6.into());
+
+		// events which are not declared in the common module are generated as usual
+		assert_eq!(eip20_shared::events_list(), ["Approval", "Transfer"]);
+		assert_eq!(eip20_shared::abi(), eip20::abi());
+	}
+
+	#[test]
+	fn test_read_only() {
+		use eip20::functions::{balance_of, transfer};
+
+		// eip20.abi only has the legacy `constant` field
+		assert_eq!(
+			[balance_of::READ_ONLY, transfer::READ_ONLY, eip20_text::functions::balance_of::READ_ONLY],
+			[true, false, true]
+		);
+	}
+
+	#[test]
+	fn test_encoded_call() {
+		use eip20::functions::balance_of;
+
+		// only accepts calls of `balanceOf`, e.g. not `eip20::functions::total_supply::encode_call()`
+		fn execute(call: ethabi::EncodedCall<balance_of::Decoder>, output: &[u8]) -> Uint {
+			call.decode_output(output).unwrap()
+		}
+
+		let call = balance_of::encode_call([0x11u8; 20]);
+		assert_eq!(call.data(), &balance_of::encode_input([0x11u8; 20])[..]);
+		assert_eq!(execute(call, &ethabi::encode(&[ethabi::Token::Uint(7.into())])), 7.into());
+	}
+
+	#[test]
+	fn test_call_builder_defaults() {
+		use swap::functions::swap;
+
+		let token = [0x11u8; 20];
+		let expected = swap::encode_input(token, 5, Uint::MAX, [0u8; 20]);
+		assert_eq!(swap::builder().token_in(token).amount(5).build().unwrap(), expected);
+		assert_eq!(
+			swap::builder().token_in(token).amount(5).deadline_default().recipient_default().build().unwrap(),
+			expected
+		);
+		assert_eq!(
+			swap::builder().token_in(token).amount(5).deadline(10).build().unwrap(),
+			swap::encode_input(token, 5, 10, [0u8; 20])
+		);
+		assert!(swap::builder().token_in(token).build().is_err());
+	}
+
+	#[test]
+	fn test_abi_reflection() {
+		let expected = ethabi::Contract::load(&include_bytes!("../../res/eip20.abi")[..]).unwrap();
+
+		assert_eq!(eip20::abi(), &expected);
+		assert_eq!(
+			eip20::functions_list(),
+			["allowance", "approve", "balanceOf", "totalSupply", "transfer", "transferFrom"]
+		);
+		assert_eq!(eip20::events_list(), ["Approval", "Transfer"]);
+
+		// custom errors have no bindings, but are part of the ABI
+		let expected = ethabi::Contract::load(&include_bytes!("../../res/eip20_v2.abi")[..]).unwrap();
+		assert_eq!(eip20_v2::abi(), &expected);
+		assert!(eip20_v2::abi().error("InsufficientBalance").is_ok());
+	}
+
+	#[test]
+	fn test_function_type() {
+		let callback = hex!("11111111111111111111111111111111111111118c5be1e5");
+
+		let encoded = callbacks::functions::register::encode_input(callback);
+		assert_eq!(encoded[4..], hex!("11111111111111111111111111111111111111118c5be1e50000000000000000"));
+
+		let decoded = callbacks::functions::register::decode_output(&encoded[4..]).unwrap();
+		assert_eq!(decoded, callback);
+	}
+
 	#[test]
 	fn encoding_input_works() {
 		let expected = "dd62ed3e00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000101010101010101010101010101010101010101".to_owned();
@@ -109,9 +293,126 @@ mod tests {
 		let from: Address = [2u8; 20].into();
 		let to: Address = [3u8; 20].into();
 		let to2: Address = [4u8; 20].into();
-		let _filter = eip20::events::transfer::filter(from, vec![to, to2]);
+		let filter = eip20::events::transfer::filter(from, vec![to, to2]);
+		assert_eq!(filter, eip20::events::transfer::filter(Some(from), [to, to2]));
+		assert_eq!(filter, eip20::events::transfer::filter(from, &[to, to2][..]));
+		assert_eq!(filter.topic2, ethabi::Topic::OneOf(vec![to.into(), to2.into()]));
 		let wildcard_filter = eip20::events::transfer::filter(None, None);
 		let wildcard_filter_sugared = eip20::events::transfer::wildcard_filter();
 		assert_eq!(wildcard_filter, wildcard_filter_sugared);
 	}
+
+	#[test]
+	fn test_derive_tokenizable() {
+		use ethabi::{Token, Tokenizable};
+		use ethabi_derive::Tokenizable;
+
+		// generated code must not depend on the `Result` in scope, e.g. of `anyhow`
+		#[allow(dead_code)]
+		type Result = ();
+
+		#[derive(Debug, PartialEq, Tokenizable)]
+		struct Order {
+			maker: Address,
+			amounts: Vec<Uint>,
+			memo: String,
+		}
+
+		#[derive(Debug, PartialEq, Tokenizable)]
+		struct Batch(Vec<Order>, bool);
+
+		let batch = Batch(vec![Order { maker: [1u8; 20].into(), amounts: vec![5.into()], memo: "hi".into() }], true);
+		let token = Token::Tuple(vec![
+			Token::Array(vec![Token::Tuple(vec![
+				Token::Address([1u8; 20].into()),
+				Token::Array(vec![Token::Uint(5.into())]),
+				Token::String("hi".into()),
+			])]),
+			Token::Bool(true),
+		]);
+
+		assert_eq!(Batch::from_token(token.clone()).unwrap(), batch);
+		assert_eq!(batch.into_token(), token);
+		assert!(Order::from_token(Token::Tuple(vec![Token::Bool(true)])).is_err());
+	}
+
+	#[test]
+	fn test_reducer_with_generated_logs() {
+		use ethabi::{reducer::Reducer, RawLog, Token};
+		use std::collections::HashMap;
+
+		let transfer = |from: Address, to: Address, value: u64| RawLog {
+			topics: vec![eip20::events::transfer::topic0(), from.into(), to.into()],
+			data: ethabi::encode(&[Token::Uint(value.into())]),
+		};
+
+		assert_eq!(eip20::events::transfer::topic0(), eip20::events::transfer::event().signature());
+		let (alice, bob) = (Address::repeat_byte(1), Address::repeat_byte(2));
+		let mut balances = HashMap::<Address, i64>::new();
+		let mut reducer = Reducer::new().on(|balances: &mut HashMap<Address, i64>, log: eip20::logs::Transfer| {
+			*balances.entry(log.from).or_default() -= log.value.low_u64() as i64;
+			*balances.entry(log.to).or_default() += log.value.low_u64() as i64;
+		});
+
+		reducer.run(&mut balances, vec![transfer(alice, bob, 10), transfer(bob, alice, 3)]).unwrap();
+		assert_eq!(balances[&alice], -7);
+		assert_eq!(balances[&bob], 7);
+	}
+
+	#[test]
+	fn test_docs() {
+		assert!(eip20_docs::DOCS.starts_with("# eip20\n"));
+		assert!(eip20_docs::DOCS.contains("`transfer(address,uint256)`, selector `0xa9059cbb`, nonpayable"));
+		assert!(eip20_docs::DOCS.contains("| `_to` | `address` |"));
+		assert!(eip20_docs::DOCS.contains(&format!("topic0 `0x{:x}`", eip20_docs::events::transfer::topic0())));
+	}
+
+	#[test]
+	fn test_lenient_bool() {
+		use ethabi::BoolOutput;
+
+		assert_eq!(eip20_lenient::functions::transfer::decode_output(&[]).unwrap(), BoolOutput::NoReturnData);
+		assert_eq!(
+			eip20_lenient::functions::transfer_from::decode_output(&hex!(
+				"0000000000000000000000000000000000000000000000000000000000000000"
+			))
+			.unwrap(),
+			BoolOutput::ReturnedFalse
+		);
+		assert!(eip20_lenient::functions::approve::decode_output(&[]).is_err());
+	}
+
+	#[test]
+	fn test_tuples() {
+		use ethabi::{encode, RawLog, Token};
+
+		let (maker, recipient, pool) =
+			(Address::repeat_byte(0x11), Address::repeat_byte(0x22), Address::repeat_byte(0x33));
+		let order = (maker, vec![Uint::from(100)], (recipient, Uint::from(30)));
+		let encoded = tuples::functions::swap::encode_input(order, [(pool, true), (pool, false)]);
+		let expected = tuples::functions::swap::function()
+			.encode_input(&[
+				Token::Tuple(vec![
+					Token::Address(maker),
+					Token::Array(vec![Token::Uint(100.into())]),
+					Token::Tuple(vec![Token::Address(recipient), Token::Uint(30.into())]),
+				]),
+				Token::FixedArray(vec![
+					Token::Tuple(vec![Token::Address(pool), Token::Bool(true)]),
+					Token::Tuple(vec![Token::Address(pool), Token::Bool(false)]),
+				]),
+			])
+			.unwrap();
+		assert_eq!(encoded, expected);
+
+		let output = encode(&[Token::Tuple(vec![Token::Bool(false), Token::String("expired".into())])]);
+		assert_eq!(tuples::functions::swap::decode_output(&output).unwrap(), (false, "expired".to_owned()));
+
+		let log = RawLog {
+			topics: vec![tuples::events::swapped::topic0(), maker.into()],
+			data: encode(&[Token::Tuple(vec![Token::Address(recipient), Token::Uint(30.into())])]),
+		};
+		let swapped = tuples::events::swapped::parse_log(log).unwrap();
+		assert_eq!(swapped, tuples::logs::Swapped { maker, fee: (recipient, Uint::from(30)) });
+	}
 }